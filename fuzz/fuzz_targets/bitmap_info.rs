@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::bmp;
+
+//Header of HBITMAP, as written by Getter<Bitmap>
+fuzz_target!(|input: (i32, i32, u16)| {
+    let (width, height, bit_count) = input;
+    //Keep allocations reasonable
+    if (width as i64 * height as i64).abs() > 1 << 20 {
+        return;
+    }
+
+    if let Ok(header) = bmp::InfoHeader::new(width, height, bit_count) {
+        let colors = vec![0; header.colors_len() * 4];
+        let pixels = vec![0; header.image_size().unwrap()];
+        let mut out = Vec::new();
+        bmp::write_file(&mut out, &header, &colors, &pixels).expect("Write bitmap");
+
+        let bitmap = bmp::parse_file(&out).expect("Parse written bitmap");
+        assert_eq!(bitmap.header, header);
+        assert_eq!(bitmap.colors.len(), colors.len());
+        assert_eq!(bitmap.pixels.len(), pixels.len());
+    }
+});
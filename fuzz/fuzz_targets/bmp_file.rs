@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::bmp;
+
+//Bitmap file, as passed to Setter<Bitmap>
+fuzz_target!(|data: &[u8]| {
+    if let Ok(bitmap) = bmp::parse_file(data) {
+        assert!(bitmap.pixels_offset <= data.len());
+        assert!(bitmap.header.image_size().unwrap() <= bitmap.pixels.len());
+        //Profile is located outside of BITMAPINFO, so GDI must not be pointed to it
+        let info = bitmap.bitmap_info();
+        if bitmap.header.size as usize >= bmp::V4_HEADER_SIZE {
+            assert!(!matches!(u32::from_le(info[14]), bmp::PROFILE_LINKED | bmp::PROFILE_EMBEDDED));
+        }
+    }
+});
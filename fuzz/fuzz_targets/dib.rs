@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::bmp;
+
+//CF_DIB/CF_DIBV5 content, as read by Getter<Bitmap>
+fuzz_target!(|data: &[u8]| {
+    let mut out = Vec::new();
+    match bmp::dib_to_file(data, &mut out) {
+        Ok(size) => {
+            assert_eq!(out.len(), size);
+            bmp::parse_file(&out).expect("Converted file must be valid");
+
+            let dib = bmp::parse_dib(data).expect("Parse DIB");
+            let mut v5 = Vec::new();
+            if let Ok(size) = bmp::write_dibv5(&mut v5, &dib) {
+                assert_eq!(v5.len(), size);
+                let written = bmp::parse_dib(&v5).expect("Written DIBV5 must be valid");
+                assert_eq!(written.pixels, dib.pixels);
+                assert_eq!(written.profile, dib.profile);
+            }
+        },
+        Err(_) => assert!(bmp::parse_dib(data).is_err()),
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::dropfiles::{self, Name};
+
+//CF_HDROP content, as read by Getter<FileList>
+fuzz_target!(|data: &[u8]| {
+    if let Ok(files) = dropfiles::parse(data) {
+        let mut len = 0;
+        let mut out = String::new();
+        for name in files.files() {
+            match name {
+                Name::Wide(name) => name.push_lossy(&mut out),
+                Name::Ansi(name) => assert!(!name.contains(&0)),
+            }
+            len += 1;
+        }
+        assert_eq!(len, files.len());
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::emf;
+
+//Enhanced metafile content, as written by Setter for EnhMetafile
+fuzz_target!(|data: &[u8]| {
+    if let Ok(parsed) = emf::parse(data) {
+        assert_eq!(parsed.header.size as usize, parsed.as_bytes().len());
+        assert_eq!(parsed.records().count(), parsed.header.records as usize);
+        assert_eq!(parsed.records().last().expect("EMR_EOF").kind, emf::EMR_EOF);
+        assert_eq!(parsed.has_plus_records(), parsed.records().any(|record| record.kind == emf::EMR_GDICOMMENT && record.data.get(4..8) == Some(b"EMF+")));
+        for record in parsed.plus_records() {
+            assert!(record.data.len() <= data.len());
+        }
+    }
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::{filegroup, Name};
+
+//FileGroupDescriptorW/FileGroupDescriptor content, as read by Getter<VirtualFiles>
+fuzz_target!(|data: &[u8]| {
+    for wide in [false, true] {
+        if let Ok(group) = filegroup::parse(data, wide) {
+            assert_eq!(group.iter().count(), group.len());
+            for descriptor in group.iter() {
+                match descriptor.name {
+                    Name::Wide(name) => assert!(name.len() < filegroup::MAX_PATH),
+                    Name::Ansi(name) => assert!(name.len() < filegroup::MAX_PATH),
+                }
+            }
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::gif;
+
+//GIF stream, as read by Getter<Vec<u8>> for Gif
+fuzz_target!(|data: &[u8]| {
+    if let Ok(parsed) = gif::parse(data) {
+        assert!(parsed.size <= data.len());
+        assert!(parsed.frames > 0);
+        assert_eq!(gif::parse(&data[..parsed.size]), Ok(parsed));
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::idlist;
+
+//Shell IDList Array content, as read by Getter<ShellIdList>
+fuzz_target!(|data: &[u8]| {
+    if let Ok(cida) = idlist::parse(data) {
+        for item in cida.parent().items() {
+            let _ = item.decode();
+        }
+
+        assert_eq!(cida.children().count(), cida.len());
+        for child in cida.children().flatten() {
+            for item in child.items() {
+                assert!(item.as_bytes().len() >= idlist::SIZE_PREFIX);
+                let _ = item.decode();
+            }
+        }
+    }
+});
@@ -0,0 +1,36 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::png;
+
+//PNG content, as read by raw::get_png
+fuzz_target!(|data: &[u8]| {
+    if let Ok(chunks) = png::chunks(data) {
+        let count = chunks.count();
+        let mut iccp = Vec::new();
+        png::write_iccp(&mut iccp, "ICC profile", data).expect("Write iCCP");
+
+        let mut out = Vec::new();
+        if let Ok(size) = png::insert_chunks(data, &[png::ICCP], &iccp, &mut out) {
+            assert_eq!(out.len(), size);
+            let written: Vec<_> = png::chunks(&out).expect("Parse written PNG").collect();
+            assert!(written.len() <= count + 1);
+            assert_eq!(written[1].bytes, &iccp[..]);
+        }
+    }
+
+    if let Ok(header) = png::header(data) {
+        //Same purpose as pixel limit of getter
+        if header.width as u64 * header.height as u64 > 1 << 20 {
+            return;
+        }
+
+        if let Ok(image) = png::read_image(data) {
+            assert_eq!((image.width, image.height), (header.width, header.height));
+
+            let mut out = Vec::new();
+            png::write_image(&mut out, &image).expect("Write image");
+            assert_eq!(png::read_image(&out).expect("Read written image"), image);
+        }
+    }
+});
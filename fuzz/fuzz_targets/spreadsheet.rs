@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::spreadsheet::{self, Workbook};
+
+//XML Spreadsheet content, as read by Getter<Workbook>
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = core::str::from_utf8(data) {
+        let mut workbook = Workbook::default();
+        if spreadsheet::parse(text, &mut workbook).is_ok() {
+            let mut out = String::new();
+            let _ = spreadsheet::write(&mut out, &workbook);
+        }
+    }
+});
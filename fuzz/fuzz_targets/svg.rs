@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::svg;
+
+//SVG document, as read by Getter<String> for Svg
+fuzz_target!(|data: &[u8]| {
+    let mut text = String::new();
+    if let Ok(len) = svg::decode(data, &mut text) {
+        assert_eq!(len, text.len());
+
+        if let Ok(parsed) = svg::parse(&text) {
+            if let Some((width, height)) = parsed.size() {
+                assert!(width >= 0.0 && height >= 0.0);
+            }
+        }
+    }
+});
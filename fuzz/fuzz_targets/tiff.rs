@@ -0,0 +1,41 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::{bmp, tiff, Resolution};
+
+//TIFF content, as read by Getter<Vec<u8>> for Tiff
+fuzz_target!(|data: &[u8]| {
+    if let Ok(parsed) = tiff::parse(data) {
+        assert!(!parsed.is_empty());
+
+        for index in 0..parsed.len() {
+            let page = match parsed.page(index) {
+                Ok(page) => page,
+                Err(_) => continue,
+            };
+            //Same purpose as pixel limit of getter
+            if page.width as u64 * page.height as u64 > 1 << 20 {
+                continue;
+            }
+
+            let mut bitmap = Vec::new();
+            if page.write_bitmap(&mut bitmap).is_ok() {
+                let dib = bmp::parse_file(&bitmap).expect("written bitmap is valid");
+                assert_eq!(dib.header.width as u32, page.width);
+
+                //Resolution of bitmap is saturated to i32
+                let resolution = page.resolution.map(|resolution| {
+                    let (x, y) = resolution.pels_per_meter();
+                    Resolution { x: x as u32, y: y as u32 }
+                });
+                assert_eq!(dib.resolution(), resolution);
+
+                let mut out = Vec::new();
+                tiff::write_bitmaps(&mut out, &[&bitmap]).expect("bitmap is written");
+                let written = tiff::parse(&out).expect("written tiff is valid");
+                assert_eq!(written.len(), 1);
+                assert_eq!(written.page(0).expect("written page is valid").resolution, resolution);
+            }
+        }
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::wave;
+
+//RIFF/WAVE content, as read by Getter<Vec<u8>> for Wave
+fuzz_target!(|data: &[u8]| {
+    if let Ok(wave) = wave::parse(data) {
+        assert_eq!(wave.samples.len() % wave.block_align as usize, 0);
+        assert_eq!(wave.frames() * wave.block_align as usize, wave.samples.len());
+        for (_, value) in wave.info() {
+            assert!(!value.contains(&0));
+        }
+
+        let mut out = Vec::new();
+        if wave::write(&mut out, &wave.spec, wave.samples).is_ok() {
+            assert_eq!(wave::parse(&out).expect("written wave is valid").samples, wave.samples);
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::webp;
+
+//WebP stream, as read by Getter<Vec<u8>> for WebP
+fuzz_target!(|data: &[u8]| {
+    if let Ok(parsed) = webp::parse(data) {
+        assert!(parsed.size <= data.len());
+        assert!(parsed.width > 0 && parsed.height > 0);
+        assert_eq!(webp::parse(&data[..parsed.size]), Ok(parsed));
+    }
+});
@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use clipboard_win::codec::wmf::{self, Picture};
+
+//Metafile content, as written by Setter for MetafilePict or stored in `.wmf` file
+fuzz_target!(|data: &[u8]| {
+    if let Ok(parsed) = wmf::parse(data) {
+        assert_eq!(parsed.header.size, parsed.as_bytes().len());
+        assert_eq!(parsed.records().last().expect("META_EOF").function, wmf::META_EOF);
+        let _ = parsed.window();
+
+        if parsed.placeable.is_some() {
+            let picture = Picture::from_placeable(data);
+            if let Ok(picture) = picture {
+                assert_eq!(picture.metafile, parsed.as_bytes());
+                let mut out = Vec::new();
+                if let Ok(size) = picture.write_placeable(&mut out) {
+                    assert_eq!(size, out.len());
+                    assert_eq!(wmf::parse(&out).expect("Parse exported").as_bytes(), parsed.as_bytes());
+                }
+            }
+        }
+    }
+});
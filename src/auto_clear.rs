@@ -0,0 +1,370 @@
+//!Automatic clearing of clipboard content after timeout.
+//!
+//!Intended for sensitive content (e.g. passwords), that should not stay on clipboard for long.
+//!
+//!Content is cleared only if it has not been replaced since it was scheduled, which is determined
+//!by comparing clipboard sequence number and owner, captured right after content is written.
+//!
+//!## Platform independent scheduling
+//!
+//![AutoClear](struct.AutoClear.html) implements scheduling and comparison over any
+//![Clock](trait.Clock.html) and [Snapshot](struct.Snapshot.html) of clipboard state, leaving actual
+//!clipboard access to the caller.
+//!
+//!## Timer
+//!
+//!On Windows with `std` feature enabled, [schedule](fn.schedule.html) starts background timer,
+//!that empties clipboard once deadline is reached.
+
+use core::time::Duration;
+use core::num::NonZeroU32;
+
+///Monotonic source of time.
+pub trait Clock {
+    ///Returns time elapsed since arbitrary, but fixed, point.
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock> Clock for &C {
+    #[inline(always)]
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Identity of clipboard content.
+pub struct Snapshot {
+    ///Clipboard sequence number.
+    pub seq_num: Option<NonZeroU32>,
+    ///Handle of clipboard owner window, if any.
+    pub owner: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Outcome of polling [AutoClear](struct.AutoClear.html).
+pub enum Action {
+    ///Nothing is scheduled.
+    Idle,
+    ///Deadline is not reached yet, poll again after specified duration.
+    Wait(Duration),
+    ///Content is unchanged and clipboard should be emptied.
+    Clear,
+    ///Content has been replaced since schedule, so it must be left intact.
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pending {
+    expected: Snapshot,
+    deadline: Duration,
+}
+
+impl Pending {
+    #[inline]
+    fn resolve(&self, current: &Snapshot) -> Action {
+        if self.expected == *current {
+            Action::Clear
+        } else {
+            Action::Skip
+        }
+    }
+}
+
+///Auto-clear scheduler.
+///
+///Holds at most one pending clear, which is consumed once resolved.
+pub struct AutoClear<C> {
+    clock: C,
+    pending: Option<Pending>,
+}
+
+impl<C: Clock> AutoClear<C> {
+    #[inline]
+    ///Creates new instance with nothing scheduled.
+    pub const fn new(clock: C) -> Self {
+        Self {
+            clock,
+            pending: None,
+        }
+    }
+
+    ///Schedules clear of content, identified by `expected`, after `timeout`.
+    ///
+    ///Replaces previously scheduled clear, if any.
+    pub fn schedule(&mut self, expected: Snapshot, timeout: Duration) {
+        let deadline = self.clock.now().saturating_add(timeout);
+        self.pending = Some(Pending {
+            expected,
+            deadline,
+        });
+    }
+
+    #[inline]
+    ///Cancels scheduled clear, returning whether it was pending.
+    pub fn cancel(&mut self) -> bool {
+        self.pending.take().is_some()
+    }
+
+    #[inline]
+    ///Returns whether clear is pending.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    ///Moves deadline of scheduled clear to `delay` from now, returning whether it was pending.
+    ///
+    ///Intended to retry clear, when clipboard cannot be accessed at deadline.
+    pub fn postpone(&mut self, delay: Duration) -> bool {
+        let deadline = self.clock.now().saturating_add(delay);
+        match self.pending.as_mut() {
+            Some(pending) => {
+                pending.deadline = deadline;
+                true
+            },
+            None => false,
+        }
+    }
+
+    ///Returns time left until deadline, if clear is pending.
+    pub fn remaining(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        self.pending.as_ref().map(|pending| pending.deadline.saturating_sub(now))
+    }
+
+    ///Polls scheduled clear against `current` state of clipboard.
+    ///
+    ///Once deadline is reached, scheduled clear is consumed and either
+    ///[Clear](enum.Action.html#variant.Clear) or [Skip](enum.Action.html#variant.Skip) is returned.
+    pub fn poll(&mut self, current: &Snapshot) -> Action {
+        let deadline = match self.pending {
+            Some(ref pending) => pending.deadline,
+            None => return Action::Idle,
+        };
+
+        let now = self.clock.now();
+        if now < deadline {
+            return Action::Wait(deadline - now);
+        }
+
+        self.flush(current)
+    }
+
+    ///Resolves scheduled clear against `current` state of clipboard, regardless of deadline.
+    ///
+    ///Intended to be used on exit, when there is no time to wait.
+    pub fn flush(&mut self, current: &Snapshot) -> Action {
+        match self.pending.take() {
+            Some(pending) => pending.resolve(current),
+            None => Action::Idle,
+        }
+    }
+}
+
+#[cfg(windows)]
+///Captures state of current clipboard content.
+///
+///Clipboard doesn't need to be open, but when it is, snapshot cannot change until it is closed.
+pub fn snapshot() -> Snapshot {
+    Snapshot {
+        seq_num: crate::raw::seq_num(),
+        owner: crate::raw::get_owner().map(|owner| owner.as_ptr() as usize),
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+pub use self::timer::{SystemClock, Handle, schedule};
+
+#[cfg(all(windows, feature = "std"))]
+mod timer {
+    use super::{Action, AutoClear, Clock, Snapshot, snapshot};
+    use crate::SysResult;
+    use error_code::SystemError;
+
+    use core::time::Duration;
+    use std::sync::{Arc, Condvar, Mutex, MutexGuard, Once, PoisonError};
+    use std::time::Instant;
+    use std::vec::Vec;
+
+    //Number of attempts to clear, when clipboard cannot be opened at deadline.
+    const RETRY_ATTEMPTS: usize = 10;
+    const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+    ///Clock based on `std::time::Instant`.
+    pub struct SystemClock(Instant);
+
+    impl SystemClock {
+        #[inline]
+        ///Creates clock, starting from current instant.
+        pub fn new() -> Self {
+            Self(Instant::now())
+        }
+    }
+
+    impl Default for SystemClock {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Clock for SystemClock {
+        #[inline(always)]
+        fn now(&self) -> Duration {
+            self.0.elapsed()
+        }
+    }
+
+    struct State {
+        auto: Mutex<AutoClear<SystemClock>>,
+        wake: Condvar,
+        //Last error of clear, that has been given up.
+        error: Mutex<Option<SystemError>>,
+    }
+
+    type Shared = Arc<State>;
+
+    static ON_EXIT: Mutex<Vec<Shared>> = Mutex::new(Vec::new());
+    static ON_EXIT_REGISTER: Once = Once::new();
+
+    extern "C" {
+        fn atexit(cb: extern "C" fn()) -> winapi::ctypes::c_int;
+    }
+
+    extern "C" fn clear_on_exit() {
+        let pending = core::mem::take(&mut *ON_EXIT.lock().unwrap_or_else(PoisonError::into_inner));
+        for shared in pending {
+            let _ = resolve(&shared, |auto, current| auto.flush(current));
+        }
+    }
+
+    //Removes resolved or cancelled clear from those performed on exit.
+    fn unregister(shared: &Shared) {
+        ON_EXIT.lock().unwrap_or_else(PoisonError::into_inner).retain(|other| !Arc::ptr_eq(other, shared));
+    }
+
+    #[inline]
+    fn lock(shared: &Shared) -> MutexGuard<'_, AutoClear<SystemClock>> {
+        shared.auto.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    //Clipboard is opened before scheduler is locked, so that slow open doesn't block other calls,
+    //and kept open while resolving, so content cannot change between check and clear.
+    fn resolve<F: FnOnce(&mut AutoClear<SystemClock>, &Snapshot) -> Action>(shared: &Shared, cb: F) -> SysResult<bool> {
+        let _clip = crate::Clipboard::new_attempts(10)?;
+        let action = cb(&mut lock(shared), &snapshot());
+        match action {
+            Action::Clear => crate::raw::empty().map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    fn run(shared: Shared) {
+        let mut attempts = 0;
+        loop {
+            {
+                let auto = lock(&shared);
+                match auto.remaining() {
+                    None => break,
+                    Some(remaining) if remaining > Duration::from_secs(0) => {
+                        let _ = shared.wake.wait_timeout(auto, remaining).unwrap_or_else(PoisonError::into_inner);
+                        continue;
+                    },
+                    Some(_) => (),
+                }
+            }
+
+            match resolve(&shared, |auto, current| auto.poll(current)) {
+                Ok(_) => break,
+                Err(error) => {
+                    attempts += 1;
+                    let mut auto = lock(&shared);
+                    if attempts >= RETRY_ATTEMPTS {
+                        auto.cancel();
+                        *shared.error.lock().unwrap_or_else(PoisonError::into_inner) = Some(error);
+                        break;
+                    }
+                    auto.postpone(RETRY_DELAY);
+                },
+            }
+        }
+
+        unregister(&shared);
+    }
+
+    ///Handle to scheduled clear.
+    ///
+    ///Dropping handle doesn't cancel timer.
+    pub struct Handle {
+        shared: Shared,
+    }
+
+    impl Handle {
+        ///Cancels scheduled clear, returning whether it was pending.
+        pub fn cancel(&self) -> bool {
+            let result = lock(&self.shared).cancel();
+            self.shared.wake.notify_all();
+            unregister(&self.shared);
+            result
+        }
+
+        #[inline]
+        ///Returns whether clear is pending.
+        pub fn is_pending(&self) -> bool {
+            lock(&self.shared).is_pending()
+        }
+
+        #[inline]
+        ///Returns error, that prevented scheduled clear after every attempt to open clipboard
+        ///failed.
+        pub fn error(&self) -> Option<SystemError> {
+            *self.shared.error.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        ///Clears clipboard without waiting for deadline, if content is unchanged.
+        ///
+        ///Returns whether clipboard has been emptied.
+        pub fn clear_now(&self) -> SysResult<bool> {
+            let result = resolve(&self.shared, |auto, current| auto.flush(current));
+            self.shared.wake.notify_all();
+            if result.is_ok() {
+                unregister(&self.shared);
+            }
+            result
+        }
+
+        ///Requests clear to be performed on process exit, unless it is resolved before.
+        pub fn clear_on_exit(&self) {
+            ON_EXIT_REGISTER.call_once(|| unsafe {
+                atexit(clear_on_exit);
+            });
+
+            let mut on_exit = ON_EXIT.lock().unwrap_or_else(PoisonError::into_inner);
+            if self.is_pending() && !on_exit.iter().any(|other| Arc::ptr_eq(other, &self.shared)) {
+                on_exit.push(self.shared.clone());
+            }
+        }
+    }
+
+    ///Schedules clipboard to be emptied after `timeout`, unless its content is replaced meanwhile.
+    ///
+    ///Must be called right after content is written, as current clipboard state is used to
+    ///identify it. If clipboard cannot be opened at deadline, clear is retried few times, before
+    ///it is given up and reported by [Handle::error](struct.Handle.html#method.error).
+    pub fn schedule(timeout: Duration) -> Handle {
+        let mut auto = AutoClear::new(SystemClock::new());
+        auto.schedule(snapshot(), timeout);
+
+        let shared = Arc::new(State {
+            auto: Mutex::new(auto),
+            wake: Condvar::new(),
+            error: Mutex::new(None),
+        });
+        let timer = shared.clone();
+        std::thread::spawn(move || run(timer));
+
+        Handle {
+            shared,
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//!Platform independent codecs of clipboard formats.
+//!
+//!Codecs operate on raw bytes of clipboard content and never trust it: all offsets and sizes are
+//!validated against provided data, making it safe to parse content placed by other applications.
+
+pub mod bmp;
+pub mod dif;
+pub mod dropfiles;
+pub mod emf;
+pub mod filegroup;
+pub mod gif;
+pub mod grid;
+pub mod idlist;
+pub mod image;
+pub mod jpeg;
+pub mod png;
+pub mod spreadsheet;
+pub mod svg;
+pub mod sylk;
+pub mod table;
+pub mod tiff;
+pub mod url;
+pub mod wave;
+pub mod webp;
+pub mod wmf;
+mod inflate;
+mod riff;
+mod xml;
+
+use core::{char, fmt};
+use alloc::string::String;
+
+use crate::limits::TooLarge;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Codec error.
+pub enum Error {
+    ///Data ends before structure is complete.
+    Truncated,
+    ///Structure contains invalid value.
+    Invalid(&'static str),
+    ///Structure is valid, but its variant is not supported.
+    Unsupported(&'static str),
+    ///Content exceeds [limits](../limits/index.html).
+    TooLarge(TooLarge),
+}
+
+impl From<TooLarge> for Error {
+    #[inline(always)]
+    fn from(error: TooLarge) -> Self {
+        Error::TooLarge(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated => fmt.write_str("Data is truncated"),
+            Error::Invalid(desc) => write!(fmt, "Invalid data: {}", desc),
+            Error::Unsupported(desc) => write!(fmt, "Unsupported data: {}", desc),
+            Error::TooLarge(error) => fmt::Display::fmt(error, fmt),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+}
+
+#[cfg(windows)]
+impl From<Error> for error_code::SystemError {
+    fn from(error: Error) -> Self {
+        use winapi::shared::winerror::{ERROR_INCORRECT_SIZE, ERROR_INVALID_DATA, ERROR_NOT_SUPPORTED};
+
+        match error {
+            Error::Truncated => error_code::SystemError::new(ERROR_INCORRECT_SIZE as _),
+            Error::Invalid(_) => error_code::SystemError::new(ERROR_INVALID_DATA as _),
+            Error::Unsupported(_) => error_code::SystemError::new(ERROR_NOT_SUPPORTED as _),
+            Error::TooLarge(error) => error.into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///Point, as in `POINT`/`POINTL`.
+pub struct Point {
+    ///Horizontal coordinate.
+    pub x: i32,
+    ///Vertical coordinate.
+    pub y: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///Rectangle, as in `RECT`/`RECTL`.
+pub struct Rect {
+    ///Left edge.
+    pub left: i32,
+    ///Top edge.
+    pub top: i32,
+    ///Right edge.
+    pub right: i32,
+    ///Bottom edge.
+    pub bottom: i32,
+}
+
+impl Rect {
+    #[inline]
+    pub(crate) fn parse(data: &[u8], offset: usize) -> Result<Self, Error> {
+        Ok(Self {
+            left: i32_le(data, offset)?,
+            top: i32_le(data, offset + 4)?,
+            right: i32_le(data, offset + 8)?,
+            bottom: i32_le(data, offset + 12)?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Resolution of image in pixels per meter, as used by bitmaps and PNG.
+pub struct Resolution {
+    ///Horizontal resolution.
+    pub x: u32,
+    ///Vertical resolution.
+    pub y: u32,
+}
+
+impl Resolution {
+    #[inline]
+    ///Creates resolution from dots per inch, rounding to nearest pixel per meter.
+    ///
+    ///Resolution, which exceeds `u32::MAX` pixels per meter, is saturated.
+    pub fn from_dpi(x: u32, y: u32) -> Self {
+        let convert = |dpi: u32| match (dpi as u64 * 10000 + 127) / 254 {
+            ppm if ppm <= u32::MAX as u64 => ppm as u32,
+            _ => u32::MAX,
+        };
+        Self {
+            x: convert(x),
+            y: convert(y),
+        }
+    }
+
+    #[inline]
+    ///Returns horizontal and vertical resolution in dots per inch, rounded to nearest integer.
+    pub fn dpi(&self) -> (u32, u32) {
+        let convert = |ppm: u32| ((ppm as u64 * 254 + 5000) / 10000) as u32;
+        (convert(self.x), convert(self.y))
+    }
+
+    #[inline]
+    ///Creates resolution from `biXPelsPerMeter` and `biYPelsPerMeter`, if both are positive.
+    pub fn from_pels_per_meter(x: i32, y: i32) -> Option<Self> {
+        match (x, y) {
+            (1..=i32::MAX, 1..=i32::MAX) => Some(Self {
+                x: x as u32,
+                y: y as u32,
+            }),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    ///Returns resolution as `biXPelsPerMeter` and `biYPelsPerMeter`.
+    pub fn pels_per_meter(&self) -> (i32, i32) {
+        let convert = |ppm: u32| match ppm {
+            0..=0x7FFF_FFFF => ppm as i32,
+            _ => i32::MAX,
+        };
+        (convert(self.x), convert(self.y))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Wide string, stored as little endian bytes without terminator.
+pub struct Wide<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Wide<'a> {
+    #[inline]
+    ///Returns number of UTF-16 code units.
+    pub fn len(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline]
+    ///Returns whether string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    ///Returns iterator over UTF-16 code units, including unpaired surrogates.
+    pub fn units(&self) -> impl Iterator<Item = u16> + Clone + 'a {
+        self.0.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+    }
+
+    ///Appends string to `out`, replacing invalid UTF-16 with `U+FFFD`.
+    pub fn push_lossy(&self, out: &mut String) {
+        out.reserve(self.len());
+        out.extend(char::decode_utf16(self.units()).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///String, stored either in ANSI code page or as wide string.
+pub enum Name<'a> {
+    ///String in ANSI code page, without terminator.
+    Ansi(&'a [u8]),
+    ///Wide string.
+    Wide(Wide<'a>),
+}
+
+#[inline]
+pub(crate) fn slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], Error> {
+    match offset.checked_add(len) {
+        Some(end) if end <= data.len() => Ok(&data[offset..end]),
+        _ => Err(Error::Truncated),
+    }
+}
+
+#[inline]
+pub(crate) fn u16_le(data: &[u8], offset: usize) -> Result<u16, Error> {
+    let bytes = slice(data, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[inline]
+pub(crate) fn u32_le(data: &[u8], offset: usize) -> Result<u32, Error> {
+    let bytes = slice(data, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[inline]
+pub(crate) fn i32_le(data: &[u8], offset: usize) -> Result<i32, Error> {
+    u32_le(data, offset).map(|value| value as i32)
+}
@@ -0,0 +1,700 @@
+//!Bitmap (BMP file and packed DIB) parser and writer.
+//!
+//!Parser validates header variant, dimensions, color table and pixel data against provided
+//!buffer, so that parsed bitmap can be safely handed to GDI.
+
+use super::{Error, Resolution, slice, u16_le, u32_le, i32_le};
+
+use alloc::vec::Vec;
+use alloc::string::String;
+
+///Size of `BITMAPFILEHEADER`.
+pub const FILE_HEADER_SIZE: usize = 14;
+///Size of `BITMAPCOREHEADER`.
+pub const CORE_HEADER_SIZE: usize = 12;
+///Size of `BITMAPINFOHEADER`.
+pub const INFO_HEADER_SIZE: usize = 40;
+///Size of `BITMAPV2INFOHEADER` (info header with RGB masks).
+pub const V2_HEADER_SIZE: usize = 52;
+///Size of `BITMAPV3INFOHEADER` (info header with RGBA masks).
+pub const V3_HEADER_SIZE: usize = 56;
+///Size of `BITMAPV4HEADER`.
+pub const V4_HEADER_SIZE: usize = 108;
+///Size of `BITMAPV5HEADER`.
+pub const V5_HEADER_SIZE: usize = 124;
+
+///Uncompressed pixels.
+pub const BI_RGB: u32 = 0;
+///Run-length encoding with 8 bits per pixel.
+pub const BI_RLE8: u32 = 1;
+///Run-length encoding with 4 bits per pixel.
+pub const BI_RLE4: u32 = 2;
+///Uncompressed pixels with RGB masks.
+pub const BI_BITFIELDS: u32 = 3;
+///JPEG image.
+pub const BI_JPEG: u32 = 4;
+///PNG image.
+pub const BI_PNG: u32 = 5;
+///Uncompressed pixels with RGBA masks.
+pub const BI_ALPHABITFIELDS: u32 = 6;
+
+///Color space with endpoints and gamma specified by header.
+pub const LCS_CALIBRATED_RGB: u32 = 0;
+///sRGB color space.
+pub const LCS_SRGB: u32 = 0x7352_4742;
+///Default color space of system.
+pub const LCS_WINDOWS_COLOR_SPACE: u32 = 0x5769_6E20;
+///Color profile is path of file.
+pub const PROFILE_LINKED: u32 = 0x4C49_4E4B;
+///Color profile is embedded into bitmap.
+pub const PROFILE_EMBEDDED: u32 = 0x4D42_4544;
+///Rendering intent, which maintains saturation.
+pub const LCS_GM_BUSINESS: u32 = 1;
+///Rendering intent, which maintains colorimetric match.
+pub const LCS_GM_GRAPHICS: u32 = 2;
+///Rendering intent, which maintains contrast.
+pub const LCS_GM_IMAGES: u32 = 4;
+///Rendering intent, which maintains white point.
+pub const LCS_GM_ABS_COLORIMETRIC: u32 = 8;
+
+const SIGNATURE: [u8; 2] = *b"BM";
+//Unicode code points of Windows-1252 characters from 0x80 to 0x9F.
+const WINDOWS_1252: [u16; 32] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x008D, 0x017D, 0x008F,
+    0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x009D, 0x017E, 0x0178,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Bitmap header, normalized from any supported variant.
+pub struct InfoHeader {
+    ///Size of original header.
+    pub size: u32,
+    ///Width in pixels.
+    pub width: i32,
+    ///Height in pixels, negative for top-down bitmaps.
+    pub height: i32,
+    ///Number of planes, always 1.
+    pub planes: u16,
+    ///Number of bits per pixel.
+    pub bit_count: u16,
+    ///Compression, one of `BI_*` constants.
+    pub compression: u32,
+    ///Size of pixel data, can be zero for uncompressed bitmaps.
+    pub size_image: u32,
+    ///Horizontal resolution.
+    pub x_pels_per_meter: i32,
+    ///Vertical resolution.
+    pub y_pels_per_meter: i32,
+    ///Number of color table entries, zero means maximum for `bit_count`.
+    pub clr_used: u32,
+    ///Number of important colors.
+    pub clr_important: u32,
+    ///Red, green, blue and alpha masks, zero if not specified.
+    pub masks: [u32; 4],
+}
+
+impl InfoHeader {
+    ///Creates `BITMAPINFOHEADER` for uncompressed bottom-up bitmap.
+    pub fn new(width: i32, height: i32, bit_count: u16) -> Result<Self, Error> {
+        let mut result = Self {
+            size: INFO_HEADER_SIZE as _,
+            width,
+            height,
+            planes: 1,
+            bit_count,
+            compression: BI_RGB,
+            size_image: 0,
+            x_pels_per_meter: 0,
+            y_pels_per_meter: 0,
+            clr_used: 0,
+            clr_important: 0,
+            masks: [0; 4],
+        };
+        result.validate()?;
+        result.size_image = match result.image_size()? {
+            size if size > u32::MAX as usize => return Err(Error::Invalid("bitmap size overflows")),
+            size => size as u32,
+        };
+        if bit_count <= 8 {
+            result.clr_used = 1 << bit_count;
+        }
+        Ok(result)
+    }
+
+    ///Parses header of any supported variant.
+    ///
+    ///For `BITMAPINFOHEADER` masks following header are not parsed.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let size = u32_le(data, 0)?;
+        match size as usize {
+            CORE_HEADER_SIZE => {
+                slice(data, 0, CORE_HEADER_SIZE)?;
+                Ok(Self {
+                    size,
+                    width: u16_le(data, 4)? as i32,
+                    height: u16_le(data, 6)? as i32,
+                    planes: u16_le(data, 8)?,
+                    bit_count: u16_le(data, 10)?,
+                    compression: BI_RGB,
+                    size_image: 0,
+                    x_pels_per_meter: 0,
+                    y_pels_per_meter: 0,
+                    clr_used: 0,
+                    clr_important: 0,
+                    masks: [0; 4],
+                })
+            },
+            INFO_HEADER_SIZE | V2_HEADER_SIZE | V3_HEADER_SIZE | V4_HEADER_SIZE | V5_HEADER_SIZE => {
+                let header = slice(data, 0, size as usize)?;
+                let mut masks = [0; 4];
+                if header.len() >= V2_HEADER_SIZE {
+                    masks[0] = u32_le(header, 40)?;
+                    masks[1] = u32_le(header, 44)?;
+                    masks[2] = u32_le(header, 48)?;
+                }
+                if header.len() >= V3_HEADER_SIZE {
+                    masks[3] = u32_le(header, 52)?;
+                }
+
+                Ok(Self {
+                    size,
+                    width: i32_le(header, 4)?,
+                    height: i32_le(header, 8)?,
+                    planes: u16_le(header, 12)?,
+                    bit_count: u16_le(header, 14)?,
+                    compression: u32_le(header, 16)?,
+                    size_image: u32_le(header, 20)?,
+                    x_pels_per_meter: i32_le(header, 24)?,
+                    y_pels_per_meter: i32_le(header, 28)?,
+                    clr_used: u32_le(header, 32)?,
+                    clr_important: u32_le(header, 36)?,
+                    masks,
+                })
+            },
+            _ => Err(Error::Unsupported("bitmap header size")),
+        }
+    }
+
+    #[inline]
+    ///Returns whether bitmap rows are stored from top to bottom.
+    pub fn is_top_down(&self) -> bool {
+        self.height < 0
+    }
+
+    #[inline]
+    ///Returns absolute height.
+    pub fn abs_height(&self) -> u32 {
+        self.height.unsigned_abs()
+    }
+
+    #[inline]
+    ///Returns whether pixels are compressed (i.e. RLE, JPEG or PNG).
+    pub fn is_compressed(&self) -> bool {
+        !matches!(self.compression, BI_RGB | BI_BITFIELDS | BI_ALPHABITFIELDS)
+    }
+
+    ///Returns number of bytes in single row of uncompressed pixels, including padding.
+    pub fn stride(&self) -> Result<usize, Error> {
+        (self.width as u64).checked_mul(self.bit_count as u64)
+                           .and_then(|bits| bits.checked_add(31))
+                           .map(|bits| bits / 32 * 4)
+                           .filter(|stride| *stride <= usize::MAX as u64)
+                           .map(|stride| stride as usize)
+                           .ok_or(Error::Invalid("bitmap stride overflows"))
+    }
+
+    ///Returns size of pixel data.
+    ///
+    ///For uncompressed bitmaps it is `stride * height`, otherwise `size_image`.
+    pub fn image_size(&self) -> Result<usize, Error> {
+        if self.is_compressed() {
+            return Ok(self.size_image as usize);
+        }
+
+        self.stride()?.checked_mul(self.abs_height() as usize).ok_or(Error::Invalid("bitmap size overflows"))
+    }
+
+    #[inline]
+    ///Returns size of single color table entry.
+    pub fn color_size(&self) -> usize {
+        match self.size as usize {
+            CORE_HEADER_SIZE => 3,
+            _ => 4,
+        }
+    }
+
+    ///Returns number of color table entries.
+    pub fn colors_len(&self) -> usize {
+        match self.clr_used {
+            0 if self.bit_count > 0 && self.bit_count <= 8 => 1 << self.bit_count,
+            0 => 0,
+            clr_used => clr_used as usize,
+        }
+    }
+
+    ///Returns size of masks, following `BITMAPINFOHEADER`.
+    pub fn masks_size(&self) -> usize {
+        match (self.size as usize, self.compression) {
+            (INFO_HEADER_SIZE, BI_BITFIELDS) => 12,
+            (INFO_HEADER_SIZE, BI_ALPHABITFIELDS) => 16,
+            _ => 0,
+        }
+    }
+
+    ///Validates header fields.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.width <= 0 {
+            return Err(Error::Invalid("bitmap width"));
+        }
+        if self.height == 0 || self.height == i32::MIN {
+            return Err(Error::Invalid("bitmap height"));
+        }
+        if self.planes != 1 {
+            return Err(Error::Invalid("bitmap planes"));
+        }
+
+        let bit_count_valid = match self.compression {
+            BI_RGB => match self.bit_count {
+                1 | 4 | 8 | 24 => true,
+                16 | 32 => self.size as usize != CORE_HEADER_SIZE,
+                _ => false,
+            },
+            BI_RLE8 => self.bit_count == 8,
+            BI_RLE4 => self.bit_count == 4,
+            BI_BITFIELDS | BI_ALPHABITFIELDS => self.bit_count == 16 || self.bit_count == 32,
+            BI_JPEG | BI_PNG => true,
+            _ => return Err(Error::Unsupported("bitmap compression")),
+        };
+        if !bit_count_valid {
+            return Err(Error::Invalid("bitmap bit count"));
+        }
+
+        if self.is_compressed() {
+            if self.size_image == 0 {
+                return Err(Error::Invalid("compressed bitmap without size"));
+            }
+            if self.is_top_down() {
+                return Err(Error::Invalid("compressed bitmap cannot be top-down"));
+            }
+        }
+
+        if self.bit_count > 0 && self.bit_count <= 8 && self.clr_used > (1 << self.bit_count) {
+            return Err(Error::Invalid("bitmap color table size"));
+        }
+
+        let image_size = self.image_size()?;
+        if self.size_image != 0 && !self.is_compressed() && (self.size_image as usize) < image_size {
+            return Err(Error::Invalid("bitmap image size"));
+        }
+
+        Ok(())
+    }
+
+    ///Writes header as `BITMAPINFOHEADER`, followed by masks if any.
+    pub fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        out.extend_from_slice(&self.planes.to_le_bytes());
+        out.extend_from_slice(&self.bit_count.to_le_bytes());
+        out.extend_from_slice(&self.compression.to_le_bytes());
+        out.extend_from_slice(&self.size_image.to_le_bytes());
+        out.extend_from_slice(&self.x_pels_per_meter.to_le_bytes());
+        out.extend_from_slice(&self.y_pels_per_meter.to_le_bytes());
+        out.extend_from_slice(&self.clr_used.to_le_bytes());
+        out.extend_from_slice(&self.clr_important.to_le_bytes());
+
+        let masks = match self.compression {
+            BI_BITFIELDS => &self.masks[..3],
+            BI_ALPHABITFIELDS => &self.masks[..],
+            _ => &[],
+        };
+        for mask in masks {
+            out.extend_from_slice(&mask.to_le_bytes());
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Color profile, referenced by `BITMAPV5HEADER`.
+pub enum Profile<'a> {
+    ///Embedded ICC profile.
+    Embedded(&'a [u8]),
+    ///Path of ICC profile file in Windows-1252 code page, without terminator.
+    Linked(&'a [u8]),
+}
+
+impl<'a> Profile<'a> {
+    ///Returns path of linked profile, or `None` for embedded profile.
+    pub fn linked_path(&self) -> Option<String> {
+        match self {
+            Profile::Embedded(_) => None,
+            Profile::Linked(path) => Some(path.iter().map(|byte| match byte {
+                0x80..=0x9F => char::from_u32(WINDOWS_1252[*byte as usize - 0x80] as u32).unwrap_or(char::REPLACEMENT_CHARACTER),
+                byte => *byte as char,
+            }).collect()),
+        }
+    }
+
+    ///Converts profile into owned one.
+    pub fn to_buf(&self) -> ProfileBuf {
+        match self {
+            Profile::Embedded(data) => ProfileBuf::Embedded(data.to_vec()),
+            Profile::Linked(_) => ProfileBuf::Linked(self.linked_path().unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Owned color profile.
+pub enum ProfileBuf {
+    ///Embedded ICC profile.
+    Embedded(Vec<u8>),
+    ///Path of ICC profile file.
+    Linked(String),
+}
+
+#[derive(Clone, Copy, Debug)]
+///Validated bitmap.
+pub struct Dib<'a> {
+    ///Parsed header.
+    pub header: InfoHeader,
+    ///Raw header, including masks following it.
+    pub header_bytes: &'a [u8],
+    ///Raw color table.
+    pub colors: &'a [u8],
+    ///Pixel data of exactly [image_size](struct.InfoHeader.html#method.image_size) bytes.
+    pub pixels: &'a [u8],
+    ///Offset of pixel data within parsed buffer.
+    pub pixels_offset: usize,
+    ///Color profile of `BITMAPV5HEADER`, if any.
+    pub profile: Option<Profile<'a>>,
+}
+
+impl<'a> Dib<'a> {
+    ///Creates `BITMAPINFO` suitable for GDI: header, masks and color table of `RGBQUAD`.
+    ///
+    ///`BITMAPCOREHEADER` is converted to `BITMAPINFOHEADER`, while other variants are preserved.
+    ///Linked or embedded profile is replaced by sRGB, as it is located outside of result.
+    pub fn bitmap_info(&self) -> Vec<u32> {
+        let mut result = Vec::new();
+
+        if self.header.size as usize == CORE_HEADER_SIZE {
+            let mut header = Vec::with_capacity(INFO_HEADER_SIZE + self.header.colors_len() * 4);
+            let mut info = self.header;
+            info.clr_used = self.header.colors_len() as u32;
+            info.write(&mut header);
+            for color in self.colors.chunks_exact(3) {
+                header.extend_from_slice(&[color[0], color[1], color[2], 0]);
+            }
+            push_words(&mut result, &header);
+        } else {
+            let mut header = self.header_bytes.to_vec();
+            if self.header.size as usize >= V4_HEADER_SIZE && matches!(u32_le(&header, 56), Ok(PROFILE_LINKED) | Ok(PROFILE_EMBEDDED)) {
+                header[56..60].copy_from_slice(&LCS_SRGB.to_le_bytes());
+                //Offset and size of profile
+                if let Some(profile) = header.get_mut(112..120) {
+                    profile.copy_from_slice(&[0; 8]);
+                }
+            }
+            push_words(&mut result, &header);
+            push_words(&mut result, self.colors);
+        }
+
+        result
+    }
+
+    #[inline]
+    ///Returns resolution, if specified by header.
+    pub fn resolution(&self) -> Option<Resolution> {
+        Resolution::from_pels_per_meter(self.header.x_pels_per_meter, self.header.y_pels_per_meter)
+    }
+
+    ///Decodes pixels as top-down rows of RGBA with 8 bits per channel, appending them to `out`.
+    ///
+    ///32 bits per pixel without alpha mask is treated as opaque if all alpha values are zero.
+    ///Fails if pixels are compressed.
+    ///
+    ///Returns number of appended bytes.
+    pub fn to_rgba(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        let header = &self.header;
+        if header.is_compressed() {
+            return Err(Error::Unsupported("compressed bitmap"));
+        }
+
+        let width = header.width as usize;
+        let height = header.abs_height() as usize;
+        let size = width.checked_mul(height).and_then(|pixels| pixels.checked_mul(4)).ok_or(Error::Invalid("bitmap size overflows"))?;
+        let stride = header.stride()?;
+        let masks = match (header.compression, header.bit_count) {
+            (BI_RGB, 16) => [0x7C00, 0x03E0, 0x001F, 0],
+            (BI_RGB, 32) => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000],
+            _ => header.masks,
+        };
+
+        let start = out.len();
+        out.reserve(size);
+        for y in 0..height {
+            let row = match header.is_top_down() {
+                true => y,
+                false => height - 1 - y,
+            };
+            let row = &self.pixels[row * stride..][..stride];
+
+            for x in 0..width {
+                match header.bit_count {
+                    1 | 4 | 8 => {
+                        let bits = header.bit_count as usize;
+                        let byte = row[x * bits / 8];
+                        let index = (byte >> (8 - bits - x * bits % 8)) as usize & ((1 << bits) - 1);
+                        let color = match self.colors.get(index * header.color_size()..) {
+                            Some(color) if color.len() >= 3 => color,
+                            _ => {
+                                out.truncate(start);
+                                return Err(Error::Invalid("bitmap color index"));
+                            }
+                        };
+                        out.extend_from_slice(&[color[2], color[1], color[0], 255]);
+                    },
+                    24 => {
+                        let pixel = &row[x * 3..x * 3 + 3];
+                        out.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+                    },
+                    bit_count => {
+                        let value = match bit_count {
+                            16 => u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32,
+                            _ => u32::from_le_bytes([row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]]),
+                        };
+                        let alpha = match masks[3] {
+                            0 => 255,
+                            mask => channel(value, mask),
+                        };
+                        out.extend_from_slice(&[channel(value, masks[0]), channel(value, masks[1]), channel(value, masks[2]), alpha]);
+                    },
+                }
+            }
+        }
+
+        if header.compression == BI_RGB && header.bit_count == 32 && out[start..].chunks_exact(4).all(|pixel| pixel[3] == 0) {
+            for pixel in out[start..].chunks_exact_mut(4) {
+                pixel[3] = 255;
+            }
+        }
+
+        Ok(out.len() - start)
+    }
+}
+
+//Extracts channel selected by `mask`, scaled to 8 bits.
+fn channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let shift = mask.trailing_zeros();
+    let max = (mask >> shift) as u64;
+    (((value & mask) >> shift) as u64 * 255 / max) as u8
+}
+
+fn push_words(out: &mut Vec<u32>, bytes: &[u8]) {
+    debug_assert_eq!(bytes.len() % 4, 0);
+    out.extend(bytes.chunks_exact(4).map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]])));
+}
+
+//Parses header and color table, leaving pixels empty with offset right after color table.
+fn parse_info(data: &[u8], offset: usize) -> Result<Dib<'_>, Error> {
+    let header_data = data.get(offset..).ok_or(Error::Truncated)?;
+    let mut header = InfoHeader::parse(header_data)?;
+
+    let header_size = header.size as usize + header.masks_size();
+    let header_bytes = slice(header_data, 0, header_size)?;
+    match header.masks_size() {
+        12 => {
+            header.masks[0] = u32_le(header_bytes, 40)?;
+            header.masks[1] = u32_le(header_bytes, 44)?;
+            header.masks[2] = u32_le(header_bytes, 48)?;
+        },
+        16 => {
+            header.masks[0] = u32_le(header_bytes, 40)?;
+            header.masks[1] = u32_le(header_bytes, 44)?;
+            header.masks[2] = u32_le(header_bytes, 48)?;
+            header.masks[3] = u32_le(header_bytes, 52)?;
+        },
+        _ => (),
+    }
+
+    header.validate()?;
+
+    let colors_size = header.colors_len().checked_mul(header.color_size()).ok_or(Error::Invalid("bitmap color table size"))?;
+    let colors = slice(header_data, header_size, colors_size)?;
+
+    Ok(Dib {
+        header,
+        header_bytes,
+        colors,
+        pixels: &[],
+        pixels_offset: offset + header_size + colors_size,
+        profile: parse_profile(header_data, header.size as usize)?,
+    })
+}
+
+//Parses profile, located at offset relative to start of `BITMAPV5HEADER`.
+fn parse_profile(header_data: &[u8], header_size: usize) -> Result<Option<Profile<'_>>, Error> {
+    if header_size != V5_HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let kind = u32_le(header_data, 56)?;
+    let offset = u32_le(header_data, 112)? as usize;
+    let size = u32_le(header_data, 116)? as usize;
+    match kind {
+        PROFILE_EMBEDDED | PROFILE_LINKED if size > 0 => (),
+        _ => return Ok(None),
+    }
+    if offset < V5_HEADER_SIZE {
+        return Err(Error::Invalid("bitmap profile overlaps header"));
+    }
+
+    let data = slice(header_data, offset, size)?;
+    Ok(Some(match kind {
+        PROFILE_EMBEDDED => Profile::Embedded(data),
+        _ => Profile::Linked(&data[..data.iter().position(|byte| *byte == 0).unwrap_or(data.len())]),
+    }))
+}
+
+fn parse_pixels<'a>(data: &'a [u8], mut dib: Dib<'a>, pixels_offset: usize) -> Result<Dib<'a>, Error> {
+    dib.pixels = slice(data, pixels_offset, dib.header.image_size()?)?;
+    dib.pixels_offset = pixels_offset;
+    Ok(dib)
+}
+
+///Parses packed DIB (i.e. `CF_DIB` or `CF_DIBV5`), where pixels follow color table.
+pub fn parse_dib(data: &[u8]) -> Result<Dib<'_>, Error> {
+    let dib = parse_info(data, 0)?;
+    let pixels_offset = dib.pixels_offset;
+    parse_pixels(data, dib, pixels_offset)
+}
+
+///Parses BMP file, where pixels are located at offset specified by file header.
+pub fn parse_file(data: &[u8]) -> Result<Dib<'_>, Error> {
+    if slice(data, 0, 2)? != SIGNATURE {
+        return Err(Error::Invalid("bitmap file signature"));
+    }
+
+    let pixels_offset = u32_le(data, 10)? as usize;
+    let dib = parse_info(data, FILE_HEADER_SIZE)?;
+    if pixels_offset < dib.pixels_offset {
+        return Err(Error::Invalid("bitmap pixels overlap header"));
+    }
+
+    parse_pixels(data, dib, pixels_offset)
+}
+
+///Writes `BITMAPFILEHEADER` for file of `size` bytes with pixels at `pixels_offset`.
+pub fn write_file_header(out: &mut Vec<u8>, size: usize, pixels_offset: usize) -> Result<(), Error> {
+    if size > u32::MAX as usize || pixels_offset > size {
+        return Err(Error::Invalid("bitmap file size"));
+    }
+
+    out.extend_from_slice(&SIGNATURE);
+    out.extend_from_slice(&(size as u32).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&(pixels_offset as u32).to_le_bytes());
+    Ok(())
+}
+
+///Writes BMP file, consisting of `header`, color table of `RGBQUAD` and pixels.
+pub fn write_file(out: &mut Vec<u8>, header: &InfoHeader, colors: &[u8], pixels: &[u8]) -> Result<(), Error> {
+    header.validate()?;
+    if colors.len() < header.colors_len() * 4 || pixels.len() < header.image_size()? {
+        return Err(Error::Truncated);
+    }
+
+    let pixels_offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE + header.masks_size() + colors.len();
+    write_file_header(out, pixels_offset + pixels.len(), pixels_offset)?;
+    header.write(out);
+    out.extend_from_slice(colors);
+    out.extend_from_slice(pixels);
+    Ok(())
+}
+
+///Writes packed DIB with `BITMAPV5HEADER` (i.e. `CF_DIBV5`), with color table, pixels and profile
+///of `dib`.
+///
+///Color space is sRGB unless `dib` has profile, which is written after pixels.
+///
+///Returns number of written bytes.
+pub fn write_dibv5(out: &mut Vec<u8>, dib: &Dib<'_>) -> Result<usize, Error> {
+    let header = &dib.header;
+    let mut colors = Vec::with_capacity(header.colors_len() * 4);
+    match header.color_size() {
+        3 => {
+            for color in dib.colors.chunks_exact(3) {
+                colors.extend_from_slice(&[color[0], color[1], color[2], 0]);
+            }
+        },
+        _ => colors.extend_from_slice(dib.colors),
+    }
+
+    let (cs_type, profile) = match dib.profile {
+        Some(Profile::Embedded(data)) => (PROFILE_EMBEDDED, data),
+        Some(Profile::Linked(path)) => (PROFILE_LINKED, path),
+        None => (LCS_SRGB, &[][..]),
+    };
+    //Linked path is null-terminated
+    let profile_size = match cs_type {
+        PROFILE_LINKED => profile.len() + 1,
+        _ => profile.len(),
+    };
+    let profile_offset = V5_HEADER_SIZE + colors.len() + dib.pixels.len();
+    let size = profile_offset.checked_add(profile_size).filter(|size| *size <= u32::MAX as usize).ok_or(Error::Invalid("bitmap size overflows"))?;
+    let profile_offset = match profile_size {
+        0 => 0,
+        _ => profile_offset,
+    };
+
+    let start = out.len();
+    out.reserve(size);
+    out.extend_from_slice(&(V5_HEADER_SIZE as u32).to_le_bytes());
+    out.extend_from_slice(&header.width.to_le_bytes());
+    out.extend_from_slice(&header.height.to_le_bytes());
+    out.extend_from_slice(&header.planes.to_le_bytes());
+    out.extend_from_slice(&header.bit_count.to_le_bytes());
+    out.extend_from_slice(&header.compression.to_le_bytes());
+    out.extend_from_slice(&(dib.pixels.len() as u32).to_le_bytes());
+    out.extend_from_slice(&header.x_pels_per_meter.to_le_bytes());
+    out.extend_from_slice(&header.y_pels_per_meter.to_le_bytes());
+    out.extend_from_slice(&((colors.len() / 4) as u32).to_le_bytes());
+    out.extend_from_slice(&header.clr_important.to_le_bytes());
+    for mask in header.masks.iter() {
+        out.extend_from_slice(&mask.to_le_bytes());
+    }
+    out.extend_from_slice(&cs_type.to_le_bytes());
+    //Endpoints and gamma are used only by LCS_CALIBRATED_RGB
+    out.resize(out.len() + 48, 0);
+    out.extend_from_slice(&LCS_GM_IMAGES.to_le_bytes());
+    out.extend_from_slice(&(profile_offset as u32).to_le_bytes());
+    out.extend_from_slice(&(profile_size as u32).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    out.extend_from_slice(&colors);
+    out.extend_from_slice(dib.pixels);
+    out.extend_from_slice(profile);
+    if cs_type == PROFILE_LINKED {
+        out.push(0);
+    }
+
+    Ok(out.len() - start)
+}
+
+///Converts packed DIB into BMP file, appending it to `out`.
+///
+///Returns number of written bytes.
+pub fn dib_to_file(data: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    let dib = parse_dib(data)?;
+    let size = FILE_HEADER_SIZE.checked_add(data.len()).ok_or(Error::Invalid("bitmap file size"))?;
+
+    write_file_header(out, size, FILE_HEADER_SIZE + dib.pixels_offset)?;
+    out.extend_from_slice(data);
+    Ok(size)
+}
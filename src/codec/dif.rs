@@ -0,0 +1,163 @@
+//!Data Interchange Format (DIF) codec, i.e. `CF_DIF`.
+//!
+//!Content is text consisting of header and data sections.
+//!Header is sequence of items (topic, `vector,value` and string lines), terminated by `DATA` item.
+//!Data is sequence of `type,number` and string line pairs, where each tuple (row) starts with
+//!`-1,0` `BOT` and whole section ends with `-1,0` `EOD`.
+
+use super::Error;
+use super::grid::{Value, date_serial};
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use core::fmt::Write;
+
+fn unquote(line: &str) -> String {
+    let line = line.trim();
+    match line.strip_prefix('"') {
+        Some(text) => text.strip_suffix('"').unwrap_or(text).replace("\"\"", "\""),
+        None => line.into(),
+    }
+}
+
+fn pair(line: &str) -> Result<(i32, &str), Error> {
+    let mut parts = line.splitn(2, ',');
+    match (parts.next().map(|kind| kind.trim().parse::<i32>()), parts.next()) {
+        (Some(Ok(kind)), Some(number)) => Ok((kind, number.trim())),
+        _ => Err(Error::Invalid("data pair")),
+    }
+}
+
+fn parse_data<'a, I: Iterator<Item = &'a str>>(lines: &mut I, out: &mut Vec<Vec<Value>>) -> Result<(), Error> {
+    let mut in_tuple = false;
+
+    loop {
+        let (kind, number) = pair(lines.next().ok_or(Error::Truncated)?)?;
+        let string = lines.next().ok_or(Error::Truncated)?.trim();
+
+        let value = match kind {
+            -1 => match string {
+                "BOT" => {
+                    out.push(Vec::new());
+                    in_tuple = true;
+                    continue;
+                },
+                "EOD" => return Ok(()),
+                _ => return Err(Error::Invalid("special data value")),
+            },
+            0 => match string {
+                "V" => match number.parse::<f64>() {
+                    Ok(number) if number.is_finite() => Value::Number(number),
+                    _ => return Err(Error::Invalid("numeric value")),
+                },
+                "TRUE" => Value::Bool(true),
+                "FALSE" => Value::Bool(false),
+                "NA" => Value::Error("#N/A".into()),
+                "ERROR" => Value::Error("#VALUE!".into()),
+                _ => return Err(Error::Invalid("value indicator")),
+            },
+            1 => match unquote(string) {
+                string if string.is_empty() => Value::Empty,
+                string => Value::Text(string),
+            },
+            _ => return Err(Error::Invalid("data pair type")),
+        };
+
+        match (in_tuple, out.last_mut()) {
+            (true, Some(row)) => row.push(value),
+            _ => return Err(Error::Invalid("value outside of tuple")),
+        }
+    }
+}
+
+///Parses DIF text, appending its tuples to `out` as rows.
+///
+///Empty strings are read as `Value::Empty`, and `NA`/`ERROR` indicators as `#N/A`/`#VALUE!`.
+///Header is validated only for being terminated by `DATA` item.
+///
+///Returns number of appended rows.
+pub fn parse(text: &str, out: &mut Vec<Vec<Value>>) -> Result<usize, Error> {
+    let text = text.trim_end_matches('\0');
+    let mut lines = text.lines();
+
+    //Header items consist of topic, `vector,value` and string.
+    loop {
+        let topic = lines.next().ok_or(Error::Truncated)?.trim();
+        lines.next().ok_or(Error::Truncated)?;
+        lines.next().ok_or(Error::Truncated)?;
+
+        if topic.eq_ignore_ascii_case("DATA") {
+            break;
+        }
+    }
+
+    let start = out.len();
+    match parse_data(&mut lines, out) {
+        Ok(()) => Ok(out.len() - start),
+        Err(error) => {
+            out.truncate(start);
+            Err(error)
+        }
+    }
+}
+
+fn write_text(out: &mut String, text: &str) -> Result<(), Error> {
+    out.push_str("1,0\r\n\"");
+    for ch in text.chars() {
+        match ch {
+            '\r' | '\n' | '\0' => return Err(Error::Invalid("cell contains line break or null character")),
+            '"' => out.push_str("\"\""),
+            ch => out.push(ch),
+        }
+    }
+    out.push_str("\"\r\n");
+    Ok(())
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<(), Error> {
+    match value {
+        Value::Empty => out.push_str("1,0\r\n\"\"\r\n"),
+        Value::Number(number) if number.is_finite() => {
+            let _ = write!(out, "0,{}\r\nV\r\n", number);
+        },
+        Value::Number(_) => return Err(Error::Invalid("number is not finite")),
+        Value::Text(text) => write_text(out, text)?,
+        Value::DateTime(value) => match date_serial(value) {
+            Some(serial) => {
+                let _ = write!(out, "0,{}\r\nV\r\n", serial);
+            },
+            None => return Err(Error::Invalid("date time")),
+        },
+        Value::Bool(true) => out.push_str("0,1\r\nTRUE\r\n"),
+        Value::Bool(false) => out.push_str("0,0\r\nFALSE\r\n"),
+        Value::Error(error) if error == "#N/A" => out.push_str("0,0\r\nNA\r\n"),
+        Value::Error(_) => out.push_str("0,0\r\nERROR\r\n"),
+    }
+
+    Ok(())
+}
+
+///Writes `rows` as DIF text, with each row being tuple.
+///
+///Errors other than `#N/A` are written as generic `ERROR`, and date and time as serial number.
+///Fails if text contains line break or null character, if number is not finite or if date is invalid.
+///
+///Returns number of written bytes.
+pub fn write<R: AsRef<[Value]>>(out: &mut String, rows: &[R]) -> Result<usize, Error> {
+    let start = out.len();
+    let vectors = rows.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+
+    let _ = write!(out, "TABLE\r\n0,1\r\n\"\"\r\nVECTORS\r\n0,{}\r\n\"\"\r\nTUPLES\r\n0,{}\r\n\"\"\r\nDATA\r\n0,0\r\n\"\"\r\n", vectors, rows.len());
+    for row in rows {
+        out.push_str("-1,0\r\nBOT\r\n");
+        for value in row.as_ref() {
+            if let Err(error) = write_value(out, value) {
+                out.truncate(start);
+                return Err(error);
+            }
+        }
+    }
+    out.push_str("-1,0\r\nEOD\r\n");
+
+    Ok(out.len() - start)
+}
@@ -0,0 +1,135 @@
+//!`DROPFILES` (i.e. `CF_HDROP`) parser.
+//!
+//!Block consists of header, followed by list of null terminated file names at offset `pFiles`.
+//!List is terminated by additional null character.
+//!Names are either wide (UTF-16) or ANSI, depending on `fWide` flag.
+
+use super::{Error, slice, u32_le, i32_le};
+
+pub use super::{Point, Name, Wide};
+
+///Size of `DROPFILES` header.
+pub const HEADER_SIZE: usize = 20;
+
+#[derive(Clone, Copy, Debug)]
+///Validated `DROPFILES` block.
+pub struct DropFiles<'a> {
+    ///Drop point, in client coordinates unless `non_client` is set.
+    pub point: Point,
+    ///Whether drop point is in non-client area, i.e. specified in screen coordinates.
+    pub non_client: bool,
+    ///Whether names are wide strings.
+    pub wide: bool,
+    //Names without final terminator.
+    list: &'a [u8],
+    len: usize,
+}
+
+impl<'a> DropFiles<'a> {
+    #[inline]
+    ///Returns number of file names.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    ///Returns whether there are no file names.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    ///Returns iterator over file names.
+    pub fn files(&self) -> Files<'a> {
+        Files {
+            wide: self.wide,
+            list: self.list,
+            len: self.len,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///Iterator over file names of [DropFiles](struct.DropFiles.html).
+pub struct Files<'a> {
+    wide: bool,
+    list: &'a [u8],
+    len: usize,
+}
+
+impl<'a> Iterator for Files<'a> {
+    type Item = Name<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        //List is validated by parser, so every name is terminated.
+        let char_size = if self.wide { 2 } else { 1 };
+        let name_len = name_len(self.list, char_size)?;
+        let name = &self.list[..name_len];
+        self.list = &self.list[name_len + char_size..];
+        self.len -= 1;
+
+        Some(match self.wide {
+            true => Name::Wide(Wide(name)),
+            false => Name::Ansi(name),
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for Files<'_> {
+}
+
+//Returns size in bytes of name at start of `list`, if it is terminated.
+pub(crate) fn name_len(list: &[u8], char_size: usize) -> Option<usize> {
+    list.chunks_exact(char_size).position(|ch| ch.iter().all(|byte| *byte == 0)).map(|pos| pos * char_size)
+}
+
+///Parses `DROPFILES` block.
+///
+///Fails if `pFiles` points outside of `data` or into header, or if any name or list itself is not terminated.
+pub fn parse(data: &[u8]) -> Result<DropFiles<'_>, Error> {
+    slice(data, 0, HEADER_SIZE)?;
+
+    let files_offset = u32_le(data, 0)? as usize;
+    let point = Point {
+        x: i32_le(data, 4)?,
+        y: i32_le(data, 8)?,
+    };
+    let non_client = u32_le(data, 12)? != 0;
+    let wide = u32_le(data, 16)? != 0;
+
+    if files_offset < HEADER_SIZE {
+        return Err(Error::Invalid("file list overlaps header"));
+    }
+    let list = data.get(files_offset..).ok_or(Error::Truncated)?;
+
+    let char_size = if wide { 2 } else { 1 };
+    let mut cursor = 0;
+    let mut len = 0;
+    loop {
+        match name_len(&list[cursor..], char_size) {
+            Some(0) => break,
+            Some(name_len) => {
+                cursor += name_len + char_size;
+                len += 1;
+            },
+            None => return Err(Error::Truncated),
+        }
+    }
+
+    Ok(DropFiles {
+        point,
+        non_client,
+        wide,
+        list: &list[..cursor],
+        len,
+    })
+}
@@ -0,0 +1,283 @@
+//!Enhanced metafile (EMF) codec, i.e. content of `CF_ENHMETAFILE`.
+//!
+//!Content is sequence of records, each starting with type and size, with `EMR_HEADER` first and
+//!`EMR_EOF` last. Dual EMF+ content embeds EMF+ records into `EMR_GDICOMMENT` records, so that
+//!readers without EMF+ support can use plain GDI records.
+
+use super::{Error, Point, Rect, Wide, slice, u16_le, u32_le, i32_le};
+
+///`EMR_HEADER`
+pub const EMR_HEADER: u32 = 1;
+///`EMR_EOF`
+pub const EMR_EOF: u32 = 14;
+///`EMR_GDICOMMENT`
+pub const EMR_GDICOMMENT: u32 = 70;
+///`ENHMETA_SIGNATURE`, i.e. ` EMF`.
+pub const ENHMETA_SIGNATURE: u32 = 0x464D_4520;
+
+//Identifier of comment with EMF+ records, i.e. `EMF+`.
+const EMFPLUS_SIGNATURE: u32 = 0x2B46_4D45;
+//Size of `ENHMETAHEADER` without optional fields.
+const HEADER_SIZE: usize = 88;
+//Size of `ENHMETAHEADER` with `szlMicrometers`.
+const HEADER_MICROMETERS_SIZE: usize = 108;
+//Size of EMF+ record header.
+const EMFPLUS_HEADER_SIZE: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Metafile header, as in `ENHMETAHEADER`.
+pub struct Header {
+    ///Inclusive bounds of picture in device units.
+    pub bounds: Rect,
+    ///Inclusive frame of picture in .01 millimeter units.
+    pub frame: Rect,
+    ///Version.
+    pub version: u32,
+    ///Size of metafile in bytes.
+    pub size: u32,
+    ///Number of records, including header and end of file.
+    pub records: u32,
+    ///Number of GDI objects used by metafile.
+    pub handles: u16,
+    ///Number of palette entries.
+    pub palette_entries: u32,
+    ///Size of reference device in pixels.
+    pub device: Point,
+    ///Size of reference device in millimeters.
+    pub millimeters: Point,
+    ///Size of reference device in micrometers, if specified.
+    pub micrometers: Option<Point>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Record of metafile.
+pub struct Record<'a> {
+    ///Type, one of `EMR_*` values.
+    pub kind: u32,
+    ///Parameters following type and size.
+    pub data: &'a [u8],
+}
+
+#[derive(Clone, Debug)]
+///Iterator over records of [Emf](struct.Emf.html).
+pub struct Records<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (record, rest) = record(self.data).ok()?;
+        self.data = rest;
+        Some(record)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///EMF+ record, embedded into `EMR_GDICOMMENT`.
+pub struct PlusRecord<'a> {
+    ///Type, as in `EmfPlusRecordType`.
+    pub kind: u16,
+    ///Flags, specific to type.
+    pub flags: u16,
+    ///Data of record, excluding padding.
+    pub data: &'a [u8],
+}
+
+#[derive(Clone, Debug)]
+///Iterator over EMF+ records of [Emf](struct.Emf.html).
+pub struct PlusRecords<'a> {
+    records: Records<'a>,
+    comment: &'a [u8],
+}
+
+impl<'a> Iterator for PlusRecords<'a> {
+    type Item = PlusRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.comment.is_empty() {
+            let record = self.records.next()?;
+            self.comment = plus_comment(&record).unwrap_or(&[]);
+        }
+
+        let (record, rest) = plus_record(self.comment).ok()?;
+        self.comment = rest;
+        Some(record)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+///Validated metafile.
+pub struct Emf<'a> {
+    ///Parsed header.
+    pub header: Header,
+    description: Wide<'a>,
+    data: &'a [u8],
+}
+
+impl<'a> Emf<'a> {
+    #[inline]
+    ///Returns content, excluding any trailing bytes beyond size specified by header.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    #[inline]
+    ///Returns description, which consists of application name and picture name separated by null
+    ///character, or empty string if not specified.
+    pub fn description(&self) -> Wide<'a> {
+        self.description
+    }
+
+    #[inline]
+    ///Returns iterator over records, including header and end of file.
+    pub fn records(&self) -> Records<'a> {
+        Records {
+            data: self.data,
+        }
+    }
+
+    #[inline]
+    ///Returns iterator over EMF+ records, embedded into comments.
+    pub fn plus_records(&self) -> PlusRecords<'a> {
+        PlusRecords {
+            records: self.records(),
+            comment: &[],
+        }
+    }
+
+    #[inline]
+    ///Returns whether metafile contains EMF+ records.
+    pub fn has_plus_records(&self) -> bool {
+        self.records().any(|record| plus_comment(&record).is_some())
+    }
+}
+
+//Splits record from rest of data.
+fn record(data: &[u8]) -> Result<(Record<'_>, &[u8]), Error> {
+    let kind = u32_le(data, 0)?;
+    let size = u32_le(data, 4)? as usize;
+    match size % 4 {
+        0 if size >= 8 => (),
+        _ => return Err(Error::Invalid("record size")),
+    }
+    let record = slice(data, 0, size)?;
+    Ok((Record { kind, data: &record[8..] }, &data[size..]))
+}
+
+//Returns EMF+ records within comment, if it is EMF+ comment.
+fn plus_comment<'a>(record: &Record<'a>) -> Option<&'a [u8]> {
+    if record.kind != EMR_GDICOMMENT {
+        return None;
+    }
+
+    let size = u32_le(record.data, 0).ok()? as usize;
+    let comment = slice(record.data, 4, size).ok()?;
+    match u32_le(comment, 0) {
+        Ok(EMFPLUS_SIGNATURE) => Some(&comment[4..]),
+        _ => None,
+    }
+}
+
+//Splits EMF+ record from rest of comment.
+fn plus_record(data: &[u8]) -> Result<(PlusRecord<'_>, &[u8]), Error> {
+    let size = u32_le(data, 4)? as usize;
+    let data_size = u32_le(data, 8)? as usize;
+    match size % 4 {
+        0 if size >= EMFPLUS_HEADER_SIZE && data_size <= size - EMFPLUS_HEADER_SIZE => (),
+        _ => return Err(Error::Invalid("EMF+ record size")),
+    }
+    let record = slice(data, 0, size)?;
+
+    Ok((PlusRecord {
+        kind: u16_le(record, 0)?,
+        flags: u16_le(record, 2)?,
+        data: &record[EMFPLUS_HEADER_SIZE..EMFPLUS_HEADER_SIZE + data_size],
+    }, &data[size..]))
+}
+
+fn point(data: &[u8], offset: usize) -> Result<Point, Error> {
+    Ok(Point {
+        x: i32_le(data, offset)?,
+        y: i32_le(data, offset + 4)?,
+    })
+}
+
+///Parses metafile, validating header and every record.
+///
+///Fails if header is invalid, if number of records differs from header, if records of EMF+
+///comments are malformed or if last record is not `EMR_EOF`. Content beyond size specified
+///by header is ignored, as clipboard memory can be larger than metafile.
+pub fn parse(data: &[u8]) -> Result<Emf<'_>, Error> {
+    let (first, _) = record(data)?;
+    if first.kind != EMR_HEADER {
+        return Err(Error::Invalid("first record is not EMR_HEADER"));
+    }
+    let header_size = first.data.len() + 8;
+    if header_size < HEADER_SIZE {
+        return Err(Error::Invalid("EMF header size"));
+    }
+    let header_data = &data[..header_size];
+    if u32_le(header_data, 40)? != ENHMETA_SIGNATURE {
+        return Err(Error::Invalid("EMF signature"));
+    }
+
+    let header = Header {
+        bounds: Rect::parse(header_data, 8)?,
+        frame: Rect::parse(header_data, 24)?,
+        version: u32_le(header_data, 44)?,
+        size: u32_le(header_data, 48)?,
+        records: u32_le(header_data, 52)?,
+        handles: u16_le(header_data, 56)?,
+        palette_entries: u32_le(header_data, 68)?,
+        device: point(header_data, 72)?,
+        millimeters: point(header_data, 80)?,
+        micrometers: match header_size >= HEADER_MICROMETERS_SIZE {
+            true => Some(point(header_data, 100)?),
+            false => None,
+        },
+    };
+
+    let data = slice(data, 0, header.size as usize)?;
+    let description = match u32_le(header_data, 60)? as usize {
+        0 => Wide(&[]),
+        len => {
+            let offset = u32_le(header_data, 64)? as usize;
+            let description = len.checked_mul(2).and_then(|size| slice(header_data, offset, size).ok());
+            Wide(description.ok_or(Error::Invalid("EMF description"))?)
+        },
+    };
+
+    let mut count = 0u32;
+    let mut last = EMR_HEADER;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (record, after) = record(rest)?;
+        if last == EMR_EOF {
+            return Err(Error::Invalid("record after EMR_EOF"));
+        }
+        if let Some(mut comment) = plus_comment(&record) {
+            while !comment.is_empty() {
+                comment = plus_record(comment)?.1;
+            }
+        }
+
+        count = count.saturating_add(1);
+        last = record.kind;
+        rest = after;
+    }
+
+    if last != EMR_EOF {
+        return Err(Error::Invalid("missing EMR_EOF"));
+    }
+    if count != header.records {
+        return Err(Error::Invalid("record count"));
+    }
+
+    Ok(Emf {
+        header,
+        description,
+        data,
+    })
+}
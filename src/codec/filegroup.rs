@@ -0,0 +1,301 @@
+//!`FILEGROUPDESCRIPTOR` (i.e. `FileGroupDescriptorW` and `FileGroupDescriptor`) parser and writer.
+//!
+//!Group consists of number of items, followed by array of fixed size `FILEDESCRIPTOR` structures,
+//!each describing virtual file, which content is provided separately as `FileContents` by its index.
+
+use super::{Error, Point, Name, Wide, slice, u32_le, i32_le};
+
+use alloc::vec::Vec;
+
+///Maximum length of file name, including terminator.
+pub const MAX_PATH: usize = 260;
+///Size of `FILEDESCRIPTORA`.
+pub const ANSI_DESCRIPTOR_SIZE: usize = 72 + MAX_PATH;
+///Size of `FILEDESCRIPTORW`.
+pub const WIDE_DESCRIPTOR_SIZE: usize = 72 + MAX_PATH * 2;
+
+///`clsid` is valid.
+pub const FD_CLSID: u32 = 0x0000_0001;
+///`size` and `point` are valid.
+pub const FD_SIZEPOINT: u32 = 0x0000_0002;
+///`attributes` is valid.
+pub const FD_ATTRIBUTES: u32 = 0x0000_0004;
+///`creation_time` is valid.
+pub const FD_CREATETIME: u32 = 0x0000_0008;
+///`access_time` is valid.
+pub const FD_ACCESSTIME: u32 = 0x0000_0010;
+///`write_time` is valid.
+pub const FD_WRITESTIME: u32 = 0x0000_0020;
+///`file_size` is valid.
+pub const FD_FILESIZE: u32 = 0x0000_0040;
+///Progress indicator is shown while copying.
+pub const FD_PROGRESSUI: u32 = 0x0000_4000;
+///Treat operation as shortcut.
+pub const FD_LINKUI: u32 = 0x0000_8000;
+///Descriptor is wide.
+pub const FD_UNICODE: u32 = 0x8000_0000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///File information of `FILEDESCRIPTOR`, except its name.
+///
+///Fields are meaningful only when corresponding `FD_*` flag is set.
+pub struct FileInfo {
+    ///Combination of `FD_*` flags.
+    pub flags: u32,
+    ///File class identifier.
+    pub clsid: [u8; 16],
+    ///Width and height of file icon.
+    pub size: Point,
+    ///Screen position of file icon.
+    pub point: Point,
+    ///File attributes, as in `FILE_ATTRIBUTE_*`.
+    pub attributes: u32,
+    ///Creation time, as `FILETIME`.
+    pub creation_time: u64,
+    ///Last access time, as `FILETIME`.
+    pub access_time: u64,
+    ///Last write time, as `FILETIME`.
+    pub write_time: u64,
+    ///Size of file in bytes.
+    pub file_size: u64,
+}
+
+impl FileInfo {
+    #[inline]
+    ///Returns whether all of `flags` are set.
+    pub const fn has(&self, flags: u32) -> bool {
+        self.flags & flags == flags
+    }
+
+    #[inline]
+    ///Sets file size.
+    pub const fn with_file_size(mut self, file_size: u64) -> Self {
+        self.flags |= FD_FILESIZE;
+        self.file_size = file_size;
+        self
+    }
+
+    #[inline]
+    ///Sets file attributes.
+    pub const fn with_attributes(mut self, attributes: u32) -> Self {
+        self.flags |= FD_ATTRIBUTES;
+        self.attributes = attributes;
+        self
+    }
+
+    #[inline]
+    ///Sets last write time.
+    pub const fn with_write_time(mut self, write_time: u64) -> Self {
+        self.flags |= FD_WRITESTIME;
+        self.write_time = write_time;
+        self
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut clsid = [0; 16];
+        clsid.copy_from_slice(slice(data, 4, 16)?);
+
+        Ok(Self {
+            flags: u32_le(data, 0)?,
+            clsid,
+            size: Point {
+                x: i32_le(data, 20)?,
+                y: i32_le(data, 24)?,
+            },
+            point: Point {
+                x: i32_le(data, 28)?,
+                y: i32_le(data, 32)?,
+            },
+            attributes: u32_le(data, 36)?,
+            creation_time: filetime(data, 40)?,
+            access_time: filetime(data, 48)?,
+            write_time: filetime(data, 56)?,
+            file_size: (u32_le(data, 64)? as u64) << 32 | u32_le(data, 68)? as u64,
+        })
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.flags.to_le_bytes());
+        out.extend_from_slice(&self.clsid);
+        for value in &[self.size.x, self.size.y, self.point.x, self.point.y] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&self.attributes.to_le_bytes());
+        for time in &[self.creation_time, self.access_time, self.write_time] {
+            out.extend_from_slice(&(*time as u32).to_le_bytes());
+            out.extend_from_slice(&((*time >> 32) as u32).to_le_bytes());
+        }
+        out.extend_from_slice(&((self.file_size >> 32) as u32).to_le_bytes());
+        out.extend_from_slice(&(self.file_size as u32).to_le_bytes());
+    }
+}
+
+//FILETIME is low part followed by high part
+#[inline]
+fn filetime(data: &[u8], offset: usize) -> Result<u64, Error> {
+    Ok((u32_le(data, offset + 4)? as u64) << 32 | u32_le(data, offset)? as u64)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Parsed `FILEDESCRIPTOR`.
+pub struct Descriptor<'a> {
+    ///File information.
+    pub info: FileInfo,
+    ///File name, which may contain relative path.
+    pub name: Name<'a>,
+}
+
+#[derive(Clone, Copy, Debug)]
+///Validated `FILEGROUPDESCRIPTOR`.
+pub struct FileGroup<'a> {
+    ///Whether descriptors are wide.
+    pub wide: bool,
+    items: &'a [u8],
+    len: usize,
+}
+
+impl<'a> FileGroup<'a> {
+    #[inline]
+    ///Returns number of descriptors.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    ///Returns whether there are no descriptors.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn descriptor_size(&self) -> usize {
+        match self.wide {
+            true => WIDE_DESCRIPTOR_SIZE,
+            false => ANSI_DESCRIPTOR_SIZE,
+        }
+    }
+
+    ///Returns descriptor at `idx`, which is also index of its `FileContents`.
+    pub fn get(&self, idx: usize) -> Option<Descriptor<'a>> {
+        if idx >= self.len {
+            return None;
+        }
+
+        let size = self.descriptor_size();
+        let item = &self.items[idx * size..(idx + 1) * size];
+
+        //Descriptors are validated by parser.
+        let info = FileInfo::parse(item).ok()?;
+        let name = &item[72..];
+        let name = match self.wide {
+            true => Name::Wide(Wide(&name[..name_len(name, 2)?])),
+            false => Name::Ansi(&name[..name_len(name, 1)?]),
+        };
+
+        Some(Descriptor {
+            info,
+            name,
+        })
+    }
+
+    #[inline]
+    ///Returns iterator over descriptors.
+    pub fn iter(&self) -> Descriptors<'a> {
+        Descriptors {
+            group: *self,
+            idx: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///Iterator over descriptors of [FileGroup](struct.FileGroup.html).
+pub struct Descriptors<'a> {
+    group: FileGroup<'a>,
+    idx: usize,
+}
+
+impl<'a> Iterator for Descriptors<'a> {
+    type Item = Descriptor<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.group.get(self.idx)?;
+        self.idx += 1;
+        Some(result)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.group.len - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Descriptors<'_> {
+}
+
+//Returns size in bytes of name, if it is terminated.
+fn name_len(name: &[u8], char_size: usize) -> Option<usize> {
+    name.chunks_exact(char_size).position(|ch| ch.iter().all(|byte| *byte == 0)).map(|pos| pos * char_size)
+}
+
+///Parses `FILEGROUPDESCRIPTORW` if `wide`, or `FILEGROUPDESCRIPTORA` otherwise.
+///
+///Fails if data is shorter than specified number of descriptors, or if any name is not terminated.
+pub fn parse(data: &[u8], wide: bool) -> Result<FileGroup<'_>, Error> {
+    let len = u32_le(data, 0)? as usize;
+    let size = match wide {
+        true => WIDE_DESCRIPTOR_SIZE,
+        false => ANSI_DESCRIPTOR_SIZE,
+    };
+    let items = len.checked_mul(size).ok_or(Error::Truncated).and_then(|items_size| slice(data, 4, items_size))?;
+
+    let char_size = if wide { 2 } else { 1 };
+    for item in items.chunks_exact(size) {
+        if name_len(&item[72..], char_size).is_none() {
+            return Err(Error::Invalid("file name is not terminated"));
+        }
+    }
+
+    Ok(FileGroup {
+        wide,
+        items,
+        len,
+    })
+}
+
+///Writes `FILEGROUPDESCRIPTORW`, consisting of `files` with their names.
+///
+///`FD_UNICODE` flag is set on each descriptor.
+///Fails if name contains null character or doesn't fit into `MAX_PATH`.
+pub fn write_wide<'a, I: IntoIterator<Item = (&'a FileInfo, &'a str)>>(out: &mut Vec<u8>, files: I) -> Result<usize, Error> {
+    let start = out.len();
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    let mut len = 0u32;
+    for (info, name) in files {
+        if name.contains('\0') {
+            out.truncate(start);
+            return Err(Error::Invalid("file name contains null character"));
+        }
+        if name.encode_utf16().count() >= MAX_PATH {
+            out.truncate(start);
+            return Err(Error::Invalid("file name is too long"));
+        }
+
+        let mut info = *info;
+        info.flags |= FD_UNICODE;
+        info.write(out);
+
+        let name_start = out.len();
+        for unit in name.encode_utf16() {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out.resize(name_start + MAX_PATH * 2, 0);
+        len += 1;
+    }
+
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+    Ok(out.len() - start)
+}
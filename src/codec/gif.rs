@@ -0,0 +1,107 @@
+//!GIF stream validator.
+//!
+//!Blocks are walked up to trailer without decompressing image data, so that stream, including
+//!every frame of animation, can be passed through unchanged.
+
+use super::{Error, slice, u16_le};
+
+///Image descriptor introducer.
+pub const IMAGE: u8 = 0x2C;
+///Extension introducer.
+pub const EXTENSION: u8 = 0x21;
+///Trailer, ending stream.
+pub const TRAILER: u8 = 0x3B;
+
+const HEADER_SIZE: usize = 13;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Validated GIF stream.
+pub struct Gif {
+    ///Width of logical screen in pixels.
+    pub width: u16,
+    ///Height of logical screen in pixels.
+    pub height: u16,
+    ///Number of images, i.e. frames of animation.
+    pub frames: usize,
+    ///Size of stream up to and including trailer, excluding trailing data.
+    pub size: usize,
+}
+
+impl Gif {
+    #[inline]
+    ///Returns whether stream consists of multiple frames.
+    pub fn is_animated(&self) -> bool {
+        self.frames > 1
+    }
+}
+
+#[inline]
+//Returns size of color table, if present according to `flags`.
+fn color_table_size(flags: u8) -> usize {
+    match flags & 0x80 {
+        0 => 0,
+        _ => 3 << ((flags & 7) + 1),
+    }
+}
+
+//Skips data sub-blocks, returning offset after block terminator.
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Result<usize, Error> {
+    loop {
+        match slice(data, pos, 1)?[0] {
+            0 => return Ok(pos + 1),
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+///Validates GIF stream, walking its blocks up to trailer.
+///
+///Fails if stream has no image or ends before trailer.
+pub fn parse(data: &[u8]) -> Result<Gif, Error> {
+    match slice(data, 0, 6)? {
+        b"GIF87a" | b"GIF89a" => (),
+        _ => return Err(Error::Invalid("GIF signature")),
+    }
+
+    let header = slice(data, 0, HEADER_SIZE)?;
+    let width = u16_le(header, 6)?;
+    let height = u16_le(header, 8)?;
+    if width == 0 || height == 0 {
+        return Err(Error::Invalid("GIF dimensions"));
+    }
+
+    let mut frames = 0;
+    let mut pos = HEADER_SIZE + color_table_size(header[10]);
+    loop {
+        match slice(data, pos, 1)?[0] {
+            IMAGE => {
+                let descriptor = slice(data, pos + 1, 9)?;
+                if u16_le(descriptor, 4)? == 0 || u16_le(descriptor, 6)? == 0 {
+                    return Err(Error::Invalid("GIF image dimensions"));
+                }
+                pos += 10 + color_table_size(descriptor[8]);
+
+                match slice(data, pos, 1)?[0] {
+                    2..=8 => (),
+                    _ => return Err(Error::Invalid("GIF code size")),
+                }
+                pos = skip_sub_blocks(data, pos + 1)?;
+                frames += 1;
+            },
+            EXTENSION => {
+                slice(data, pos + 1, 1)?;
+                pos = skip_sub_blocks(data, pos + 2)?;
+            },
+            TRAILER => match frames {
+                0 => return Err(Error::Invalid("GIF without image")),
+                _ => return Ok(Gif {
+                    width,
+                    height,
+                    frames,
+                    size: pos + 1,
+                }),
+            },
+            _ => return Err(Error::Invalid("GIF block")),
+        }
+    }
+}
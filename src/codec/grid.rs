@@ -0,0 +1,294 @@
+//!Typed cell grid, shared by spreadsheet interchange codecs.
+//!
+//!Grid is sparse and addressed by zero based row and column, so that content with few distant
+//!cells doesn't require proportional memory.
+
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::string::String;
+
+#[derive(Clone, Debug, PartialEq)]
+///Cell value.
+pub enum Value {
+    ///No value.
+    Empty,
+    ///Number.
+    Number(f64),
+    ///Text.
+    Text(String),
+    ///Logical value.
+    Bool(bool),
+    ///Date and time in ISO 8601 format, as in `1999-12-31T23:59:59.000`.
+    ///
+    ///Formats without date type store it as [serial number](fn.date_serial.html).
+    DateTime(String),
+    ///Error, such as `#N/A` or `#DIV/0!`.
+    Error(String),
+}
+
+impl Default for Value {
+    #[inline(always)]
+    fn default() -> Self {
+        Value::Empty
+    }
+}
+
+//Days since 1970-01-01 of proleptic Gregorian calendar date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn number<T: core::str::FromStr>(text: Option<&str>, len: usize) -> Option<T> {
+    match text {
+        Some(text) if text.len() == len && text.bytes().all(|byte| byte.is_ascii_digit()) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+///Converts ISO 8601 date and optional time (`YYYY-MM-DD[THH:MM[:SS[.fff]]]`) into spreadsheet
+///serial number, i.e. days since `1899-12-30`.
+///
+///Returns `None` if value is not valid date.
+pub fn date_serial(value: &str) -> Option<f64> {
+    let mut parts = value.splitn(2, 'T');
+    let mut date = parts.next()?.splitn(3, '-');
+    let year: i64 = number(date.next(), 4)?;
+    let month: u32 = number(date.next(), 2)?;
+    let day: u32 = number(date.next(), 2)?;
+
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if day == 0 || day > days_in_month {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    if let Some(time) = parts.next() {
+        let mut time = time.splitn(3, ':');
+        let hour: u32 = number(time.next(), 2)?;
+        let minute: u32 = number(time.next(), 2)?;
+        let second = match time.next() {
+            Some(second) => {
+                let (whole, fraction) = second.split_at(second.find('.').unwrap_or(second.len()));
+                let whole: u32 = number(Some(whole), 2)?;
+                let fraction = match fraction {
+                    "" => 0.0,
+                    fraction if fraction.len() > 1 && fraction[1..].bytes().all(|byte| byte.is_ascii_digit()) => fraction.parse::<f64>().ok()?,
+                    _ => return None,
+                };
+                whole as f64 + fraction
+            },
+            None => 0.0,
+        };
+
+        if hour > 23 || minute > 59 || second >= 60.0 {
+            return None;
+        }
+        seconds = (hour * 3600 + minute * 60) as f64 + second;
+    }
+
+    let days = days_from_civil(year, month, day) - days_from_civil(1899, 12, 30);
+    Some(days as f64 + seconds / 86400.0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Number format of cell.
+pub enum NumberFormat {
+    ///Default format of sheet.
+    Default,
+    ///General format.
+    General,
+    ///Fixed number of decimal digits.
+    Fixed,
+    ///Scientific notation.
+    Exponent,
+    ///Currency.
+    Currency,
+    ///Percentage.
+    Percent,
+}
+
+impl Default for NumberFormat {
+    #[inline(always)]
+    fn default() -> Self {
+        NumberFormat::Default
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Horizontal alignment of cell.
+pub enum Align {
+    ///Default alignment of sheet.
+    Default,
+    ///General alignment, i.e. text to the left and numbers to the right.
+    General,
+    ///Left.
+    Left,
+    ///Center.
+    Center,
+    ///Right.
+    Right,
+    ///Content is repeated to fill cell.
+    Fill,
+}
+
+impl Default for Align {
+    #[inline(always)]
+    fn default() -> Self {
+        Align::Default
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///Basic formatting of cell.
+pub struct Format {
+    ///Number format.
+    pub number: NumberFormat,
+    ///Number of decimal digits.
+    pub digits: u8,
+    ///Horizontal alignment.
+    pub align: Align,
+    ///Bold font.
+    pub bold: bool,
+    ///Italic font.
+    pub italic: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+///Cell of grid.
+pub struct Cell {
+    ///Value, which is last computed result if cell has formula.
+    pub value: Value,
+    ///Formula, in notation of originating format.
+    pub formula: Option<String>,
+    ///Formatting, if specified.
+    pub format: Option<Format>,
+}
+
+impl Cell {
+    #[inline]
+    ///Creates cell with `value`.
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            formula: None,
+            format: None,
+        }
+    }
+
+    #[inline]
+    ///Returns whether cell has neither value, formula nor formatting.
+    pub fn is_empty(&self) -> bool {
+        self.value == Value::Empty && self.formula.is_none() && self.format.is_none()
+    }
+}
+
+impl From<Value> for Cell {
+    #[inline(always)]
+    fn from(value: Value) -> Self {
+        Self::new(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+///Sparse grid of cells.
+pub struct Grid {
+    cells: BTreeMap<(u32, u32), Cell>,
+}
+
+impl Grid {
+    #[inline]
+    ///Creates empty grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    ///Returns number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[inline]
+    ///Returns whether grid has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    ///Returns number of rows, up to last one with cell.
+    pub fn rows(&self) -> u32 {
+        self.cells.keys().next_back().map(|(row, _)| row.saturating_add(1)).unwrap_or(0)
+    }
+
+    ///Returns number of columns, up to last one with cell.
+    pub fn columns(&self) -> u32 {
+        self.cells.keys().map(|(_, column)| column.saturating_add(1)).max().unwrap_or(0)
+    }
+
+    #[inline]
+    ///Returns cell at `row` and `column`.
+    pub fn get(&self, row: u32, column: u32) -> Option<&Cell> {
+        self.cells.get(&(row, column))
+    }
+
+    #[inline]
+    ///Returns cell at `row` and `column`, inserting empty one if necessary.
+    pub fn get_mut(&mut self, row: u32, column: u32) -> &mut Cell {
+        self.cells.entry((row, column)).or_default()
+    }
+
+    #[inline]
+    ///Sets cell at `row` and `column`, returning previous one.
+    pub fn set<T: Into<Cell>>(&mut self, row: u32, column: u32, cell: T) -> Option<Cell> {
+        self.cells.insert((row, column), cell.into())
+    }
+
+    #[inline]
+    ///Returns iterator over `((row, column), cell)` in row-major order.
+    pub fn iter(&self) -> btree_map::Iter<'_, (u32, u32), Cell> {
+        self.cells.iter()
+    }
+
+    #[inline]
+    ///Removes all cells.
+    pub fn clear(&mut self) {
+        self.cells.clear()
+    }
+}
+
+impl Extend<((u32, u32), Cell)> for Grid {
+    #[inline]
+    fn extend<I: IntoIterator<Item = ((u32, u32), Cell)>>(&mut self, cells: I) {
+        self.cells.extend(cells)
+    }
+}
+
+impl IntoIterator for Grid {
+    type Item = ((u32, u32), Cell);
+    type IntoIter = btree_map::IntoIter<(u32, u32), Cell>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Grid {
+    type Item = (&'a (u32, u32), &'a Cell);
+    type IntoIter = btree_map::Iter<'a, (u32, u32), Cell>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}
@@ -0,0 +1,357 @@
+//!Shell IDList Array (i.e. `CIDA` of `CFSTR_SHELLIDLIST`) parser.
+//!
+//!`CIDA` consists of number of child items, followed by offsets of parent folder `ITEMIDLIST` and
+//!each child `ITEMIDLIST`, which is relative to parent.
+//!
+//!`ITEMIDLIST` is sequence of `SHITEMID`, each prefixed by its size and terminated by zero size.
+//!Content of `SHITEMID` is private to shell folder, but common types can be decoded.
+
+use super::{Error, Name, Wide, slice, u16_le, u32_le};
+
+///Size of `SHITEMID` size prefix, as well as `ITEMIDLIST` terminator.
+pub const SIZE_PREFIX: usize = 2;
+
+const fn guid(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> [u8; 16] {
+    let data1 = data1.to_le_bytes();
+    let data2 = data2.to_le_bytes();
+    let data3 = data3.to_le_bytes();
+    [
+        data1[0], data1[1], data1[2], data1[3], data2[0], data2[1], data3[0], data3[1],
+        data4[0], data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7],
+    ]
+}
+
+///`CLSID` of "This PC" (My Computer).
+pub const CLSID_MY_COMPUTER: [u8; 16] = guid(0x20D04FE0, 0x3AEA, 0x1069, [0xA2, 0xD8, 0x08, 0x00, 0x2B, 0x30, 0x30, 0x9D]);
+///`CLSID` of "Network".
+pub const CLSID_NETWORK: [u8; 16] = guid(0xF02C1A0D, 0xBE21, 0x4350, [0x88, 0xB0, 0x73, 0x67, 0xFC, 0x96, 0xEF, 0x3C]);
+///`CLSID` of "Recycle Bin".
+pub const CLSID_RECYCLE_BIN: [u8; 16] = guid(0x645FF040, 0x5081, 0x101B, [0x9F, 0x08, 0x00, 0xAA, 0x00, 0x2F, 0x95, 0x4E]);
+///`CLSID` of "Control Panel".
+pub const CLSID_CONTROL_PANEL: [u8; 16] = guid(0x21EC2020, 0x3AEA, 0x1069, [0xA2, 0xDD, 0x08, 0x00, 0x2B, 0x30, 0x30, 0x9D]);
+///`CLSID` of "Control Panel" in category view.
+pub const CLSID_CONTROL_PANEL_CATEGORY: [u8; 16] = guid(0x26EE0668, 0xA00A, 0x44D7, [0x93, 0x71, 0xBE, 0xB0, 0x64, 0xC9, 0x86, 0x83]);
+///`CLSID` of "Libraries".
+pub const CLSID_LIBRARIES: [u8; 16] = guid(0x031E4825, 0x7B94, 0x4DC3, [0xB1, 0x31, 0xE9, 0x46, 0xB4, 0x4C, 0x8D, 0xD5]);
+///`CLSID` of user's profile folder.
+pub const CLSID_USER_FILES: [u8; 16] = guid(0x59031A47, 0x3F72, 0x44A7, [0x89, 0xC5, 0x55, 0x95, 0xFE, 0x6B, 0x30, 0xEE]);
+///`CLSID` of "Quick access".
+pub const CLSID_QUICK_ACCESS: [u8; 16] = guid(0x679F85CB, 0x0220, 0x4080, [0xB2, 0x9B, 0x55, 0x40, 0xCC, 0x05, 0xAA, 0xB6]);
+
+///Known folders, which can be identified by [Item::Root](enum.Item.html#variant.Root).
+pub const KNOWN_FOLDERS: [(&str, [u8; 16]); 8] = [
+    ("This PC", CLSID_MY_COMPUTER),
+    ("Network", CLSID_NETWORK),
+    ("Recycle Bin", CLSID_RECYCLE_BIN),
+    ("Control Panel", CLSID_CONTROL_PANEL),
+    ("Control Panel", CLSID_CONTROL_PANEL_CATEGORY),
+    ("Libraries", CLSID_LIBRARIES),
+    ("User Files", CLSID_USER_FILES),
+    ("Quick access", CLSID_QUICK_ACCESS),
+];
+
+///Returns name of known folder, identified by `clsid`.
+pub fn known_folder(clsid: &[u8; 16]) -> Option<&'static str> {
+    KNOWN_FOLDERS.iter().find(|(_, known)| known == clsid).map(|(name, _)| *name)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Single `SHITEMID`.
+pub struct ItemId<'a>(&'a [u8]);
+
+impl<'a> ItemId<'a> {
+    #[inline]
+    ///Returns raw bytes, including size prefix.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    #[inline]
+    ///Returns content, following size prefix.
+    pub fn data(&self) -> &'a [u8] {
+        &self.0[SIZE_PREFIX..]
+    }
+
+    ///Decodes common shell item types, returning [Item::Unknown](enum.Item.html#variant.Unknown) otherwise.
+    pub fn decode(&self) -> Item<'a> {
+        let data = self.data();
+        let kind = match data.first() {
+            Some(kind) => *kind,
+            None => return Item::Unknown(0),
+        };
+
+        match kind {
+            0x1F => match slice(data, 2, 16) {
+                Ok(bytes) => {
+                    let mut clsid = [0; 16];
+                    clsid.copy_from_slice(bytes);
+                    Item::Root(clsid)
+                },
+                Err(_) => Item::Unknown(kind),
+            },
+            0x20..=0x2F => match data.get(1..).and_then(|path| ansi_str(path)) {
+                Some(path) if !path.is_empty() => Item::Drive(path),
+                _ => Item::Unknown(kind),
+            },
+            0x30..=0x3F => match FileSystemItem::parse(data) {
+                Some(item) => Item::FileSystem(item),
+                None => Item::Unknown(kind),
+            },
+            _ => Item::Unknown(kind),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///File system item, i.e. file or directory.
+pub struct FileSystemItem<'a> {
+    ///Whether item is directory.
+    pub is_directory: bool,
+    ///File size, truncated to 32 bits.
+    pub file_size: u32,
+    ///Modification time, as FAT date (high word) and time (low word).
+    pub modified: u32,
+    ///File attributes, truncated to 16 bits.
+    pub attributes: u16,
+    ///Short (8.3) name, which is wide for items with unicode flag (`0x04`).
+    pub short_name: Name<'a>,
+    ///Long name from extension block, if present.
+    pub long_name: Option<Wide<'a>>,
+}
+
+impl<'a> FileSystemItem<'a> {
+    //Data excludes size prefix.
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        const SHORT_NAME_OFFSET: usize = 12;
+        const EXTENSION_SIGNATURE: u32 = 0xBEEF_0004;
+
+        let kind = *data.first()?;
+        let file_size = u32_le(data, 2).ok()?;
+        let date = u16_le(data, 6).ok()? as u32;
+        let time = u16_le(data, 8).ok()? as u32;
+        let attributes = u16_le(data, 10).ok()?;
+
+        //Short name is ANSI, unless item is flagged as unicode.
+        let (short_name, short_name_size) = match kind & 0x04 != 0 {
+            true => {
+                let name = data.get(SHORT_NAME_OFFSET..)?;
+                let len = name.chunks_exact(2).position(|ch| ch == [0, 0])? * 2;
+                (Name::Wide(Wide(&name[..len])), len + 2)
+            },
+            false => {
+                let name = ansi_str(data.get(SHORT_NAME_OFFSET..)?)?;
+                (Name::Ansi(name), name.len() + 1)
+            }
+        };
+
+        //Extension block starts at even offset within SHITEMID, i.e. including size prefix.
+        let mut extension_offset = SHORT_NAME_OFFSET + short_name_size;
+        if extension_offset % 2 == 1 {
+            extension_offset += 1;
+        }
+
+        let long_name = data.get(extension_offset..).and_then(|extension| {
+            let size = u16_le(extension, 0).ok()? as usize;
+            let version = u16_le(extension, 2).ok()?;
+            if u32_le(extension, 4).ok()? != EXTENSION_SIGNATURE {
+                return None;
+            }
+
+            let extension = slice(extension, 0, size).ok()?;
+            let name_offset = match version {
+                0..=2 => return None,
+                3..=6 => 20,
+                7 => 38,
+                8 => 42,
+                _ => 46,
+            };
+            let name = extension.get(name_offset..)?;
+            let len = name.chunks_exact(2).position(|ch| ch == [0, 0])? * 2;
+            Some(Wide(&name[..len]))
+        });
+
+        Some(Self {
+            is_directory: kind & 0x01 != 0,
+            file_size,
+            modified: date << 16 | time,
+            attributes,
+            short_name,
+            long_name,
+        })
+    }
+}
+
+//Returns ANSI string up to terminator, if it is terminated.
+fn ansi_str(data: &[u8]) -> Option<&[u8]> {
+    data.iter().position(|byte| *byte == 0).map(|len| &data[..len])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Decoded shell item.
+pub enum Item<'a> {
+    ///Root folder, identified by `CLSID` (e.g. "This PC" or "Control Panel").
+    ///
+    ///Refer to [known_folder](fn.known_folder.html).
+    Root([u8; 16]),
+    ///Drive, identified by path in ANSI code page (e.g. `C:\`).
+    Drive(&'a [u8]),
+    ///File system item.
+    FileSystem(FileSystemItem<'a>),
+    ///Item of unknown type, as specified by first byte of content.
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Validated `ITEMIDLIST`.
+pub struct IdList<'a>(&'a [u8]);
+
+impl<'a> IdList<'a> {
+    ///Parses `ITEMIDLIST` at start of `data`, which may contain trailing data.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        let mut cursor = 0;
+        loop {
+            match u16_le(data, cursor)? as usize {
+                0 => break Ok(Self(&data[..cursor + SIZE_PREFIX])),
+                size if size < SIZE_PREFIX => break Err(Error::Invalid("item id size")),
+                size => {
+                    slice(data, cursor, size)?;
+                    cursor += size;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    ///Returns raw bytes, including terminator.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    #[inline]
+    ///Returns whether list is empty, which identifies desktop.
+    pub fn is_empty(&self) -> bool {
+        self.0.len() == SIZE_PREFIX
+    }
+
+    #[inline]
+    ///Returns iterator over item ids.
+    pub fn items(&self) -> ItemIds<'a> {
+        ItemIds(&self.0[..self.0.len() - SIZE_PREFIX])
+    }
+}
+
+#[derive(Clone, Debug)]
+///Iterator over item ids of [IdList](struct.IdList.html).
+pub struct ItemIds<'a>(&'a [u8]);
+
+impl<'a> Iterator for ItemIds<'a> {
+    type Item = ItemId<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        //List is validated by parser
+        let size = u16_le(self.0, 0).ok()? as usize;
+        let (item, rest) = self.0.split_at(size);
+        self.0 = rest;
+        Some(ItemId(item))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+///Parsed `CIDA`.
+///
+///Child items are validated on access, so that malformed item does not prevent access to others.
+pub struct Cida<'a> {
+    parent: IdList<'a>,
+    data: &'a [u8],
+    len: usize,
+}
+
+impl<'a> Cida<'a> {
+    #[inline]
+    ///Returns parent folder.
+    pub fn parent(&self) -> IdList<'a> {
+        self.parent
+    }
+
+    #[inline]
+    ///Returns number of child items.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    ///Returns whether there are no child items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///Returns child item at `idx`, relative to parent folder.
+    pub fn child(&self, idx: usize) -> Option<Result<IdList<'a>, Error>> {
+        if idx >= self.len {
+            return None;
+        }
+
+        //Offsets are checked by parser
+        let offset = u32_le(self.data, 4 + (idx + 1) * 4).ok()? as usize;
+        Some(IdList::parse(&self.data[offset..]))
+    }
+
+    #[inline]
+    ///Returns iterator over child items.
+    pub fn children(&self) -> Children<'a> {
+        Children {
+            cida: *self,
+            idx: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///Iterator over child items of [Cida](struct.Cida.html).
+pub struct Children<'a> {
+    cida: Cida<'a>,
+    idx: usize,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Result<IdList<'a>, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.cida.child(self.idx)?;
+        self.idx += 1;
+        Some(result)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.cida.len - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Children<'_> {
+}
+
+///Parses `CIDA`.
+///
+///Fails if any offset is outside of `data`, or parent folder is malformed.
+pub fn parse(data: &[u8]) -> Result<Cida<'_>, Error> {
+    let len = u32_le(data, 0)? as usize;
+    let offsets_size = len.checked_add(1).and_then(|count| count.checked_mul(4)).ok_or(Error::Truncated)?;
+    let offsets = slice(data, 4, offsets_size)?;
+
+    for offset in offsets.chunks_exact(4) {
+        let offset = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+        if offset >= data.len() {
+            return Err(Error::Truncated);
+        }
+    }
+
+    let parent_offset = u32_le(offsets, 0)? as usize;
+    Ok(Cida {
+        parent: IdList::parse(&data[parent_offset..])?,
+        data,
+        len,
+    })
+}
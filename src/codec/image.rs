@@ -0,0 +1,252 @@
+//!Decoded image, shared by bitmap and PNG formats.
+//!
+//!Pixels are stored as top-down rows of 8-bit RGBA or BGRA with straight (i.e. not premultiplied)
+//!alpha. Bitmaps are converted to and from it through [Dib](../bmp/struct.Dib.html), while
+//!helpers cover pixel conversions required by GDI, which expects BGRA, often bottom-up and
+//!premultiplied.
+
+use super::{Error, Resolution};
+use super::bmp::{self, Dib, InfoHeader, Profile};
+
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Order of channels within pixel.
+pub enum PixelFormat {
+    ///Red, green, blue and alpha.
+    Rgba8,
+    ///Blue, green, red and alpha, as used by bitmaps.
+    Bgra8,
+}
+
+impl Default for PixelFormat {
+    #[inline]
+    fn default() -> Self {
+        PixelFormat::Rgba8
+    }
+}
+
+///Swaps red and blue channels of every pixel, converting between RGBA and BGRA.
+pub fn swap_red_blue(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+///Multiplies color channels of every pixel by its alpha.
+pub fn premultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        for channel in pixel[..3].iter_mut() {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+///Divides color channels of every pixel by its alpha, reverting [premultiply](fn.premultiply.html).
+///
+///Channels of fully transparent pixels are zeroed.
+pub fn unpremultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        for channel in pixel[..3].iter_mut() {
+            *channel = match alpha {
+                0 => 0,
+                alpha => core::cmp::min((*channel as u32 * 255 + alpha / 2) / alpha, 255) as u8,
+            };
+        }
+    }
+}
+
+///Reverses order of rows, each of `stride` bytes, converting between top-down and bottom-up.
+///
+///Incomplete last row is left in place.
+pub fn flip_rows(pixels: &mut [u8], stride: usize) {
+    if stride == 0 {
+        return;
+    }
+
+    let rows = pixels.len() / stride;
+    for row in 0..rows / 2 {
+        let (top, bottom) = pixels.split_at_mut((rows - 1 - row) * stride);
+        top[row * stride..(row + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+///Image with 8 bits per channel.
+pub struct Image {
+    ///Width in pixels.
+    pub width: u32,
+    ///Height in pixels.
+    pub height: u32,
+    ///Order of channels.
+    pub format: PixelFormat,
+    ///Number of bytes in single row, at least `width * 4`.
+    pub stride: usize,
+    ///Top-down rows of pixels with straight alpha.
+    pub pixels: Vec<u8>,
+    ///Resolution, if known.
+    pub resolution: Option<Resolution>,
+    ///Embedded ICC profile, if any.
+    pub icc_profile: Option<Vec<u8>>,
+}
+
+impl Image {
+    ///Creates transparent image with rows of `width * 4` bytes.
+    pub fn new(width: u32, height: u32, format: PixelFormat) -> Result<Self, Error> {
+        let stride = (width as usize).checked_mul(4).ok_or(Error::Invalid("image size overflows"))?;
+        let size = stride.checked_mul(height as usize).ok_or(Error::Invalid("image size overflows"))?;
+
+        Ok(Self {
+            width,
+            height,
+            format,
+            stride,
+            pixels: alloc::vec![0; size],
+            resolution: None,
+            icc_profile: None,
+        })
+    }
+
+    ///Validates dimensions and stride against pixels.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::Invalid("image dimensions"));
+        }
+
+        let row = (self.width as usize).checked_mul(4).ok_or(Error::Invalid("image size overflows"))?;
+        if self.stride < row {
+            return Err(Error::Invalid("image stride"));
+        }
+
+        let size = self.stride.checked_mul(self.height as usize - 1)
+                              .and_then(|size| size.checked_add(row))
+                              .ok_or(Error::Invalid("image size overflows"))?;
+        match self.pixels.len() >= size {
+            true => Ok(()),
+            false => Err(Error::Truncated),
+        }
+    }
+
+    #[inline]
+    ///Returns pixels of row `y`, excluding padding.
+    ///
+    ///Panics if image is invalid or `y` is out of bounds.
+    pub fn row(&self, y: u32) -> &[u8] {
+        &self.pixels[y as usize * self.stride..][..self.width as usize * 4]
+    }
+
+    ///Returns whether alpha of every pixel is 255.
+    pub fn is_opaque(&self) -> bool {
+        (0..self.height).all(|y| self.row(y).chunks_exact(4).all(|pixel| pixel[3] == 0xFF))
+    }
+
+    ///Converts pixels into `format`.
+    pub fn convert(&mut self, format: PixelFormat) {
+        if self.format == format {
+            return;
+        }
+
+        let row = self.width as usize * 4;
+        if self.stride == 0 {
+            self.format = format;
+            return;
+        }
+        for line in self.pixels.chunks_mut(self.stride) {
+            let len = core::cmp::min(row, line.len());
+            swap_red_blue(&mut line[..len]);
+        }
+        self.format = format;
+    }
+
+    ///Decodes bitmap, including its resolution and embedded color profile.
+    ///
+    ///Image is RGBA with rows of `width * 4` bytes. Fails if pixels are compressed.
+    pub fn from_dib(dib: &Dib<'_>) -> Result<Self, Error> {
+        let mut pixels = Vec::new();
+        dib.to_rgba(&mut pixels)?;
+
+        Ok(Self {
+            width: dib.header.width as u32,
+            height: dib.header.abs_height(),
+            format: PixelFormat::Rgba8,
+            stride: dib.header.width as usize * 4,
+            pixels,
+            resolution: dib.resolution(),
+            icc_profile: match dib.profile {
+                Some(Profile::Embedded(profile)) => Some(profile.to_vec()),
+                _ => None,
+            },
+        })
+    }
+
+    //Returns header of 32 bits per pixel bottom-up bitmap, with its pixels in BGRA.
+    fn to_bitmap(&self, compression: u32) -> Result<(InfoHeader, Vec<u8>), Error> {
+        self.validate()?;
+        if self.width > i32::MAX as u32 || self.height > i32::MAX as u32 {
+            return Err(Error::Invalid("image dimensions"));
+        }
+
+        let mut header = InfoHeader::new(self.width as i32, self.height as i32, 32)?;
+        if let Some(resolution) = self.resolution {
+            let (x, y) = resolution.pels_per_meter();
+            header.x_pels_per_meter = x;
+            header.y_pels_per_meter = y;
+        }
+        if compression == bmp::BI_BITFIELDS {
+            header.compression = compression;
+            header.masks = [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000];
+        }
+
+        let mut pixels = Vec::with_capacity(header.size_image as usize);
+        for y in (0..self.height).rev() {
+            pixels.extend_from_slice(self.row(y));
+        }
+        if self.format == PixelFormat::Rgba8 {
+            swap_red_blue(&mut pixels);
+        }
+        Ok((header, pixels))
+    }
+
+    ///Writes image as BMP file of 32 bits per pixel, appending it to `out`.
+    ///
+    ///Returns number of written bytes.
+    pub fn write_bitmap(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        let (header, pixels) = self.to_bitmap(bmp::BI_RGB)?;
+        let start = out.len();
+        bmp::write_file(out, &header, &[], &pixels)?;
+        Ok(out.len() - start)
+    }
+
+    ///Writes image as packed DIB with `BITMAPINFOHEADER` (i.e. `CF_DIB`) of 32 bits per pixel,
+    ///appending it to `out`.
+    ///
+    ///Color profile is not written, as header cannot reference it.
+    ///
+    ///Returns number of written bytes.
+    pub fn write_dib(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        let (header, pixels) = self.to_bitmap(bmp::BI_RGB)?;
+        let start = out.len();
+        header.write(out);
+        out.extend_from_slice(&pixels);
+        Ok(out.len() - start)
+    }
+
+    ///Writes image as packed DIB with `BITMAPV5HEADER` (i.e. `CF_DIBV5`) of 32 bits per pixel with
+    ///alpha mask, appending it to `out`.
+    ///
+    ///Returns number of written bytes.
+    pub fn write_dibv5(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        let (header, pixels) = self.to_bitmap(bmp::BI_BITFIELDS)?;
+        let dib = Dib {
+            header,
+            header_bytes: &[],
+            colors: &[],
+            pixels: &pixels,
+            pixels_offset: 0,
+            profile: self.icc_profile.as_ref().map(|profile| Profile::Embedded(profile)),
+        };
+        bmp::write_dibv5(out, &dib)
+    }
+}
@@ -0,0 +1,277 @@
+//Zlib decoder, sufficient for PNG image data and embedded profiles.
+//
+//Huffman codes are decoded bit by bit in canonical order, trading speed for small size.
+
+use super::Error;
+
+use alloc::vec::Vec;
+
+const MAX_BITS: usize = 15;
+const MAX_LITERALS: usize = 288;
+const MAX_DISTANCES: usize = 30;
+//Order of code length code lengths of dynamic block.
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+struct Huffman {
+    //Number of codes of each length.
+    counts: [u16; MAX_BITS + 1],
+    //Symbols ordered by code.
+    symbols: [u16; MAX_LITERALS],
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, Error> {
+        let mut result = Self {
+            counts: [0; MAX_BITS + 1],
+            symbols: [0; MAX_LITERALS],
+        };
+        for len in lengths {
+            result.counts[*len as usize] += 1;
+        }
+
+        //Incomplete codes are permitted, as unused codes fail on decoding
+        let mut left = 1i32;
+        for len in 1..=MAX_BITS {
+            left = (left << 1) - result.counts[len] as i32;
+            if left < 0 {
+                return Err(Error::Invalid("deflate code is over-subscribed"));
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + result.counts[len];
+        }
+        for (symbol, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                result.symbols[offsets[*len as usize] as usize] = symbol as u16;
+                offsets[*len as usize] += 1;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+struct Inflater<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bits: u32,
+    bits_len: u32,
+    out: &'a mut Vec<u8>,
+    start: usize,
+    limit: usize,
+}
+
+impl<'a> Inflater<'a> {
+    fn bits(&mut self, count: u32) -> Result<u32, Error> {
+        while self.bits_len < count {
+            let byte = *self.data.get(self.pos).ok_or(Error::Truncated)?;
+            self.pos += 1;
+            self.bits |= (byte as u32) << self.bits_len;
+            self.bits_len += 8;
+        }
+
+        let result = self.bits & ((1u32 << count) - 1);
+        self.bits >>= count;
+        self.bits_len -= count;
+        Ok(result)
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> Result<u16, Error> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..=MAX_BITS {
+            code |= self.bits(1)? as i32;
+            let count = huffman.counts[len] as i32;
+            if code - count < first {
+                return Ok(huffman.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(Error::Invalid("deflate code"))
+    }
+
+    fn reserve(&self, len: usize) -> Result<(), Error> {
+        match self.out.len() - self.start + len <= self.limit {
+            true => Ok(()),
+            false => Err(Error::Invalid("deflate data exceeds expected size")),
+        }
+    }
+
+    fn blocks(&mut self) -> Result<(), Error> {
+        loop {
+            let last = self.bits(1)?;
+            match self.bits(2)? {
+                0 => self.stored()?,
+                1 => self.fixed()?,
+                2 => self.dynamic()?,
+                _ => return Err(Error::Invalid("deflate block type")),
+            }
+            if last == 1 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn stored(&mut self) -> Result<(), Error> {
+        //Block starts at byte boundary
+        self.bits = 0;
+        self.bits_len = 0;
+
+        let header = super::slice(self.data, self.pos, 4)?;
+        let len = u16::from_le_bytes([header[0], header[1]]);
+        if len != !u16::from_le_bytes([header[2], header[3]]) {
+            return Err(Error::Invalid("deflate stored block length"));
+        }
+
+        let block = super::slice(self.data, self.pos + 4, len as usize)?;
+        self.reserve(block.len())?;
+        self.out.extend_from_slice(block);
+        self.pos += 4 + block.len();
+        Ok(())
+    }
+
+    fn codes(&mut self, literals: &Huffman, distances: &Huffman) -> Result<(), Error> {
+        loop {
+            let symbol = self.decode(literals)? as usize;
+            match symbol {
+                0..=255 => {
+                    self.reserve(1)?;
+                    self.out.push(symbol as u8);
+                },
+                256 => return Ok(()),
+                257..=285 => {
+                    let symbol = symbol - 257;
+                    let len = LENGTH_BASE[symbol] as usize + self.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+                    let symbol = self.decode(distances)? as usize;
+                    if symbol >= MAX_DISTANCES {
+                        return Err(Error::Invalid("deflate distance"));
+                    }
+                    let distance = DISTANCE_BASE[symbol] as usize + self.bits(DISTANCE_EXTRA[symbol] as u32)? as usize;
+                    if distance > self.out.len() - self.start {
+                        return Err(Error::Invalid("deflate distance"));
+                    }
+
+                    self.reserve(len)?;
+                    let from = self.out.len() - distance;
+                    for index in from..from + len {
+                        let byte = self.out[index];
+                        self.out.push(byte);
+                    }
+                },
+                _ => return Err(Error::Invalid("deflate literal")),
+            }
+        }
+    }
+
+    fn fixed(&mut self) -> Result<(), Error> {
+        let mut lengths = [0u8; MAX_LITERALS + 32];
+        for (symbol, len) in lengths.iter_mut().enumerate() {
+            *len = match symbol {
+                0..=143 => 8,
+                144..=255 => 9,
+                256..=279 => 7,
+                280..=287 => 8,
+                _ => 5,
+            };
+        }
+
+        let literals = Huffman::new(&lengths[..MAX_LITERALS])?;
+        let distances = Huffman::new(&lengths[MAX_LITERALS..])?;
+        self.codes(&literals, &distances)
+    }
+
+    fn dynamic(&mut self) -> Result<(), Error> {
+        let literals_len = self.bits(5)? as usize + 257;
+        let distances_len = self.bits(5)? as usize + 1;
+        let code_lengths_len = self.bits(4)? as usize + 4;
+        if literals_len > 286 || distances_len > MAX_DISTANCES {
+            return Err(Error::Invalid("deflate code counts"));
+        }
+
+        let mut lengths = [0u8; MAX_LITERALS + MAX_DISTANCES];
+        for index in CODE_LENGTH_ORDER.iter().take(code_lengths_len) {
+            lengths[*index] = self.bits(3)? as u8;
+        }
+        let code_lengths = Huffman::new(&lengths[..19])?;
+
+        let total = literals_len + distances_len;
+        let mut index = 0;
+        while index < total {
+            let symbol = self.decode(&code_lengths)?;
+            let (len, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => match index {
+                    0 => return Err(Error::Invalid("deflate repeat without length")),
+                    _ => (lengths[index - 1], 3 + self.bits(2)? as usize),
+                },
+                17 => (0, 3 + self.bits(3)? as usize),
+                _ => (0, 11 + self.bits(7)? as usize),
+            };
+            if index + repeat > total {
+                return Err(Error::Invalid("deflate code lengths"));
+            }
+            for length in lengths[index..index + repeat].iter_mut() {
+                *length = len;
+            }
+            index += repeat;
+        }
+
+        if lengths[256] == 0 {
+            return Err(Error::Invalid("deflate code without end of block"));
+        }
+
+        let literals = Huffman::new(&lengths[..literals_len])?;
+        let distances = Huffman::new(&lengths[literals_len..total])?;
+        self.codes(&literals, &distances)
+    }
+}
+
+//Decodes zlib stream, appending at most `limit` bytes to `out`.
+//
+//Nothing is appended on error. Returns number of appended bytes.
+pub(crate) fn zlib(data: &[u8], out: &mut Vec<u8>, limit: usize) -> Result<usize, Error> {
+    let header = super::slice(data, 0, 2)?;
+    if header[0] & 0x0F != 8 || header[0] >> 4 > 7 || u16::from_be_bytes([header[0], header[1]]) % 31 != 0 {
+        return Err(Error::Invalid("zlib header"));
+    }
+    if header[1] & 0x20 != 0 {
+        return Err(Error::Unsupported("zlib preset dictionary"));
+    }
+
+    let start = out.len();
+    let mut inflater = Inflater {
+        data,
+        pos: 2,
+        bits: 0,
+        bits_len: 0,
+        out,
+        start,
+        limit,
+    };
+
+    let result = inflater.blocks();
+
+    //Checksum follows at byte boundary
+    let pos = inflater.pos;
+    let result = result.and_then(|_| {
+        let checksum = super::slice(data, pos, 4)?;
+        match u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) == super::png::adler32(&out[start..]) {
+            true => Ok(out.len() - start),
+            false => Err(Error::Invalid("zlib checksum")),
+        }
+    });
+    if result.is_err() {
+        out.truncate(start);
+    }
+    result
+}
@@ -0,0 +1,143 @@
+//!JPEG stream validator.
+//!
+//!Markers are walked up to end of image without decoding entropy-coded data, so that stream can
+//!be passed through unchanged.
+
+use super::{Error, Resolution, slice};
+
+///Start of image marker.
+pub const SOI: u8 = 0xD8;
+///End of image marker.
+pub const EOI: u8 = 0xD9;
+///Start of scan marker.
+pub const SOS: u8 = 0xDA;
+///JFIF application segment marker.
+pub const APP0: u8 = 0xE0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Validated JPEG stream.
+pub struct Jpeg {
+    ///Width in pixels.
+    pub width: u16,
+    ///Height in pixels.
+    pub height: u16,
+    ///Number of color components.
+    pub components: u8,
+    ///Whether frame is progressive.
+    pub progressive: bool,
+    ///Resolution, as specified by JFIF header.
+    pub resolution: Option<Resolution>,
+    ///Size of stream up to and including end of image marker, excluding trailing data.
+    pub size: usize,
+}
+
+//Parses density of JFIF header, which is specified either per inch or per centimeter.
+fn jfif_resolution(segment: &[u8]) -> Option<Resolution> {
+    match segment {
+        [b'J', b'F', b'I', b'F', 0, _, _, unit, x0, x1, y0, y1, ..] => {
+            let (x, y) = (u16::from_be_bytes([*x0, *x1]) as u32, u16::from_be_bytes([*y0, *y1]) as u32);
+            match (unit, x > 0 && y > 0) {
+                (1, true) => Some(Resolution::from_dpi(x, y)),
+                (2, true) => Some(Resolution { x: x * 100, y: y * 100 }),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+//Skips entropy-coded data, returning offset of marker following it.
+fn skip_scan(data: &[u8], mut pos: usize) -> Result<usize, Error> {
+    loop {
+        pos += data.get(pos..).and_then(|rest| rest.iter().position(|byte| *byte == 0xFF)).ok_or(Error::Truncated)?;
+        match slice(data, pos + 1, 1)?[0] {
+            //Stuffed byte and restart markers belong to scan
+            0x00 | 0xD0..=0xD7 => pos += 2,
+            //Fill byte
+            0xFF => pos += 1,
+            _ => return Ok(pos),
+        }
+    }
+}
+
+///Validates JPEG stream, walking its segments up to end of image.
+///
+///First frame header provides dimensions. Fails if stream has no frame or ends before end of
+///image marker.
+pub fn parse(data: &[u8]) -> Result<Jpeg, Error> {
+    if slice(data, 0, 2)? != [0xFF, SOI] {
+        return Err(Error::Invalid("JPEG signature"));
+    }
+
+    let mut result: Option<Jpeg> = None;
+    let mut resolution = None;
+    let mut pos = 2;
+    loop {
+        if slice(data, pos, 1)?[0] != 0xFF {
+            return Err(Error::Invalid("JPEG marker"));
+        }
+        //Marker can be preceded by fill bytes
+        while slice(data, pos + 1, 1)?[0] == 0xFF {
+            pos += 1;
+        }
+        let marker = data[pos + 1];
+        pos += 2;
+
+        match marker {
+            EOI => {
+                let mut jpeg = result.ok_or(Error::Invalid("JPEG without frame"))?;
+                jpeg.resolution = resolution;
+                jpeg.size = pos;
+                return Ok(jpeg);
+            },
+            //Standalone markers
+            0x01 | 0xD0..=0xD7 => continue,
+            0x00 | SOI => return Err(Error::Invalid("JPEG marker")),
+            _ => (),
+        }
+
+        let len = slice(data, pos, 2)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        if len < 2 {
+            return Err(Error::Invalid("JPEG segment length"));
+        }
+        let segment = slice(data, pos + 2, len - 2)?;
+        pos += len;
+
+        match marker {
+            APP0 if resolution.is_none() => resolution = jfif_resolution(segment),
+            //Frame headers, excluding DHT, JPG and DAC
+            0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF if result.is_none() => {
+                let components = *segment.get(5).ok_or(Error::Invalid("JPEG frame header"))?;
+                if components == 0 || segment.len() != 6 + components as usize * 3 {
+                    return Err(Error::Invalid("JPEG frame header"));
+                }
+
+                let height = u16::from_be_bytes([segment[1], segment[2]]);
+                let width = u16::from_be_bytes([segment[3], segment[4]]);
+                if width == 0 {
+                    return Err(Error::Invalid("JPEG dimensions"));
+                }
+                if height == 0 {
+                    return Err(Error::Unsupported("JPEG height defined by DNL"));
+                }
+
+                result = Some(Jpeg {
+                    width,
+                    height,
+                    components,
+                    progressive: [0xC2, 0xC6, 0xCA, 0xCE].contains(&marker),
+                    resolution: None,
+                    size: 0,
+                });
+            },
+            SOS => {
+                if result.is_none() {
+                    return Err(Error::Invalid("JPEG scan before frame"));
+                }
+                pos = skip_scan(data, pos)?;
+            },
+            _ => (),
+        }
+    }
+}
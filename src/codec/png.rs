@@ -0,0 +1,612 @@
+//!PNG chunk reader and writer, with minimal image decoder and encoder.
+//!
+//!Chunks are iterated without decoding image data, so that ancillary chunks (e.g. color profile)
+//!can be inspected and inserted into PNG produced by image encoder.
+//!
+//!Images are decoded from any valid PNG, including interlaced, while encoder writes RGBA in stored,
+//!i.e. uncompressed, deflate blocks.
+
+use super::{Error, Resolution, slice};
+use super::image::{Image, PixelFormat};
+
+use alloc::vec::Vec;
+
+///PNG signature.
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+///Image header chunk.
+pub const IHDR: [u8; 4] = *b"IHDR";
+///Embedded ICC profile chunk.
+pub const ICCP: [u8; 4] = *b"iCCP";
+///sRGB color space chunk.
+pub const SRGB: [u8; 4] = *b"sRGB";
+///Physical pixel dimensions chunk.
+pub const PHYS: [u8; 4] = *b"pHYs";
+///Palette chunk.
+pub const PLTE: [u8; 4] = *b"PLTE";
+///Transparency chunk.
+pub const TRNS: [u8; 4] = *b"tRNS";
+///Image data chunk.
+pub const IDAT: [u8; 4] = *b"IDAT";
+///Image end chunk.
+pub const IEND: [u8; 4] = *b"IEND";
+
+///Grayscale color type.
+pub const COLOR_GRAY: u8 = 0;
+///Truecolor color type.
+pub const COLOR_RGB: u8 = 2;
+///Indexed color type.
+pub const COLOR_PALETTE: u8 = 3;
+///Grayscale with alpha color type.
+pub const COLOR_GRAY_ALPHA: u8 = 4;
+///Truecolor with alpha color type.
+pub const COLOR_RGBA: u8 = 6;
+
+///Maximum size of decompressed ICC profile.
+pub const MAX_PROFILE_SIZE: usize = 16 * 1024 * 1024;
+
+//Maximum size of data of single stored deflate block.
+const STORED_BLOCK_SIZE: usize = 0xFFFF;
+//Maximum size of single IDAT chunk written by encoder.
+const IDAT_SIZE: usize = 0x10_0000;
+//Origin and spacing of pixels of each Adam7 pass.
+const ADAM7: [(u32, u32, u32, u32); 7] = [(0, 0, 8, 8), (4, 0, 8, 8), (0, 4, 4, 8), (2, 0, 4, 4), (0, 2, 2, 4), (1, 0, 2, 2), (0, 1, 1, 2)];
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = match value & 1 {
+                1 => 0xEDB8_8320 ^ (value >> 1),
+                _ => value >> 1,
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+fn crc(parts: &[&[u8]]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for part in parts {
+        for byte in part.iter() {
+            crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+    }
+    !crc
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    //Largest number of bytes that can be summed without overflow before reduction.
+    const CHUNK_SIZE: usize = 5552;
+    const MODULUS: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(CHUNK_SIZE) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    (b << 16) | a
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Chunk of PNG stream.
+pub struct Chunk<'a> {
+    ///Type of chunk.
+    pub kind: [u8; 4],
+    ///Data of chunk, excluding length, type and CRC.
+    pub data: &'a [u8],
+    ///Whole chunk, including length, type and CRC.
+    pub bytes: &'a [u8],
+}
+
+#[derive(Clone, Debug)]
+///Iterator over chunks of PNG stream, stopping at `IEND` or at first malformed chunk.
+pub struct Chunks<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (chunk, rest) = chunk(self.data).ok()?;
+        self.data = match chunk.kind {
+            IEND => &[],
+            _ => rest,
+        };
+        Some(chunk)
+    }
+}
+
+//Splits chunk from rest of data.
+fn chunk(data: &[u8]) -> Result<(Chunk<'_>, &[u8]), Error> {
+    let len = slice(data, 0, 4)?;
+    let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    let size = len.checked_add(12).ok_or(Error::Truncated)?;
+    let bytes = slice(data, 0, size)?;
+
+    let chunk = Chunk {
+        kind: [bytes[4], bytes[5], bytes[6], bytes[7]],
+        data: &bytes[8..8 + len],
+        bytes,
+    };
+    Ok((chunk, &data[size..]))
+}
+
+///Returns iterator over chunks of PNG stream, starting with `IHDR`.
+///
+///Fails if signature or `IHDR` is invalid.
+pub fn chunks(data: &[u8]) -> Result<Chunks<'_>, Error> {
+    if slice(data, 0, SIGNATURE.len())? != SIGNATURE {
+        return Err(Error::Invalid("PNG signature"));
+    }
+
+    let data = &data[SIGNATURE.len()..];
+    let (header, _) = chunk(data)?;
+    match header.kind {
+        IHDR if header.data.len() == 13 => Ok(Chunks {
+            data,
+        }),
+        _ => Err(Error::Invalid("PNG header")),
+    }
+}
+
+///Writes chunk of specified `kind`, calculating its CRC.
+pub fn write_chunk(out: &mut Vec<u8>, kind: [u8; 4], data: &[u8]) -> Result<(), Error> {
+    if data.len() > i32::MAX as usize {
+        return Err(Error::Invalid("PNG chunk size"));
+    }
+
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(&kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc(&[&kind, data]).to_be_bytes());
+    Ok(())
+}
+
+///Writes zlib stream with `data` in stored, i.e. uncompressed, deflate blocks.
+pub fn write_zlib_stored(out: &mut Vec<u8>, data: &[u8]) {
+    //Deflate with 32K window and no compression.
+    out.extend_from_slice(&[0x78, 0x01]);
+
+    let mut blocks = data.chunks(STORED_BLOCK_SIZE).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none() as u8;
+        let len = block.len() as u16;
+        out.push(last);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+}
+
+///Writes `iCCP` chunk, embedding ICC `profile` under `name`.
+///
+///Name must consist of 1 to 79 printable Latin-1 characters.
+pub fn write_iccp(out: &mut Vec<u8>, name: &str, profile: &[u8]) -> Result<(), Error> {
+    match name.len() {
+        1..=79 if name.bytes().all(|byte| (b' '..=b'~').contains(&byte)) => (),
+        _ => return Err(Error::Invalid("PNG profile name")),
+    }
+
+    let mut data = Vec::with_capacity(name.len() + 2 + profile.len() + profile.len() / STORED_BLOCK_SIZE * 5 + 11);
+    data.extend_from_slice(name.as_bytes());
+    //Null separator and compression method, which is always zlib
+    data.extend_from_slice(&[0, 0]);
+    write_zlib_stored(&mut data, profile);
+    write_chunk(out, ICCP, &data)
+}
+
+///Parses data of `pHYs` chunk.
+///
+///Returns `None` if unit is not meter (i.e. chunk specifies only aspect ratio) or resolution is zero.
+pub fn parse_phys(data: &[u8]) -> Option<Resolution> {
+    match data {
+        [x0, x1, x2, x3, y0, y1, y2, y3, 1] => {
+            let x = u32::from_be_bytes([*x0, *x1, *x2, *x3]);
+            let y = u32::from_be_bytes([*y0, *y1, *y2, *y3]);
+            match x > 0 && y > 0 {
+                true => Some(Resolution { x, y }),
+                false => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+///Writes `pHYs` chunk with `resolution` in pixels per meter.
+pub fn write_phys(out: &mut Vec<u8>, resolution: Resolution) -> Result<(), Error> {
+    let mut data = [1u8; 9];
+    data[..4].copy_from_slice(&resolution.x.to_be_bytes());
+    data[4..8].copy_from_slice(&resolution.y.to_be_bytes());
+    write_chunk(out, PHYS, &data)
+}
+
+///Returns resolution of PNG stream, as specified by `pHYs` chunk.
+pub fn resolution(data: &[u8]) -> Result<Option<Resolution>, Error> {
+    Ok(chunks(data)?.find(|chunk| chunk.kind == PHYS).and_then(|chunk| parse_phys(chunk.data)))
+}
+
+///Copies PNG stream `data` to `out`, replacing every chunk of kinds in `replace` with `chunks`,
+///which are inserted right after `IHDR`.
+///
+///`chunks` must consist of complete chunks, as written by [write_chunk](fn.write_chunk.html).
+///Content after `IEND` is dropped.
+///
+///Returns number of written bytes.
+pub fn insert_chunks(data: &[u8], replace: &[[u8; 4]], chunks: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    let start = out.len();
+    let mut end = false;
+    out.extend_from_slice(&SIGNATURE);
+    for (index, chunk) in self::chunks(data)?.enumerate() {
+        if !replace.contains(&chunk.kind) {
+            out.extend_from_slice(chunk.bytes);
+        }
+        //Only leading IHDR is followed by inserted chunks
+        if index == 0 {
+            out.extend_from_slice(chunks);
+        }
+        end = chunk.kind == IEND;
+    }
+
+    if !end {
+        out.truncate(start);
+        return Err(Error::Truncated);
+    }
+    Ok(out.len() - start)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Image header, i.e. data of `IHDR` chunk.
+pub struct Header {
+    ///Width in pixels.
+    pub width: u32,
+    ///Height in pixels.
+    pub height: u32,
+    ///Number of bits per sample or palette index.
+    pub bit_depth: u8,
+    ///Color type.
+    pub color_type: u8,
+    ///Whether image is interlaced by Adam7.
+    pub interlaced: bool,
+}
+
+impl Header {
+    ///Parses and validates data of `IHDR` chunk.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() != 13 {
+            return Err(Error::Invalid("PNG header"));
+        }
+
+        let width = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        let height = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        if width == 0 || height == 0 || width > i32::MAX as u32 || height > i32::MAX as u32 {
+            return Err(Error::Invalid("PNG dimensions"));
+        }
+
+        let (bit_depth, color_type) = (data[8], data[9]);
+        match (color_type, bit_depth) {
+            (COLOR_GRAY, 1) | (COLOR_GRAY, 2) | (COLOR_GRAY, 4) | (COLOR_GRAY, 8) | (COLOR_GRAY, 16) => (),
+            (COLOR_PALETTE, 1) | (COLOR_PALETTE, 2) | (COLOR_PALETTE, 4) | (COLOR_PALETTE, 8) => (),
+            (COLOR_RGB, 8) | (COLOR_RGB, 16) | (COLOR_GRAY_ALPHA, 8) | (COLOR_GRAY_ALPHA, 16) | (COLOR_RGBA, 8) | (COLOR_RGBA, 16) => (),
+            _ => return Err(Error::Invalid("PNG bit depth")),
+        }
+        if data[10] != 0 {
+            return Err(Error::Unsupported("PNG compression method"));
+        }
+        if data[11] != 0 {
+            return Err(Error::Unsupported("PNG filter method"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            bit_depth,
+            color_type,
+            interlaced: match data[12] {
+                0 => false,
+                1 => true,
+                _ => return Err(Error::Unsupported("PNG interlace method")),
+            },
+        })
+    }
+
+    #[inline]
+    ///Returns number of samples per pixel.
+    pub fn channels(&self) -> usize {
+        match self.color_type {
+            COLOR_RGB => 3,
+            COLOR_GRAY_ALPHA => 2,
+            COLOR_RGBA => 4,
+            _ => 1,
+        }
+    }
+
+    #[inline]
+    //Returns number of bytes of row of `width` pixels, excluding filter type.
+    fn row_size(&self, width: u32) -> u64 {
+        (width as u64 * self.channels() as u64 * self.bit_depth as u64 + 7) >> 3
+    }
+
+    //Returns passes as origin, spacing and dimensions.
+    fn passes(&self) -> impl Iterator<Item = (u32, u32, u32, u32, u32, u32)> {
+        let (width, height) = (self.width, self.height);
+        let passes = match self.interlaced {
+            true => &ADAM7[..],
+            false => &[(0, 0, 1, 1)][..],
+        };
+        passes.iter().filter(move |(x, y, _, _)| *x < width && *y < height).map(move |&(x, y, dx, dy)| {
+            (x, y, dx, dy, (width - x - 1) / dx + 1, (height - y - 1) / dy + 1)
+        })
+    }
+
+    ///Returns size of decompressed image data, including filter types of rows.
+    pub fn data_size(&self) -> Option<usize> {
+        let size = self.passes().fold(0u64, |size, (_, _, _, _, width, height)| size + (self.row_size(width) + 1) * height as u64);
+        match size <= usize::MAX as u64 {
+            true => Some(size as usize),
+            false => None,
+        }
+    }
+}
+
+///Returns header of PNG stream.
+pub fn header(data: &[u8]) -> Result<Header, Error> {
+    let header = chunks(data)?.next().ok_or(Error::Truncated)?;
+    Header::parse(header.data)
+}
+
+///Decompresses ICC profile from data of `iCCP` chunk, appending it to `out`.
+///
+///Profile larger than [MAX_PROFILE_SIZE](constant.MAX_PROFILE_SIZE.html) is rejected.
+///
+///Returns number of appended bytes.
+pub fn read_iccp(data: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+    let name = match data.iter().take(80).position(|byte| *byte == 0) {
+        Some(0) | None => return Err(Error::Invalid("PNG profile name")),
+        Some(name) => name,
+    };
+
+    match slice(data, name + 1, 1)?[0] {
+        0 => super::inflate::zlib(&data[name + 2..], out, MAX_PROFILE_SIZE),
+        _ => Err(Error::Unsupported("PNG compression method")),
+    }
+}
+
+//Reverses filter of `row`, with `prior` row being empty for first row of pass.
+fn unfilter(kind: u8, row: &mut [u8], prior: &[u8], bpp: usize) -> Result<(), Error> {
+    let up = |index: usize| prior.get(index).copied().unwrap_or(0);
+    match kind {
+        0 => (),
+        1 => for index in bpp..row.len() {
+            row[index] = row[index].wrapping_add(row[index - bpp]);
+        },
+        2 => for (index, byte) in row.iter_mut().enumerate() {
+            *byte = byte.wrapping_add(up(index));
+        },
+        3 => for index in 0..row.len() {
+            let left = match index >= bpp {
+                true => row[index - bpp],
+                false => 0,
+            };
+            row[index] = row[index].wrapping_add(((left as u16 + up(index) as u16) / 2) as u8);
+        },
+        4 => for index in 0..row.len() {
+            let (left, up_left) = match index >= bpp {
+                true => (row[index - bpp], up(index - bpp)),
+                false => (0, 0),
+            };
+            let above = up(index);
+            let estimate = left as i16 + above as i16 - up_left as i16;
+            let (distance_left, distance_up, distance_up_left) = ((estimate - left as i16).abs(), (estimate - above as i16).abs(), (estimate - up_left as i16).abs());
+            let predictor = match (distance_left <= distance_up && distance_left <= distance_up_left, distance_up <= distance_up_left) {
+                (true, _) => left,
+                (false, true) => above,
+                (false, false) => up_left,
+            };
+            row[index] = row[index].wrapping_add(predictor);
+        },
+        _ => return Err(Error::Invalid("PNG filter type")),
+    }
+
+    Ok(())
+}
+
+//Converts pixel `x` of unfiltered `row` into RGBA.
+fn pixel(header: &Header, row: &[u8], x: usize, palette: &[u8], transparency: &[u8]) -> Result<[u8; 4], Error> {
+    let depth = header.bit_depth as usize;
+    let sample = |index: usize| -> u16 {
+        match depth {
+            16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+            8 => row[index] as u16,
+            _ => {
+                let bit = index * depth;
+                ((row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1)) as u16
+            },
+        }
+    };
+    let scale = |value: u16| -> u8 {
+        match depth {
+            16 => (value >> 8) as u8,
+            8 => value as u8,
+            _ => (value as u32 * 255 / ((1 << depth) - 1)) as u8,
+        }
+    };
+
+    Ok(match header.color_type {
+        COLOR_GRAY => {
+            let gray = sample(x);
+            let alpha = match transparency {
+                [high, low] if u16::from_be_bytes([*high, *low]) == gray => 0,
+                _ => 0xFF,
+            };
+            let gray = scale(gray);
+            [gray, gray, gray, alpha]
+        },
+        COLOR_RGB => {
+            let (red, green, blue) = (sample(x * 3), sample(x * 3 + 1), sample(x * 3 + 2));
+            let alpha = match transparency {
+                [r0, r1, g0, g1, b0, b1] if [u16::from_be_bytes([*r0, *r1]), u16::from_be_bytes([*g0, *g1]), u16::from_be_bytes([*b0, *b1])] == [red, green, blue] => 0,
+                _ => 0xFF,
+            };
+            [scale(red), scale(green), scale(blue), alpha]
+        },
+        COLOR_PALETTE => {
+            let index = sample(x) as usize;
+            let color = palette.get(index * 3..index * 3 + 3).ok_or(Error::Invalid("PNG palette index"))?;
+            [color[0], color[1], color[2], transparency.get(index).copied().unwrap_or(0xFF)]
+        },
+        COLOR_GRAY_ALPHA => {
+            let gray = scale(sample(x * 2));
+            [gray, gray, gray, scale(sample(x * 2 + 1))]
+        },
+        _ => [scale(sample(x * 4)), scale(sample(x * 4 + 1)), scale(sample(x * 4 + 2)), scale(sample(x * 4 + 3))],
+    })
+}
+
+///Decodes PNG stream as RGBA image, including its resolution and embedded ICC profile.
+///
+///Every bit depth, color type and interlacing is supported, with 16 bits per sample truncated to
+///8 bits. Pixels are allocated according to header, so its dimensions should be checked against
+///[limits](../../limits/index.html) first.
+pub fn read_image(data: &[u8]) -> Result<Image, Error> {
+    let mut chunks = chunks(data)?;
+    let header = Header::parse(chunks.next().ok_or(Error::Truncated)?.data)?;
+
+    let mut palette = &[][..];
+    let mut transparency = &[][..];
+    let mut compressed = Vec::new();
+    let mut resolution = None;
+    let mut icc_profile = None;
+    let mut end = false;
+    for chunk in chunks {
+        match chunk.kind {
+            PLTE => palette = chunk.data,
+            TRNS => transparency = chunk.data,
+            IDAT => compressed.extend_from_slice(chunk.data),
+            PHYS => resolution = parse_phys(chunk.data),
+            ICCP => {
+                let mut profile = Vec::new();
+                read_iccp(chunk.data, &mut profile)?;
+                icc_profile = Some(profile);
+            },
+            IEND => end = true,
+            _ => (),
+        }
+    }
+
+    if !end {
+        return Err(Error::Truncated);
+    }
+    if header.color_type == COLOR_PALETTE && (palette.is_empty() || palette.len() % 3 != 0 || palette.len() > 256 * 3) {
+        return Err(Error::Invalid("PNG palette"));
+    }
+
+    let size = header.data_size().ok_or(Error::Invalid("PNG size overflows"))?;
+    let mut data = Vec::new();
+    if super::inflate::zlib(&compressed, &mut data, size)? != size {
+        return Err(Error::Truncated);
+    }
+
+    let mut image = Image::new(header.width, header.height, PixelFormat::Rgba8)?;
+    image.resolution = resolution;
+    image.icc_profile = icc_profile;
+
+    let bpp = core::cmp::max(header.channels() * header.bit_depth as usize / 8, 1);
+    let mut offset = 0;
+    for (x0, y0, dx, dy, width, height) in header.passes() {
+        //Fits, as whole data size does
+        let row_size = header.row_size(width) as usize;
+        for y in 0..height as usize {
+            let (done, rest) = data.split_at_mut(offset + 1);
+            let prior = match y {
+                0 => &[][..],
+                _ => &done[offset - row_size..offset],
+            };
+            let row = &mut rest[..row_size];
+            unfilter(done[offset], row, prior, bpp)?;
+
+            let line = (y0 as usize + y * dy as usize) * image.stride;
+            for x in 0..width as usize {
+                let pixel = pixel(&header, row, x, palette, transparency)?;
+                let start = line + (x0 as usize + x * dx as usize) * 4;
+                image.pixels[start..start + 4].copy_from_slice(&pixel);
+            }
+            offset += row_size + 1;
+        }
+    }
+
+    Ok(image)
+}
+
+///Encodes image as PNG stream of 8 bits RGBA, including its resolution and embedded ICC profile,
+///appending it to `out`.
+///
+///Image data is written in stored deflate blocks, i.e. without compression.
+///
+///Returns number of written bytes.
+pub fn write_image(out: &mut Vec<u8>, image: &Image) -> Result<usize, Error> {
+    image.validate()?;
+    if image.width > i32::MAX as u32 || image.height > i32::MAX as u32 {
+        return Err(Error::Invalid("image dimensions"));
+    }
+
+    let row = image.width as usize * 4;
+    let mut data = Vec::with_capacity((row + 1) * image.height as usize);
+    for y in 0..image.height {
+        //Filter type none
+        data.push(0);
+        let start = data.len();
+        data.extend_from_slice(image.row(y));
+        if image.format == PixelFormat::Bgra8 {
+            super::image::swap_red_blue(&mut data[start..]);
+        }
+    }
+    let mut zlib = Vec::with_capacity(data.len() + data.len() / STORED_BLOCK_SIZE * 5 + 11);
+    write_zlib_stored(&mut zlib, &data);
+
+    let mut header = [0u8; 13];
+    header[..4].copy_from_slice(&image.width.to_be_bytes());
+    header[4..8].copy_from_slice(&image.height.to_be_bytes());
+    header[8] = 8;
+    header[9] = COLOR_RGBA;
+
+    let start = out.len();
+    out.extend_from_slice(&SIGNATURE);
+    let result = write_chunk(out, IHDR, &header).and_then(|_| {
+        if let Some(profile) = image.icc_profile.as_ref() {
+            write_iccp(out, "ICC profile", profile)?;
+        }
+        if let Some(resolution) = image.resolution {
+            write_phys(out, resolution)?;
+        }
+        for part in zlib.chunks(IDAT_SIZE) {
+            write_chunk(out, IDAT, part)?;
+        }
+        write_chunk(out, IEND, &[])
+    });
+
+    match result {
+        Ok(()) => Ok(out.len() - start),
+        Err(error) => {
+            out.truncate(start);
+            Err(error)
+        },
+    }
+}
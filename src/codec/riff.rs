@@ -0,0 +1,55 @@
+//!RIFF container, shared by WAVE and WebP.
+//!
+//!Each chunk is four character code, size and data padded to even size.
+
+use super::{Error, slice, u32_le};
+
+//Chunk id and data.
+pub type Chunk<'a> = ([u8; 4], &'a [u8]);
+
+//Splits chunk at start of `data`, returning it with rest after padding.
+pub fn chunk(data: &[u8]) -> Result<(Chunk<'_>, &[u8]), Error> {
+    let mut id = [0; 4];
+    id.copy_from_slice(slice(data, 0, 4)?);
+    let size = u32_le(data, 4)? as usize;
+    let content = slice(data, 8, size)?;
+    //Padding of last chunk is commonly missing.
+    let rest = data.get(8 + size + (size & 1)..).unwrap_or(&[]);
+    Ok(((id, content), rest))
+}
+
+#[derive(Clone, Debug)]
+//Iterator over consecutive chunks, stopping after first truncated one.
+pub struct Chunks<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Chunks<'a> {
+    #[inline]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Result<Chunk<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        match chunk(self.data) {
+            Ok((chunk, rest)) => {
+                self.data = rest;
+                Some(Ok(chunk))
+            },
+            Err(error) => {
+                self.data = &[];
+                Some(Err(error))
+            },
+        }
+    }
+}
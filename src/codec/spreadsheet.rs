@@ -0,0 +1,448 @@
+//!SpreadsheetML 2003 codec, i.e. Excel `XML Spreadsheet`.
+//!
+//!Workbook consists of `Styles` and `Worksheet` elements, where worksheet's `Table` contains
+//!`Row` elements with `Cell` elements. Rows and cells are positioned either by one based
+//!`ss:Index` or directly after previous one, taking merged cells into account.
+//!
+//!Cell keeps its type, formula (in R1C1 notation) and basic formatting of its style.
+
+use super::Error;
+use super::grid::{Grid, Cell, Value, Format, NumberFormat, Align, date_serial};
+use super::xml::{self, Token, Reader};
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::Cow;
+use core::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Range of merged cells.
+pub struct Merge {
+    ///Zero based row of top left cell.
+    pub row: u32,
+    ///Zero based column of top left cell.
+    pub column: u32,
+    ///Number of merged rows, including first.
+    pub rows: u32,
+    ///Number of merged columns, including first.
+    pub columns: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+///Worksheet of workbook.
+pub struct Worksheet {
+    ///Name.
+    pub name: String,
+    ///Cells.
+    pub grid: Grid,
+    ///Merged ranges, each at position of its top left cell.
+    pub merges: Vec<Merge>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+///Workbook fragment, as placed onto clipboard.
+pub struct Workbook {
+    ///Worksheets, usually only one for copied range.
+    pub worksheets: Vec<Worksheet>,
+}
+
+fn attribute<'a, 'b>(attributes: &'b [(&'a str, Cow<'a, str>)], name: &str) -> Option<&'b str> {
+    attributes.iter().find(|(attribute, _)| *attribute == name).map(|(_, value)| value.as_ref())
+}
+
+//Parses one based index into zero based.
+fn index(attributes: &[(&str, Cow<'_, str>)]) -> Result<Option<u32>, Error> {
+    match attribute(attributes, "Index").map(str::parse::<u32>) {
+        Some(Ok(index)) if index > 0 => Ok(Some(index - 1)),
+        Some(_) => Err(Error::Invalid("cell coordinate")),
+        None => Ok(None),
+    }
+}
+
+fn count(attributes: &[(&str, Cow<'_, str>)], name: &str) -> Result<u32, Error> {
+    match attribute(attributes, name).map(str::parse::<u32>) {
+        Some(Ok(count)) => Ok(count),
+        Some(Err(_)) => Err(Error::Invalid("cell count")),
+        None => Ok(0),
+    }
+}
+
+fn number_format(format: &str, result: &mut Format) {
+    let (number, digits) = match format {
+        "General" | "General Number" => (NumberFormat::General, 0),
+        "Fixed" | "Standard" => (NumberFormat::Fixed, 2),
+        "Percent" => (NumberFormat::Percent, 2),
+        "Scientific" => (NumberFormat::Exponent, 2),
+        "Currency" | "Euro Currency" => (NumberFormat::Currency, 2),
+        format => {
+            let digits = match format.find('.') {
+                Some(dot) => format[dot + 1..].bytes().take_while(|byte| *byte == b'0').count() as u8,
+                None => 0,
+            };
+            if format.contains('%') {
+                (NumberFormat::Percent, digits)
+            } else if format.contains("E+") || format.contains("E-") {
+                (NumberFormat::Exponent, digits)
+            } else if format.starts_with('0') || format.starts_with('#') {
+                (NumberFormat::Fixed, digits)
+            } else {
+                return;
+            }
+        }
+    };
+
+    result.number = number;
+    result.digits = digits;
+}
+
+fn typed_value(kind: &str, data: String) -> Result<Value, Error> {
+    match kind {
+        "Number" => match data.trim().parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(Value::Number(number)),
+            _ => Err(Error::Invalid("cell value")),
+        },
+        "String" => Ok(Value::Text(data)),
+        "Boolean" => match data.trim() {
+            "1" => Ok(Value::Bool(true)),
+            "0" => Ok(Value::Bool(false)),
+            _ => Err(Error::Invalid("cell value")),
+        },
+        "DateTime" => Ok(Value::DateTime(data)),
+        "Error" => Ok(Value::Error(data)),
+        _ => Err(Error::Invalid("cell type")),
+    }
+}
+
+#[derive(Default)]
+struct Position {
+    row: u32,
+    column: u32,
+    //Next row and column.
+    next_row: u32,
+    next_column: u32,
+}
+
+///Parses `XML Spreadsheet`, appending its worksheets to `out`.
+///
+///Elements other than styles, worksheets and their cells are ignored, as are cell comments.
+///
+///Returns number of appended worksheets.
+pub fn parse(text: &str, out: &mut Workbook) -> Result<usize, Error> {
+    let text = text.trim_end_matches('\0');
+
+    let mut styles: Vec<(String, Format)> = Vec::new();
+    let mut worksheets = Vec::new();
+    let mut position = Position::default();
+    let mut in_workbook = false;
+    //Style ID, which properties are being read.
+    let mut style: Option<usize> = None;
+    //Type of data and its text, if data is being read.
+    let mut data: Option<(String, String)> = None;
+    let mut cell = None;
+    let mut skip_depth = 0usize;
+
+    for token in Reader::new(text) {
+        let token = token?;
+
+        if skip_depth > 0 {
+            match token {
+                Token::Start { empty: false, .. } => skip_depth += 1,
+                Token::End(_) => skip_depth -= 1,
+                _ => (),
+            }
+            continue;
+        }
+
+        match token {
+            Token::Start { name, attributes, empty } => match name {
+                "Workbook" => in_workbook = true,
+                _ if !in_workbook => return Err(Error::Invalid("root element is not Workbook")),
+                "Style" => {
+                    let id = attribute(&attributes, "ID").unwrap_or_default().into();
+                    styles.push((id, Format::default()));
+                    style = match empty {
+                        true => None,
+                        false => Some(styles.len() - 1),
+                    };
+                },
+                "Alignment" | "Font" | "NumberFormat" => if let Some(idx) = style {
+                    let format = &mut styles[idx].1;
+                    match name {
+                        "Alignment" => if let Some(align) = attribute(&attributes, "Horizontal") {
+                            format.align = match align {
+                                "Left" => Align::Left,
+                                "Center" | "CenterAcrossSelection" => Align::Center,
+                                "Right" => Align::Right,
+                                "Fill" => Align::Fill,
+                                "General" => Align::General,
+                                _ => Align::Default,
+                            };
+                        },
+                        "Font" => {
+                            format.bold = attribute(&attributes, "Bold") == Some("1");
+                            format.italic = attribute(&attributes, "Italic") == Some("1");
+                        },
+                        _ => if let Some(number) = attribute(&attributes, "Format") {
+                            number_format(number, format);
+                        },
+                    }
+                },
+                "Worksheet" => {
+                    worksheets.push(Worksheet {
+                        name: attribute(&attributes, "Name").unwrap_or_default().into(),
+                        ..Worksheet::default()
+                    });
+                    position = Position::default();
+                },
+                "Row" if !worksheets.is_empty() => {
+                    position.row = index(&attributes)?.unwrap_or(position.next_row);
+                    position.next_row = position.row.checked_add(count(&attributes, "Span")?)
+                                                    .and_then(|row| row.checked_add(1))
+                                                    .ok_or(Error::Invalid("cell coordinate"))?;
+                    position.next_column = 0;
+                },
+                "Cell" => if let Some(worksheet) = worksheets.last_mut() {
+                    let row = position.row;
+                    let column = index(&attributes)?.unwrap_or(position.next_column);
+                    let across = count(&attributes, "MergeAcross")?;
+                    let down = count(&attributes, "MergeDown")?;
+                    position.column = column;
+                    position.next_column = column.checked_add(across)
+                                                 .and_then(|column| column.checked_add(1))
+                                                 .ok_or(Error::Invalid("cell coordinate"))?;
+
+                    if across > 0 || down > 0 {
+                        match (row.checked_add(down).and(down.checked_add(1)), across.checked_add(1)) {
+                            (Some(rows), Some(columns)) => worksheet.merges.push(Merge {
+                                row,
+                                column,
+                                rows,
+                                columns,
+                            }),
+                            _ => return Err(Error::Invalid("merged range")),
+                        }
+                    }
+
+                    let format = attribute(&attributes, "StyleID").and_then(|id| styles.iter().rev().find(|(style, _)| style == id)).map(|(_, format)| *format);
+                    let formula = attribute(&attributes, "Formula").map(String::from);
+                    if format.is_some() || formula.is_some() {
+                        worksheet.grid.set(row, column, Cell {
+                            value: Value::Empty,
+                            formula,
+                            format,
+                        });
+                    }
+                    cell = match empty {
+                        true => None,
+                        false => Some((row, column)),
+                    };
+                },
+                "Data" if cell.is_some() && !empty => {
+                    data = Some((attribute(&attributes, "Type").unwrap_or("String").into(), String::new()));
+                },
+                "Comment" if !empty => skip_depth = 1,
+                _ => (),
+            },
+            Token::Text(text) => if let Some((_, value)) = data.as_mut() {
+                value.push_str(&text);
+            },
+            Token::End(name) => match name {
+                "Style" => style = None,
+                "Cell" => cell = None,
+                "Data" => if let (Some((kind, value)), Some((row, column)), Some(worksheet)) = (data.take(), cell, worksheets.last_mut()) {
+                    worksheet.grid.get_mut(row, column).value = typed_value(&kind, value)?;
+                },
+                _ => (),
+            },
+        }
+    }
+
+    if !in_workbook {
+        return Err(Error::Invalid("root element is not Workbook"));
+    }
+
+    let len = worksheets.len();
+    out.worksheets.extend(worksheets);
+    Ok(len)
+}
+
+fn format_attributes(out: &mut String, format: &Format) {
+    let align = match format.align {
+        Align::Default => None,
+        Align::General => Some("General"),
+        Align::Left => Some("Left"),
+        Align::Center => Some("Center"),
+        Align::Right => Some("Right"),
+        Align::Fill => Some("Fill"),
+    };
+    if let Some(align) = align {
+        let _ = write!(out, "<Alignment ss:Horizontal=\"{}\"/>", align);
+    }
+
+    if format.bold || format.italic {
+        out.push_str("<Font");
+        if format.bold {
+            out.push_str(" ss:Bold=\"1\"");
+        }
+        if format.italic {
+            out.push_str(" ss:Italic=\"1\"");
+        }
+        out.push_str("/>");
+    }
+
+    let mut pattern = String::from("0");
+    if format.digits > 0 {
+        pattern.push('.');
+        for _ in 0..format.digits {
+            pattern.push('0');
+        }
+    }
+    let number = match format.number {
+        NumberFormat::Default => None,
+        NumberFormat::General => Some(Cow::Borrowed("General")),
+        NumberFormat::Fixed => Some(Cow::Owned(pattern)),
+        NumberFormat::Percent => Some(Cow::Owned(pattern + "%")),
+        NumberFormat::Exponent => Some(Cow::Owned(pattern + "E+00")),
+        NumberFormat::Currency => Some(Cow::Borrowed("Currency")),
+    };
+    if let Some(number) = number {
+        let _ = write!(out, "<NumberFormat ss:Format=\"{}\"/>", number);
+    }
+}
+
+fn write_data(out: &mut String, value: &Value) -> Result<(), Error> {
+    let (kind, text): (&str, Cow<'_, str>) = match value {
+        Value::Empty => return Ok(()),
+        Value::Number(number) if number.is_finite() => ("Number", alloc::format!("{}", number).into()),
+        Value::Number(_) => return Err(Error::Invalid("number is not finite")),
+        Value::Text(text) => ("String", text.as_str().into()),
+        Value::Bool(value) => ("Boolean", if *value { "1" } else { "0" }.into()),
+        Value::DateTime(value) => match date_serial(value) {
+            Some(_) => ("DateTime", value.as_str().into()),
+            None => return Err(Error::Invalid("date time")),
+        },
+        Value::Error(error) => ("Error", error.as_str().into()),
+    };
+
+    let _ = write!(out, "<Data ss:Type=\"{}\">", kind);
+    xml::escape(out, &text)?;
+    out.push_str("</Data>");
+    Ok(())
+}
+
+fn write_worksheet(out: &mut String, worksheet: &Worksheet, styles: &[Format]) -> Result<(), Error> {
+    out.push_str(" <Worksheet ss:Name=\"");
+    xml::escape(out, &worksheet.name)?;
+    out.push_str("\">\r\n  <Table>\r\n");
+
+    //Merged ranges need cell to be written, even if it is not in grid.
+    let mut positions: Vec<(u32, u32)> = worksheet.grid.iter().map(|(position, _)| *position).collect();
+    for merge in worksheet.merges.iter() {
+        if merge.rows == 0 || merge.columns == 0 {
+            return Err(Error::Invalid("merged range"));
+        }
+        if worksheet.grid.get(merge.row, merge.column).is_none() {
+            positions.push((merge.row, merge.column));
+        }
+    }
+    positions.sort_unstable();
+    positions.dedup();
+
+    let mut current_row = None;
+    for (row, column) in positions {
+        let (one_row, one_column) = match (row.checked_add(1), column.checked_add(1)) {
+            (Some(row), Some(column)) => (row, column),
+            _ => return Err(Error::Invalid("cell coordinate")),
+        };
+
+        if current_row != Some(row) {
+            if current_row.is_some() {
+                out.push_str("   </Row>\r\n");
+            }
+            let _ = write!(out, "   <Row ss:Index=\"{}\">\r\n", one_row);
+            current_row = Some(row);
+        }
+
+        let _ = write!(out, "    <Cell ss:Index=\"{}\"", one_column);
+        if let Some(merge) = worksheet.merges.iter().find(|merge| merge.row == row && merge.column == column) {
+            if merge.columns > 1 {
+                let _ = write!(out, " ss:MergeAcross=\"{}\"", merge.columns - 1);
+            }
+            if merge.rows > 1 {
+                let _ = write!(out, " ss:MergeDown=\"{}\"", merge.rows - 1);
+            }
+        }
+
+        match worksheet.grid.get(row, column) {
+            Some(cell) => {
+                if let Some(idx) = cell.format.and_then(|format| styles.iter().position(|style| *style == format)) {
+                    let _ = write!(out, " ss:StyleID=\"s{}\"", idx + 1);
+                }
+                if let Some(formula) = cell.formula.as_ref() {
+                    out.push_str(" ss:Formula=\"");
+                    xml::escape(out, formula)?;
+                    out.push('"');
+                }
+                out.push('>');
+                write_data(out, &cell.value)?;
+                out.push_str("</Cell>\r\n");
+            },
+            None => out.push_str("/>\r\n"),
+        }
+    }
+    if current_row.is_some() {
+        out.push_str("   </Row>\r\n");
+    }
+
+    out.push_str("  </Table>\r\n </Worksheet>\r\n");
+    Ok(())
+}
+
+///Writes `workbook` as `XML Spreadsheet`.
+///
+///Each distinct cell format is written as separate style.
+///Fails if text contains characters not allowed in XML, if number is not finite, if date is
+///invalid or if merged range is empty.
+///
+///Returns number of written bytes.
+pub fn write(out: &mut String, workbook: &Workbook) -> Result<usize, Error> {
+    let start = out.len();
+
+    let mut styles = Vec::new();
+    for worksheet in workbook.worksheets.iter() {
+        for (_, cell) in worksheet.grid.iter() {
+            if let Some(format) = cell.format {
+                if !styles.contains(&format) {
+                    styles.push(format);
+                }
+            }
+        }
+    }
+
+    out.push_str("<?xml version=\"1.0\"?>\r\n<?mso-application progid=\"Excel.Sheet\"?>\r\n");
+    out.push_str("<Workbook xmlns=\"urn:schemas-microsoft-com:office:spreadsheet\"\r\n");
+    out.push_str(" xmlns:o=\"urn:schemas-microsoft-com:office:office\"\r\n");
+    out.push_str(" xmlns:x=\"urn:schemas-microsoft-com:office:excel\"\r\n");
+    out.push_str(" xmlns:ss=\"urn:schemas-microsoft-com:office:spreadsheet\"\r\n");
+    out.push_str(" xmlns:html=\"http://www.w3.org/TR/REC-html40\">\r\n");
+
+    if !styles.is_empty() {
+        out.push_str(" <Styles>\r\n");
+        for (idx, format) in styles.iter().enumerate() {
+            let _ = write!(out, "  <Style ss:ID=\"s{}\">", idx + 1);
+            format_attributes(out, format);
+            out.push_str("</Style>\r\n");
+        }
+        out.push_str(" </Styles>\r\n");
+    }
+
+    for worksheet in workbook.worksheets.iter() {
+        if let Err(error) = write_worksheet(out, worksheet, &styles) {
+            out.truncate(start);
+            return Err(error);
+        }
+    }
+
+    out.push_str("</Workbook>\r\n");
+    Ok(out.len() - start)
+}
@@ -0,0 +1,252 @@
+//!SVG document codec, i.e. registered `image/svg+xml` and `SVG`.
+//!
+//!Document is usually UTF-8, but some applications place it as UTF-16, with or without byte order
+//!mark, which is detected when decoding. Only dimensions of root `svg` element are extracted,
+//!while document itself is passed unchanged.
+//!
+//!Entities declared by DTD are not supported, as they are never expanded.
+
+use super::Error;
+use super::xml::{Token, Reader};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Encoding of document.
+pub enum Encoding {
+    ///UTF-8, which is default.
+    Utf8,
+    ///UTF-16 little endian.
+    Utf16Le,
+    ///UTF-16 big endian.
+    Utf16Be,
+}
+
+impl Encoding {
+    ///Detects encoding by byte order mark or by leading `<` of UTF-16.
+    ///
+    ///Returns encoding and size of byte order mark.
+    pub fn detect(data: &[u8]) -> (Self, usize) {
+        match data {
+            [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+            [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+            [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+            [b'<', 0, ..] => (Encoding::Utf16Le, 0),
+            [0, b'<', ..] => (Encoding::Utf16Be, 0),
+            _ => (Encoding::Utf8, 0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Unit of length.
+pub enum Unit {
+    ///User units, i.e. pixels.
+    None,
+    ///Pixels.
+    Px,
+    ///Inches.
+    In,
+    ///Centimeters.
+    Cm,
+    ///Millimeters.
+    Mm,
+    ///Points.
+    Pt,
+    ///Picas.
+    Pc,
+    ///Font size.
+    Em,
+    ///Height of `x` of font.
+    Ex,
+    ///Percentage of viewport.
+    Percent,
+}
+
+//Units by suffix, with `%` being last to not shadow others.
+const UNITS: [(&str, Unit); 9] = [
+    ("px", Unit::Px),
+    ("in", Unit::In),
+    ("cm", Unit::Cm),
+    ("mm", Unit::Mm),
+    ("pt", Unit::Pt),
+    ("pc", Unit::Pc),
+    ("em", Unit::Em),
+    ("ex", Unit::Ex),
+    ("%", Unit::Percent),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+///Length of `width` or `height` attribute.
+pub struct Length {
+    ///Non-negative value.
+    pub value: f64,
+    ///Unit.
+    pub unit: Unit,
+}
+
+impl Length {
+    ///Parses length, failing if it is negative.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let text = text.trim();
+        let (number, unit) = UNITS.iter().find_map(|(suffix, unit)| text.strip_suffix(suffix).map(|number| (number, *unit))).unwrap_or((text, Unit::None));
+
+        match number.parse::<f64>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(Self {
+                value,
+                unit,
+            }),
+            _ => Err(Error::Invalid("SVG length")),
+        }
+    }
+
+    ///Returns length in pixels at 96 DPI, unless it is relative to font or viewport.
+    pub fn pixels(&self) -> Option<f64> {
+        let scale = match self.unit {
+            Unit::None | Unit::Px => 1.0,
+            Unit::In => 96.0,
+            Unit::Cm => 96.0 / 2.54,
+            Unit::Mm => 96.0 / 25.4,
+            Unit::Pt => 96.0 / 72.0,
+            Unit::Pc => 16.0,
+            Unit::Em | Unit::Ex | Unit::Percent => return None,
+        };
+        Some(self.value * scale)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+///Rectangle of user space, i.e. `viewBox` attribute.
+pub struct ViewBox {
+    ///Left edge.
+    pub x: f64,
+    ///Top edge.
+    pub y: f64,
+    ///Non-negative width.
+    pub width: f64,
+    ///Non-negative height.
+    pub height: f64,
+}
+
+impl ViewBox {
+    ///Parses four numbers, separated by whitespace and/or comma.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut numbers = [0.0; 4];
+        let mut parts = text.split(|ch: char| ch == ',' || ch.is_whitespace()).filter(|part| !part.is_empty());
+        for number in numbers.iter_mut() {
+            *number = match parts.next().map(str::parse::<f64>) {
+                Some(Ok(number)) if number.is_finite() => number,
+                _ => return Err(Error::Invalid("SVG viewBox")),
+            };
+        }
+
+        let [x, y, width, height] = numbers;
+        match parts.next().is_none() && width >= 0.0 && height >= 0.0 {
+            true => Ok(Self {
+                x,
+                y,
+                width,
+                height,
+            }),
+            false => Err(Error::Invalid("SVG viewBox")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+///Dimensions of validated SVG document.
+pub struct Svg {
+    ///`width` of root element.
+    pub width: Option<Length>,
+    ///`height` of root element.
+    pub height: Option<Length>,
+    ///`viewBox` of root element.
+    pub view_box: Option<ViewBox>,
+}
+
+impl Svg {
+    ///Returns size of rendered document in pixels at 96 DPI.
+    ///
+    ///Dimension, which is missing or relative, is taken from `viewBox`.
+    pub fn size(&self) -> Option<(f64, f64)> {
+        let width = self.width.and_then(|width| width.pixels()).or_else(|| self.view_box.map(|view_box| view_box.width))?;
+        let height = self.height.and_then(|height| height.pixels()).or_else(|| self.view_box.map(|view_box| view_box.height))?;
+        Some((width, height))
+    }
+}
+
+///Decodes document in detected encoding, appending it to `out` without byte order mark and
+///terminating nulls.
+///
+///Nothing is appended on error. Returns number of appended bytes.
+pub fn decode(data: &[u8], out: &mut String) -> Result<usize, Error> {
+    let (encoding, bom) = Encoding::detect(data);
+    let data = &data[bom..];
+
+    match encoding {
+        Encoding::Utf8 => {
+            let len = data.iter().rposition(|byte| *byte != 0).map_or(0, |idx| idx + 1);
+            let text = core::str::from_utf8(&data[..len]).map_err(|_| Error::Invalid("SVG is not valid UTF-8"))?;
+            out.push_str(text);
+            Ok(text.len())
+        },
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if data.len() & 1 != 0 {
+                return Err(Error::Invalid("SVG is not valid UTF-16"));
+            }
+
+            let mut units: Vec<u16> = data.chunks_exact(2).map(|unit| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                _ => u16::from_be_bytes([unit[0], unit[1]]),
+            }).collect();
+            while units.last() == Some(&0) {
+                units.pop();
+            }
+
+            let start = out.len();
+            for ch in core::char::decode_utf16(units.iter().copied()) {
+                match ch {
+                    Ok(ch) => out.push(ch),
+                    Err(_) => {
+                        out.truncate(start);
+                        return Err(Error::Invalid("SVG is not valid UTF-16"));
+                    },
+                }
+            }
+            Ok(out.len() - start)
+        },
+    }
+}
+
+///Validates document, extracting dimensions of its root element.
+///
+///Fails if document is not well-formed or its root is not `svg`.
+pub fn parse(text: &str) -> Result<Svg, Error> {
+    let mut result = None;
+    for token in Reader::new(text.trim_end_matches('\0')) {
+        match token? {
+            Token::Start { name, attributes, .. } if result.is_none() => {
+                if name != "svg" {
+                    return Err(Error::Invalid("root element is not svg"));
+                }
+
+                let mut svg = Svg::default();
+                for (attribute, value) in attributes.iter() {
+                    match *attribute {
+                        //Automatic size is same as missing one
+                        "width" | "height" if value.trim() == "auto" => (),
+                        "width" => svg.width = Some(Length::parse(value)?),
+                        "height" => svg.height = Some(Length::parse(value)?),
+                        "viewBox" => svg.view_box = Some(ViewBox::parse(value)?),
+                        _ => (),
+                    }
+                }
+                result = Some(svg);
+            },
+            _ => (),
+        }
+    }
+
+    result.ok_or(Error::Invalid("root element is not svg"))
+}
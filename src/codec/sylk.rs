@@ -0,0 +1,294 @@
+//!Symbolic Link (SYLK) codec, i.e. `CF_SYLK`.
+//!
+//!Content is text of records, each on its own line, starting with `ID` and ending with `E`.
+//!Record consists of type and fields, separated by `;`, where each field starts with its key
+//!character. Literal `;` within field is doubled.
+//!
+//!Supported records are `C` (cell value and formula) and `F` (cell formatting), while `B`
+//!(bounds) is only written. Other records are ignored.
+
+use super::Error;
+use super::grid::{Grid, Cell, Value, Format, NumberFormat, Align, date_serial};
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use core::fmt::Write;
+
+//Splits record into fields, unescaping `;;`
+fn fields(record: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut field = String::new();
+    let mut chars = record.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ';' if chars.peek() == Some(&';') => {
+                chars.next();
+                field.push(';');
+            },
+            ';' => result.push(core::mem::take(&mut field)),
+            ch => field.push(ch),
+        }
+    }
+    result.push(field);
+
+    result
+}
+
+//Parses one based coordinate into zero based.
+fn coordinate(value: &str) -> Result<u32, Error> {
+    match value.parse::<u32>() {
+        Ok(value) if value > 0 => Ok(value - 1),
+        _ => Err(Error::Invalid("cell coordinate")),
+    }
+}
+
+fn parse_value(value: &str) -> Result<Value, Error> {
+    if let Some(text) = value.strip_prefix('"') {
+        let text = text.strip_suffix('"').unwrap_or(text);
+        return Ok(Value::Text(text.into()));
+    }
+
+    match value {
+        "TRUE" => Ok(Value::Bool(true)),
+        "FALSE" => Ok(Value::Bool(false)),
+        value if value.starts_with('#') => Ok(Value::Error(value.into())),
+        value => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(Value::Number(number)),
+            _ => Err(Error::Invalid("cell value")),
+        }
+    }
+}
+
+fn parse_format(code: &str, format: &mut Format) -> Result<(), Error> {
+    let mut chars = code.chars();
+    let (number, align) = match (chars.next(), chars.next_back()) {
+        (Some(number), Some(align)) => (number, align),
+        _ => return Err(Error::Invalid("cell format")),
+    };
+
+    format.number = match number {
+        'D' => NumberFormat::Default,
+        'G' | '*' => NumberFormat::General,
+        'F' | 'I' => NumberFormat::Fixed,
+        'E' => NumberFormat::Exponent,
+        'C' | '$' => NumberFormat::Currency,
+        '%' => NumberFormat::Percent,
+        _ => return Err(Error::Invalid("cell format")),
+    };
+    format.digits = chars.as_str().parse().map_err(|_| Error::Invalid("cell format"))?;
+    format.align = match align {
+        'D' => Align::Default,
+        'G' => Align::General,
+        'L' => Align::Left,
+        'C' => Align::Center,
+        'R' => Align::Right,
+        'X' => Align::Fill,
+        '-' => Align::Default,
+        _ => return Err(Error::Invalid("cell format")),
+    };
+
+    Ok(())
+}
+
+fn parse_style(style: &str, format: &mut Format) {
+    let mut chars = style.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            'D' => format.bold = true,
+            'I' => format.italic = true,
+            //Font index
+            'M' => {
+                let digits = chars.as_str().bytes().take_while(u8::is_ascii_digit).count();
+                chars = chars.as_str()[digits..].chars();
+            },
+            _ => (),
+        }
+    }
+}
+
+///Parses SYLK text.
+///
+///Fails if `ID` record is not first, or if `E` record is missing.
+///Row and column formatting is ignored, as grid has only cell formatting.
+pub fn parse(text: &str) -> Result<Grid, Error> {
+    let text = text.trim_end_matches('\0');
+    let mut records = text.split(['\r', '\n']).filter(|record| !record.is_empty());
+
+    match records.next() {
+        Some(record) if record == "ID" || record.starts_with("ID;") => (),
+        _ => return Err(Error::Invalid("missing ID record")),
+    }
+
+    let mut grid = Grid::new();
+    let mut row = 0;
+    let mut column = 0;
+
+    for record in records {
+        let fields = fields(record);
+        let (kind, fields) = fields.split_first().expect("at least one field");
+
+        match kind.as_str() {
+            "E" => return Ok(grid),
+            "C" => {
+                let mut value = None;
+                let mut formula = None;
+                for field in fields {
+                    match field.split_at(field.chars().next().map(char::len_utf8).unwrap_or(0)) {
+                        ("Y", value) => row = coordinate(value)?,
+                        ("X", value) => column = coordinate(value)?,
+                        ("K", field) => value = Some(parse_value(field)?),
+                        ("E", field) => formula = Some(String::from(field)),
+                        _ => (),
+                    }
+                }
+
+                if value.is_some() || formula.is_some() {
+                    let cell = grid.get_mut(row, column);
+                    cell.value = value.unwrap_or(Value::Empty);
+                    cell.formula = formula;
+                }
+            },
+            "F" => {
+                let mut format = None;
+                let mut is_cell = true;
+                for field in fields {
+                    match field.split_at(field.chars().next().map(char::len_utf8).unwrap_or(0)) {
+                        ("Y", value) => row = coordinate(value)?,
+                        ("X", value) => column = coordinate(value)?,
+                        ("F", code) => parse_format(code, format.get_or_insert_with(Format::default))?,
+                        ("S", style) => parse_style(style, format.get_or_insert_with(Format::default)),
+                        //Row, column or default formatting
+                        ("R", _) | ("C", _) | ("D", _) => is_cell = false,
+                        _ => (),
+                    }
+                }
+
+                if let (Some(format), true) = (format, is_cell) {
+                    grid.get_mut(row, column).format = Some(format);
+                }
+            },
+            _ => continue,
+        }
+    }
+
+    Err(Error::Truncated)
+}
+
+fn write_escaped(out: &mut String, value: &str) -> Result<(), Error> {
+    for ch in value.chars() {
+        match ch {
+            '\r' | '\n' | '\0' => return Err(Error::Invalid("cell contains line break or null character")),
+            ';' => out.push_str(";;"),
+            ch => out.push(ch),
+        }
+    }
+    Ok(())
+}
+
+fn write_format(out: &mut String, format: &Format) {
+    out.push_str(";F");
+    out.push(match format.number {
+        NumberFormat::Default => 'D',
+        NumberFormat::General => 'G',
+        NumberFormat::Fixed => 'F',
+        NumberFormat::Exponent => 'E',
+        NumberFormat::Currency => 'C',
+        NumberFormat::Percent => '%',
+    });
+    let _ = write!(out, "{}", format.digits);
+    out.push(match format.align {
+        Align::Default => 'D',
+        Align::General => 'G',
+        Align::Left => 'L',
+        Align::Center => 'C',
+        Align::Right => 'R',
+        Align::Fill => 'X',
+    });
+
+    if format.bold || format.italic {
+        out.push_str(";S");
+        if format.bold {
+            out.push('D');
+        }
+        if format.italic {
+            out.push('I');
+        }
+    }
+}
+
+fn write_cell(out: &mut String, row: u32, column: u32, cell: &Cell) -> Result<(), Error> {
+    let (row, column) = match (row.checked_add(1), column.checked_add(1)) {
+        (Some(row), Some(column)) => (row, column),
+        _ => return Err(Error::Invalid("cell coordinate")),
+    };
+
+    if let Some(format) = cell.format.as_ref() {
+        out.push('F');
+        write_format(out, format);
+        let _ = write!(out, ";Y{};X{}\r\n", row, column);
+    }
+
+    if cell.value == Value::Empty && cell.formula.is_none() {
+        return Ok(());
+    }
+
+    let _ = write!(out, "C;Y{};X{}", row, column);
+    match &cell.value {
+        Value::Empty => (),
+        Value::Number(number) if number.is_finite() => {
+            let _ = write!(out, ";K{}", number);
+        },
+        Value::Number(_) => return Err(Error::Invalid("number is not finite")),
+        Value::Text(text) => {
+            out.push_str(";K\"");
+            write_escaped(out, text)?;
+            out.push('"');
+        },
+        Value::DateTime(value) => match date_serial(value) {
+            Some(serial) => {
+                let _ = write!(out, ";K{}", serial);
+            },
+            None => return Err(Error::Invalid("date time")),
+        },
+        Value::Bool(true) => out.push_str(";KTRUE"),
+        Value::Bool(false) => out.push_str(";KFALSE"),
+        Value::Error(error) => {
+            out.push_str(";K");
+            write_escaped(out, error)?;
+        },
+    }
+    if let Some(formula) = cell.formula.as_ref() {
+        out.push_str(";E");
+        write_escaped(out, formula)?;
+    }
+    out.push_str("\r\n");
+
+    Ok(())
+}
+
+///Writes `grid` as SYLK text.
+///
+///Date and time is written as serial number.
+///Fails if text contains line break or null character, if number is not finite or if date is invalid.
+///
+///Returns number of written bytes.
+pub fn write(out: &mut String, grid: &Grid) -> Result<usize, Error> {
+    let start = out.len();
+    out.push_str("ID;PWXL;N;E\r\n");
+
+    let (rows, columns) = (grid.rows(), grid.columns());
+    if rows > 0 {
+        let _ = write!(out, "B;Y{};X{};D0 0 {} {}\r\n", rows, columns, rows - 1, columns - 1);
+    }
+
+    for (&(row, column), cell) in grid {
+        if let Err(error) = write_cell(out, row, column, cell) {
+            out.truncate(start);
+            return Err(error);
+        }
+    }
+
+    out.push_str("E\r\n");
+    Ok(out.len() - start)
+}
@@ -0,0 +1,147 @@
+//!Delimited text tables, as produced by spreadsheets (i.e. Excel `Csv` and tab-separated text).
+//!
+//!Cells containing delimiter, quote or line break are quoted, with quotes inside doubled.
+//!Rows are terminated by `\r\n`, though `\n` and lone `\r` are accepted when parsing.
+
+use super::Error;
+
+use alloc::vec::Vec;
+use alloc::string::String;
+
+///Delimiter of comma-separated values.
+pub const CSV: char = ',';
+///Delimiter of tab-separated values, used by spreadsheets for plain text.
+pub const TSV: char = '\t';
+
+///Guesses delimiter of comma-separated values by its first row.
+///
+///Spreadsheets use list separator of user's locale, which is either `,` or `;`.
+pub fn sniff(text: &str) -> char {
+    let mut in_quotes = false;
+    let mut commas = 0usize;
+    let mut semicolons = 0usize;
+
+    for ch in text.chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => commas += 1,
+            ';' if !in_quotes => semicolons += 1,
+            '\r' | '\n' if !in_quotes => break,
+            _ => (),
+        }
+    }
+
+    match semicolons > commas {
+        true => ';',
+        false => CSV,
+    }
+}
+
+///Parses `text` with cells separated by `delimiter`, appending rows to `out`.
+///
+///Parsing is lenient: quote is special only at start of cell, and unterminated quoted cell
+///extends to the end of text.
+///Trailing null characters and trailing rows without content are ignored.
+///
+///Returns number of appended rows.
+pub fn parse(text: &str, delimiter: char, out: &mut Vec<Vec<String>>) -> usize {
+    let text = text.trim_end_matches('\0');
+    let start = out.len();
+
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut cell_start = true;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                },
+                '"' => in_quotes = false,
+                ch => cell.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if cell_start => {
+                in_quotes = true;
+                cell_start = false;
+            },
+            ch if ch == delimiter => {
+                row.push(core::mem::take(&mut cell));
+                cell_start = true;
+            },
+            '\r' | '\n' => {
+                if ch == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                row.push(core::mem::take(&mut cell));
+                out.push(core::mem::take(&mut row));
+                cell_start = true;
+            },
+            ch => {
+                cell.push(ch);
+                cell_start = false;
+            }
+        }
+    }
+
+    //Last row is not terminated
+    if !cell_start || !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        out.push(row);
+    }
+
+    while out.len() > start && out[out.len() - 1].iter().all(String::is_empty) {
+        out.pop();
+    }
+
+    out.len() - start
+}
+
+fn write_cell(out: &mut String, cell: &str, delimiter: char) {
+    if cell.contains([delimiter, '"', '\r', '\n']) {
+        out.push('"');
+        for ch in cell.chars() {
+            if ch == '"' {
+                out.push('"');
+            }
+            out.push(ch);
+        }
+        out.push('"');
+    } else {
+        out.push_str(cell);
+    }
+}
+
+///Writes `rows` with cells separated by `delimiter`, terminating each row with `\r\n`.
+///
+///Fails if cell contains null character, as it would terminate clipboard text.
+///
+///Returns number of written bytes.
+pub fn write<R: AsRef<[S]>, S: AsRef<str>>(out: &mut String, rows: &[R], delimiter: char) -> Result<usize, Error> {
+    let start = out.len();
+
+    for row in rows {
+        for (idx, cell) in row.as_ref().iter().enumerate() {
+            let cell = cell.as_ref();
+            if cell.contains('\0') {
+                out.truncate(start);
+                return Err(Error::Invalid("cell contains null character"));
+            }
+
+            if idx > 0 {
+                out.push(delimiter);
+            }
+            write_cell(out, cell, delimiter);
+        }
+        out.push_str("\r\n");
+    }
+
+    Ok(out.len() - start)
+}
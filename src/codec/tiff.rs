@@ -0,0 +1,868 @@
+//!Tagged Image File Format (TIFF) codec, i.e. `CF_TIFF`.
+//!
+//!Content starts with byte order mark (`II` or `MM`) and offset of first image file directory
+//!(IFD). Each IFD describes single page as list of tags, followed by offset of next IFD.
+//!
+//!Decoding supports strips, compressed with PackBits or LZW (optionally with horizontal
+//!predictor) or uncompressed, of bilevel, grayscale, palette and RGB images. Pages are
+//!converted into BMP files, being image representation of this crate, and written as
+//!uncompressed RGB or RGBA.
+
+use super::{Error, Resolution, slice};
+use super::bmp::{self, InfoHeader};
+
+use alloc::vec::Vec;
+use alloc::collections::BTreeSet;
+
+///No compression.
+pub const COMPRESSION_NONE: u16 = 1;
+///CCITT modified Huffman RLE.
+pub const COMPRESSION_CCITT_RLE: u16 = 2;
+///CCITT Group 3 fax.
+pub const COMPRESSION_CCITT_T4: u16 = 3;
+///CCITT Group 4 fax.
+pub const COMPRESSION_CCITT_T6: u16 = 4;
+///Lempel-Ziv-Welch.
+pub const COMPRESSION_LZW: u16 = 5;
+///JPEG.
+pub const COMPRESSION_JPEG: u16 = 7;
+///Deflate.
+pub const COMPRESSION_DEFLATE: u16 = 8;
+///Macintosh run-length encoding.
+pub const COMPRESSION_PACKBITS: u16 = 32773;
+
+///Zero is white.
+pub const PHOTOMETRIC_WHITE_IS_ZERO: u16 = 0;
+///Zero is black.
+pub const PHOTOMETRIC_BLACK_IS_ZERO: u16 = 1;
+///RGB.
+pub const PHOTOMETRIC_RGB: u16 = 2;
+///Palette color.
+pub const PHOTOMETRIC_PALETTE: u16 = 3;
+
+///No absolute unit, i.e. resolution specifies only aspect ratio.
+pub const RESOLUTION_UNIT_NONE: u16 = 1;
+///Inch.
+pub const RESOLUTION_UNIT_INCH: u16 = 2;
+///Centimeter.
+pub const RESOLUTION_UNIT_CENTIMETER: u16 = 3;
+
+const NEW_SUBFILE_TYPE: u16 = 254;
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const PHOTOMETRIC: u16 = 262;
+const FILL_ORDER: u16 = 266;
+const STRIP_OFFSETS: u16 = 273;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const ROWS_PER_STRIP: u16 = 278;
+const STRIP_BYTE_COUNTS: u16 = 279;
+const X_RESOLUTION: u16 = 282;
+const Y_RESOLUTION: u16 = 283;
+const PLANAR_CONFIGURATION: u16 = 284;
+const RESOLUTION_UNIT: u16 = 296;
+const PAGE_NUMBER: u16 = 297;
+const PREDICTOR: u16 = 317;
+const COLOR_MAP: u16 = 320;
+const TILE_WIDTH: u16 = 322;
+const EXTRA_SAMPLES: u16 = 338;
+
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
+const RATIONAL: u16 = 5;
+
+//Size of field type, if known.
+fn type_size(kind: u16) -> Option<usize> {
+    match kind {
+        //BYTE, ASCII, SBYTE, UNDEFINED
+        1 | 2 | 6 | 7 => Some(1),
+        //SHORT, SSHORT
+        3 | 8 => Some(2),
+        //LONG, SLONG, FLOAT, IFD
+        4 | 9 | 11 | 13 => Some(4),
+        //RATIONAL, SRATIONAL, DOUBLE
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+//Number of bytes, containing `bits`.
+#[inline]
+fn whole_bytes(bits: u64) -> u64 {
+    match bits % 8 {
+        0 => bits / 8,
+        _ => bits / 8 + 1,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Order {
+    big_endian: bool,
+}
+
+impl Order {
+    fn u16(self, data: &[u8], offset: usize) -> Result<u16, Error> {
+        let bytes = slice(data, offset, 2)?;
+        let bytes = [bytes[0], bytes[1]];
+        Ok(match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    }
+
+    fn u32(self, data: &[u8], offset: usize) -> Result<u32, Error> {
+        let bytes = slice(data, offset, 4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Ok(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+///Validated TIFF content.
+pub struct Tiff<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+    pages: Vec<usize>,
+}
+
+impl<'a> Tiff<'a> {
+    #[inline]
+    ///Returns whether content is big endian, i.e. starts with `MM`.
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    #[inline]
+    ///Returns number of pages, i.e. IFDs.
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    #[inline]
+    ///Returns whether there are no pages, which is never the case for parsed content.
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    ///Returns page at `index`.
+    ///
+    ///Fails if `index` is out of bounds or if required tags are missing or invalid.
+    pub fn page(&self, index: usize) -> Result<Page<'a>, Error> {
+        let offset = *self.pages.get(index).ok_or(Error::Invalid("page index"))?;
+        Page::parse(self.data, Order { big_endian: self.big_endian }, offset)
+    }
+}
+
+///Parses TIFF header and IFD chain.
+///
+///Fails if chain is empty or loops, or if any tag value lies outside of `data`.
+pub fn parse(data: &[u8]) -> Result<Tiff<'_>, Error> {
+    let order = match slice(data, 0, 2)? {
+        b"II" => Order { big_endian: false },
+        b"MM" => Order { big_endian: true },
+        _ => return Err(Error::Invalid("TIFF byte order")),
+    };
+    match order.u16(data, 2)? {
+        42 => (),
+        43 => return Err(Error::Unsupported("BigTIFF")),
+        _ => return Err(Error::Invalid("TIFF signature")),
+    }
+
+    let mut pages = Vec::new();
+    let mut visited = BTreeSet::new();
+    let mut offset = order.u32(data, 4)? as usize;
+    while offset != 0 {
+        if !visited.insert(offset) {
+            return Err(Error::Invalid("IFD chain loops"));
+        }
+
+        let count = order.u16(data, offset)? as usize;
+        if count == 0 {
+            return Err(Error::Invalid("empty IFD"));
+        }
+        let entries = slice(data, offset + 2, count * 12)?;
+        for entry in entries.chunks_exact(12) {
+            let kind = order.u16(entry, 2)?;
+            let size = match type_size(kind) {
+                Some(size) => (order.u32(entry, 4)? as usize).checked_mul(size).ok_or(Error::Truncated)?,
+                //Unknown types must be ignored
+                None => continue,
+            };
+            if size > 4 {
+                slice(data, order.u32(entry, 8)? as usize, size)?;
+            }
+        }
+
+        pages.push(offset);
+        offset = order.u32(data, offset + 2 + count * 12)? as usize;
+    }
+
+    if pages.is_empty() {
+        return Err(Error::Invalid("missing IFD"));
+    }
+
+    Ok(Tiff {
+        data,
+        big_endian: order.big_endian,
+        pages,
+    })
+}
+
+#[derive(Clone, Debug)]
+///Page of TIFF content, as described by its IFD.
+pub struct Page<'a> {
+    ///Width in pixels.
+    pub width: u32,
+    ///Height in pixels.
+    pub height: u32,
+    ///Number of bits in each sample.
+    pub bits_per_sample: u16,
+    ///Number of samples (i.e. channels) per pixel.
+    pub samples_per_pixel: u16,
+    ///Compression, one of `COMPRESSION_*` constants.
+    pub compression: u16,
+    ///Color space, as in `PHOTOMETRIC_*` constants.
+    pub photometric: u16,
+    ///Resolution, if specified in absolute unit.
+    pub resolution: Option<Resolution>,
+    data: &'a [u8],
+    order: Order,
+    rows_per_strip: u32,
+    strip_offsets: Vec<u32>,
+    strip_byte_counts: Vec<u32>,
+    planar: u16,
+    predictor: u16,
+    color_map: Vec<u32>,
+    //Index of alpha sample and whether it is premultiplied.
+    alpha: Option<(usize, bool)>,
+}
+
+impl<'a> Page<'a> {
+    //Returns unsigned integer values of entry.
+    fn values(data: &[u8], order: Order, entry: &[u8]) -> Result<Vec<u32>, Error> {
+        let kind = order.u16(entry, 2)?;
+        let count = order.u32(entry, 4)? as usize;
+        let size = match kind {
+            1 | SHORT | LONG => type_size(kind).unwrap_or(1),
+            _ => return Err(Error::Invalid("TIFF tag type")),
+        };
+        let values = match count * size {
+            0..=4 => slice(entry, 8, count * size)?,
+            len => slice(data, order.u32(entry, 8)? as usize, len)?,
+        };
+
+        let mut result = Vec::with_capacity(count);
+        for idx in 0..count {
+            result.push(match size {
+                1 => values[idx] as u32,
+                2 => order.u16(values, idx * 2)? as u32,
+                _ => order.u32(values, idx * 4)?,
+            });
+        }
+        Ok(result)
+    }
+
+    //Returns first value of rational entry, if valid.
+    fn rational(data: &[u8], order: Order, entry: &[u8]) -> Option<(u32, u32)> {
+        if order.u16(entry, 2).ok()? != RATIONAL || order.u32(entry, 4).ok()? == 0 {
+            return None;
+        }
+
+        let offset = order.u32(entry, 8).ok()? as usize;
+        Some((order.u32(data, offset).ok()?, order.u32(data, offset + 4).ok()?))
+    }
+
+    fn parse(data: &'a [u8], order: Order, offset: usize) -> Result<Self, Error> {
+        let count = order.u16(data, offset)? as usize;
+        let entries = slice(data, offset + 2, count * 12)?;
+
+        let mut width = None;
+        let mut height = None;
+        let mut bits = Vec::new();
+        let mut result = Self {
+            width: 0,
+            height: 0,
+            bits_per_sample: 1,
+            samples_per_pixel: 1,
+            compression: COMPRESSION_NONE,
+            photometric: u16::MAX,
+            resolution: None,
+            data,
+            order,
+            rows_per_strip: u32::MAX,
+            strip_offsets: Vec::new(),
+            strip_byte_counts: Vec::new(),
+            planar: 1,
+            predictor: 1,
+            color_map: Vec::new(),
+            alpha: None,
+        };
+        let mut extra_samples = Vec::new();
+        let mut resolution_unit = RESOLUTION_UNIT_INCH;
+        let mut x_resolution = None;
+        let mut y_resolution = None;
+
+        for entry in entries.chunks_exact(12) {
+            let single = |values: Vec<u32>| values.first().copied().ok_or(Error::Invalid("TIFF tag without value"));
+            let short = |values: Vec<u32>| single(values).and_then(|value| match value {
+                value if value <= u16::MAX as u32 => Ok(value as u16),
+                _ => Err(Error::Invalid("TIFF tag value")),
+            });
+
+            match order.u16(entry, 0)? {
+                IMAGE_WIDTH => width = Some(single(Self::values(data, order, entry)?)?),
+                IMAGE_LENGTH => height = Some(single(Self::values(data, order, entry)?)?),
+                BITS_PER_SAMPLE => bits = Self::values(data, order, entry)?,
+                COMPRESSION => result.compression = short(Self::values(data, order, entry)?)?,
+                PHOTOMETRIC => result.photometric = short(Self::values(data, order, entry)?)?,
+                FILL_ORDER => match single(Self::values(data, order, entry)?)? {
+                    1 => (),
+                    _ => return Err(Error::Unsupported("TIFF fill order")),
+                },
+                STRIP_OFFSETS => result.strip_offsets = Self::values(data, order, entry)?,
+                SAMPLES_PER_PIXEL => result.samples_per_pixel = short(Self::values(data, order, entry)?)?,
+                ROWS_PER_STRIP => result.rows_per_strip = single(Self::values(data, order, entry)?)?,
+                STRIP_BYTE_COUNTS => result.strip_byte_counts = Self::values(data, order, entry)?,
+                X_RESOLUTION => x_resolution = Self::rational(data, order, entry),
+                Y_RESOLUTION => y_resolution = Self::rational(data, order, entry),
+                RESOLUTION_UNIT => resolution_unit = short(Self::values(data, order, entry)?)?,
+                PLANAR_CONFIGURATION => result.planar = short(Self::values(data, order, entry)?)?,
+                PREDICTOR => result.predictor = short(Self::values(data, order, entry)?)?,
+                COLOR_MAP => result.color_map = Self::values(data, order, entry)?,
+                TILE_WIDTH => return Err(Error::Unsupported("tiled TIFF")),
+                EXTRA_SAMPLES => extra_samples = Self::values(data, order, entry)?,
+                _ => (),
+            }
+        }
+
+        result.width = width.ok_or(Error::Invalid("missing TIFF width"))?;
+        if let (Some(x), Some(y)) = (x_resolution, y_resolution) {
+            result.resolution = resolution(resolution_unit, x, y);
+        }
+        result.height = height.ok_or(Error::Invalid("missing TIFF height"))?;
+        if result.photometric == u16::MAX {
+            return Err(Error::Invalid("missing TIFF photometric interpretation"));
+        }
+        if let Some(&first) = bits.first() {
+            if bits.iter().any(|value| *value != first) {
+                return Err(Error::Unsupported("TIFF samples of different size"));
+            }
+            result.bits_per_sample = match first {
+                value if value <= u16::MAX as u32 => value as u16,
+                _ => return Err(Error::Invalid("TIFF bits per sample")),
+            };
+        }
+
+        let color_samples: u16 = match result.photometric {
+            PHOTOMETRIC_RGB => 3,
+            _ => 1,
+        };
+        result.alpha = match extra_samples.first().copied() {
+            Some(1) if result.samples_per_pixel > color_samples => Some((color_samples as usize, true)),
+            Some(2) if result.samples_per_pixel > color_samples => Some((color_samples as usize, false)),
+            _ => None,
+        };
+
+        Ok(result)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::Invalid("TIFF dimensions"));
+        }
+        if self.planar != 1 {
+            return Err(Error::Unsupported("TIFF planar configuration"));
+        }
+        match self.compression {
+            COMPRESSION_NONE | COMPRESSION_PACKBITS | COMPRESSION_LZW => (),
+            _ => return Err(Error::Unsupported("TIFF compression")),
+        }
+        match (self.predictor, self.bits_per_sample) {
+            (1, _) | (2, 8) | (2, 16) => (),
+            _ => return Err(Error::Unsupported("TIFF predictor")),
+        }
+
+        let bits = self.bits_per_sample;
+        let samples = self.samples_per_pixel;
+        let valid = match self.photometric {
+            PHOTOMETRIC_WHITE_IS_ZERO | PHOTOMETRIC_BLACK_IS_ZERO => samples >= 1 && matches!(bits, 1 | 2 | 4 | 8 | 16),
+            PHOTOMETRIC_RGB => samples >= 3 && matches!(bits, 8 | 16),
+            PHOTOMETRIC_PALETTE => {
+                samples >= 1 && matches!(bits, 1 | 2 | 4 | 8) && self.color_map.len() == 3 << bits
+            },
+            _ => return Err(Error::Unsupported("TIFF photometric interpretation")),
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(Error::Unsupported("TIFF bits or samples per pixel")),
+        }
+    }
+
+    //Returns sample at `index` within row, scaled to 8 bits unless it is palette index.
+    fn sample(&self, row: &[u8], index: usize) -> u8 {
+        match self.bits_per_sample {
+            8 => row[index],
+            16 => match self.order.big_endian {
+                true => row[index * 2],
+                false => row[index * 2 + 1],
+            },
+            bits => {
+                let bit = index * bits as usize;
+                let value = (row[bit / 8] >> (8 - bits as usize - bit % 8)) & ((1 << bits) - 1);
+                match self.photometric {
+                    PHOTOMETRIC_PALETTE => value,
+                    _ => (value as u32 * 255 / ((1 << bits) - 1)) as u8,
+                }
+            },
+        }
+    }
+
+    fn undo_predictor(&self, row: &mut [u8]) {
+        let samples = self.samples_per_pixel as usize;
+        match self.bits_per_sample {
+            8 => for idx in samples..row.len() {
+                row[idx] = row[idx].wrapping_add(row[idx - samples]);
+            },
+            _ => for idx in samples..row.len() / 2 {
+                let value = |row: &[u8], idx: usize| match self.order.big_endian {
+                    true => u16::from_be_bytes([row[idx * 2], row[idx * 2 + 1]]),
+                    false => u16::from_le_bytes([row[idx * 2], row[idx * 2 + 1]]),
+                };
+                let sum = value(row, idx).wrapping_add(value(row, idx - samples));
+                let bytes = match self.order.big_endian {
+                    true => sum.to_be_bytes(),
+                    false => sum.to_le_bytes(),
+                };
+                row[idx * 2..idx * 2 + 2].copy_from_slice(&bytes);
+            },
+        }
+    }
+
+    fn push_row(&self, row: &[u8], out: &mut Vec<u8>) {
+        let samples = self.samples_per_pixel as usize;
+        for x in 0..self.width as usize {
+            let pixel = x * samples;
+            let alpha = match self.alpha {
+                Some((idx, _)) => self.sample(row, pixel + idx),
+                None => 255,
+            };
+            let mut rgba = match self.photometric {
+                PHOTOMETRIC_WHITE_IS_ZERO => {
+                    let gray = 255 - self.sample(row, pixel);
+                    [gray, gray, gray, alpha]
+                },
+                PHOTOMETRIC_BLACK_IS_ZERO => {
+                    let gray = self.sample(row, pixel);
+                    [gray, gray, gray, alpha]
+                },
+                PHOTOMETRIC_PALETTE => {
+                    let index = self.sample(row, pixel) as usize;
+                    let colors = self.color_map.len() / 3;
+                    let color = |channel: usize| (self.color_map[channel * colors + index] >> 8) as u8;
+                    [color(0), color(1), color(2), alpha]
+                },
+                _ => [self.sample(row, pixel), self.sample(row, pixel + 1), self.sample(row, pixel + 2), alpha],
+            };
+
+            if let Some((_, true)) = self.alpha {
+                if alpha > 0 {
+                    for channel in &mut rgba[..3] {
+                        *channel = (*channel as u32 * 255 / alpha as u32).min(255) as u8;
+                    }
+                }
+            }
+            out.extend_from_slice(&rgba);
+        }
+    }
+
+    ///Decodes pixels as top-down rows of RGBA with 8 bits per channel, appending them to `out`.
+    ///
+    ///Premultiplied alpha is converted to straight alpha, while 16 bit samples are reduced to 8 bits.
+    ///Fails if compression or color space is unsupported, or if strips are truncated.
+    ///
+    ///Returns number of appended bytes.
+    pub fn to_rgba(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        self.validate()?;
+
+        let pixel_bits = self.bits_per_sample as u64 * self.samples_per_pixel as u64;
+        let row_size = whole_bytes(self.width as u64 * pixel_bits);
+        let size = (self.width as u64).checked_mul(self.height as u64).and_then(|pixels| pixels.checked_mul(4));
+        let (row_size, size) = match size {
+            Some(size) if size <= isize::MAX as u64 && row_size <= isize::MAX as u64 => (row_size as usize, size as usize),
+            _ => return Err(Error::Invalid("TIFF size overflows")),
+        };
+
+        let rows_per_strip = self.rows_per_strip.clamp(1, self.height);
+        let strips = match self.height % rows_per_strip {
+            0 => self.height / rows_per_strip,
+            _ => self.height / rows_per_strip + 1,
+        } as usize;
+        if self.strip_offsets.len() < strips || self.strip_byte_counts.len() < strips {
+            return Err(Error::Invalid("TIFF strip count"));
+        }
+
+        let start = out.len();
+        let mut strip = Vec::new();
+        let mut rows_left = self.height;
+        for idx in 0..strips {
+            let rows = rows_left.min(rows_per_strip);
+            rows_left -= rows;
+            let expected = rows as usize * row_size;
+
+            let result = slice(self.data, self.strip_offsets[idx] as usize, self.strip_byte_counts[idx] as usize).and_then(|data| {
+                strip.clear();
+                match self.compression {
+                    COMPRESSION_PACKBITS => unpack_bits(data, &mut strip, expected),
+                    COMPRESSION_LZW => lzw_decode(data, &mut strip, expected),
+                    _ => {
+                        strip.extend_from_slice(&data[..data.len().min(expected)]);
+                        Ok(())
+                    }
+                }
+            });
+            if let Err(error) = result {
+                out.truncate(start);
+                return Err(error);
+            }
+            if strip.len() < expected {
+                out.truncate(start);
+                return Err(Error::Truncated);
+            }
+
+            if idx == 0 {
+                out.reserve(size);
+            }
+            for row in strip.chunks_exact_mut(row_size) {
+                if self.predictor == 2 {
+                    self.undo_predictor(row);
+                }
+                self.push_row(row, out);
+            }
+        }
+
+        Ok(out.len() - start)
+    }
+
+    ///Converts page into 32 bits per pixel top-down BMP file, appending it to `out`.
+    ///
+    ///Returns number of written bytes.
+    pub fn write_bitmap(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        if self.width > i32::MAX as u32 || self.height > i32::MAX as u32 {
+            return Err(Error::Invalid("TIFF dimensions"));
+        }
+
+        let mut pixels = Vec::new();
+        self.to_rgba(&mut pixels)?;
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+
+        let mut header = InfoHeader::new(self.width as i32, -(self.height as i32), 32)?;
+        if let Some(resolution) = self.resolution {
+            let (x, y) = resolution.pels_per_meter();
+            header.x_pels_per_meter = x;
+            header.y_pels_per_meter = y;
+        }
+        let start = out.len();
+        bmp::write_file(out, &header, &[], &pixels)?;
+        Ok(out.len() - start)
+    }
+}
+
+//Decodes PackBits until `len` bytes are produced or input ends.
+fn unpack_bits(mut data: &[u8], out: &mut Vec<u8>, len: usize) -> Result<(), Error> {
+    while out.len() < len {
+        let (&header, rest) = match data.split_first() {
+            Some(split) => split,
+            None => break,
+        };
+
+        match header as i8 {
+            -128 => data = rest,
+            count @ 0..=127 => {
+                let literal = slice(rest, 0, count as usize + 1)?;
+                out.extend_from_slice(literal);
+                data = &rest[literal.len()..];
+            },
+            count => {
+                let byte = *rest.first().ok_or(Error::Truncated)?;
+                out.resize(out.len() + (1 - count as isize) as usize, byte);
+                data = &rest[1..];
+            },
+        }
+    }
+
+    out.truncate(len);
+    Ok(())
+}
+
+const LZW_CLEAR: u16 = 256;
+const LZW_END: u16 = 257;
+const LZW_MAX_CODES: usize = 4096;
+
+#[derive(Clone, Copy)]
+struct LzwEntry {
+    prefix: u16,
+    last: u8,
+    first: u8,
+    len: u16,
+}
+
+//Decodes TIFF variant of LZW, i.e. codes of 9 to 12 bits in MSB order with early code size
+//change, until `len` bytes are produced or input ends.
+fn lzw_decode(data: &[u8], out: &mut Vec<u8>, len: usize) -> Result<(), Error> {
+    let mut table = Vec::with_capacity(LZW_MAX_CODES);
+    table.extend((0..258).map(|code| LzwEntry {
+        prefix: 0,
+        last: code as u8,
+        first: code as u8,
+        len: 1,
+    }));
+
+    let mut bits = 0u32;
+    let mut bits_len = 0;
+    let mut pos = 0;
+    let mut width = 9;
+    let mut previous: Option<u16> = None;
+
+    while out.len() < len {
+        while bits_len < width {
+            match data.get(pos) {
+                Some(&byte) => bits = (bits << 8) | byte as u32,
+                None => {
+                    out.truncate(len);
+                    return Ok(());
+                },
+            }
+            pos += 1;
+            bits_len += 8;
+        }
+        let code = ((bits >> (bits_len - width)) & ((1 << width) - 1)) as u16;
+        bits_len -= width;
+
+        match code {
+            LZW_CLEAR => {
+                table.truncate(258);
+                width = 9;
+                previous = None;
+                continue;
+            },
+            LZW_END => break,
+            _ => (),
+        }
+
+        let first = match (table.get(code as usize), previous) {
+            (Some(entry), _) => entry.first,
+            (None, Some(previous)) if code as usize == table.len() => table[previous as usize].first,
+            _ => return Err(Error::Invalid("LZW code")),
+        };
+        if let Some(previous) = previous {
+            if table.len() < LZW_MAX_CODES {
+                let entry = table[previous as usize];
+                table.push(LzwEntry {
+                    prefix: previous,
+                    last: first,
+                    first: entry.first,
+                    len: entry.len + 1,
+                });
+            }
+        }
+
+        let entry = table[code as usize];
+        let end = out.len() + entry.len as usize;
+        out.resize(end, 0);
+        let mut entry_code = code;
+        for byte in out[end - entry.len as usize..].iter_mut().rev() {
+            let entry = table[entry_code as usize];
+            *byte = entry.last;
+            entry_code = entry.prefix;
+        }
+
+        previous = Some(code);
+        width = match table.len() + 1 {
+            len if len >= 2048 => 12,
+            len if len >= 1024 => 11,
+            len if len >= 512 => 10,
+            _ => 9,
+        };
+    }
+
+    out.truncate(len);
+    Ok(())
+}
+
+///Converts resolution tags, where `x` and `y` are rationals of numerator and denominator, into
+///resolution.
+///
+///Returns `None` if unit is not absolute or if resolution is zero or invalid.
+pub fn resolution(unit: u16, x: (u32, u32), y: (u32, u32)) -> Option<Resolution> {
+    let convert = |(numerator, denominator): (u32, u32)| {
+        //Pixels per meter, rounded to nearest integer
+        let (numerator, denominator) = match unit {
+            RESOLUTION_UNIT_INCH => (numerator as u64 * 10000, denominator as u64 * 254),
+            RESOLUTION_UNIT_CENTIMETER => (numerator as u64 * 100, denominator as u64),
+            _ => return None,
+        };
+        match denominator {
+            0 => None,
+            _ => match (numerator + denominator / 2) / denominator {
+                value @ 1..=0xFFFF_FFFF => Some(value as u32),
+                _ => None,
+            },
+        }
+    };
+
+    Some(Resolution {
+        x: convert(x)?,
+        y: convert(y)?,
+    })
+}
+
+///Converts resolution into resolution unit and rationals for `XResolution` and `YResolution`.
+///
+///Resolution is written in whole pixels per inch if it is exact, and in pixels per centimeter
+///otherwise.
+pub fn resolution_tags(resolution: Resolution) -> (u16, (u32, u32), (u32, u32)) {
+    let (x, y) = resolution.dpi();
+    match Resolution::from_dpi(x, y) == resolution {
+        true => (RESOLUTION_UNIT_INCH, (x, 1), (y, 1)),
+        false => (RESOLUTION_UNIT_CENTIMETER, (resolution.x, 100), (resolution.y, 100)),
+    }
+}
+
+fn write_entry(out: &mut Vec<u8>, tag: u16, kind: u16, count: u32, value: u32) {
+    out.extend_from_slice(&tag.to_le_bytes());
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    match (kind, count) {
+        //Single short is stored in first half of value
+        (SHORT, 1) => {
+            out.extend_from_slice(&(value as u16).to_le_bytes());
+            out.extend_from_slice(&[0, 0]);
+        },
+        _ => out.extend_from_slice(&value.to_le_bytes()),
+    }
+}
+
+fn pad(out: &mut Vec<u8>, start: usize) {
+    if (out.len() - start) % 2 == 1 {
+        out.push(0);
+    }
+}
+
+///Writes BMP files as pages of little endian TIFF, appending it to `out`.
+///
+///Pages are uncompressed RGB, or RGBA with unassociated alpha if any pixel is not opaque.
+///Fails if there are no bitmaps, if any of them is invalid or compressed, or if content exceeds 4 GB.
+///
+///Returns number of written bytes.
+pub fn write_bitmaps<T: AsRef<[u8]>>(out: &mut Vec<u8>, bitmaps: &[T]) -> Result<usize, Error> {
+    if bitmaps.is_empty() {
+        return Err(Error::Invalid("no bitmaps"));
+    }
+    let pages = bitmaps.len();
+    if pages > u16::MAX as usize {
+        return Err(Error::Invalid("too many pages"));
+    }
+
+    let start = out.len();
+    out.extend_from_slice(b"II");
+    out.extend_from_slice(&42u16.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    let mut next_offset = start + 4;
+
+    let mut rgba = Vec::new();
+    for (index, bitmap) in bitmaps.iter().enumerate() {
+        let dib = match bmp::parse_file(bitmap.as_ref()) {
+            Ok(dib) => dib,
+            Err(error) => {
+                out.truncate(start);
+                return Err(error);
+            }
+        };
+        rgba.clear();
+        if let Err(error) = dib.to_rgba(&mut rgba) {
+            out.truncate(start);
+            return Err(error);
+        }
+
+        let samples: u16 = match rgba.chunks_exact(4).all(|pixel| pixel[3] == 255) {
+            true => 3,
+            false => 4,
+        };
+        let pixels_offset = out.len() - start;
+        match samples {
+            3 => out.extend(rgba.chunks_exact(4).flat_map(|pixel| pixel[..3].iter().copied())),
+            _ => out.extend_from_slice(&rgba),
+        }
+        let pixels_size = out.len() - start - pixels_offset;
+        pad(out, start);
+
+        let bits_offset = out.len() - start;
+        for _ in 0..samples {
+            out.extend_from_slice(&8u16.to_le_bytes());
+        }
+
+        let resolution_offset = out.len() - start;
+        let resolution = dib.resolution().map(resolution_tags);
+        if let Some((_, x, y)) = resolution {
+            for value in &[x.0, x.1, y.0, y.1] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        let ifd_offset = out.len() - start;
+        if ifd_offset + 200 > u32::MAX as usize {
+            out.truncate(start);
+            return Err(Error::Invalid("TIFF size"));
+        }
+        out[next_offset..next_offset + 4].copy_from_slice(&(ifd_offset as u32).to_le_bytes());
+
+        let mut entries = 11;
+        if pages > 1 {
+            entries += 1;
+        }
+        if samples == 4 {
+            entries += 1;
+        }
+        if resolution.is_some() {
+            entries += 3;
+        }
+        out.extend_from_slice(&(entries as u16).to_le_bytes());
+        //Page of multi-page image
+        write_entry(out, NEW_SUBFILE_TYPE, LONG, 1, if pages > 1 { 2 } else { 0 });
+        write_entry(out, IMAGE_WIDTH, LONG, 1, dib.header.width as u32);
+        write_entry(out, IMAGE_LENGTH, LONG, 1, dib.header.abs_height());
+        write_entry(out, BITS_PER_SAMPLE, SHORT, samples as u32, bits_offset as u32);
+        write_entry(out, COMPRESSION, SHORT, 1, COMPRESSION_NONE as u32);
+        write_entry(out, PHOTOMETRIC, SHORT, 1, PHOTOMETRIC_RGB as u32);
+        write_entry(out, STRIP_OFFSETS, LONG, 1, pixels_offset as u32);
+        write_entry(out, SAMPLES_PER_PIXEL, SHORT, 1, samples as u32);
+        write_entry(out, ROWS_PER_STRIP, LONG, 1, dib.header.abs_height());
+        write_entry(out, STRIP_BYTE_COUNTS, LONG, 1, pixels_size as u32);
+        if resolution.is_some() {
+            write_entry(out, X_RESOLUTION, RATIONAL, 1, resolution_offset as u32);
+            write_entry(out, Y_RESOLUTION, RATIONAL, 1, resolution_offset as u32 + 8);
+        }
+        write_entry(out, PLANAR_CONFIGURATION, SHORT, 1, 1);
+        if let Some((unit, _, _)) = resolution {
+            write_entry(out, RESOLUTION_UNIT, SHORT, 1, unit as u32);
+        }
+        if pages > 1 {
+            //Page number and total number of pages
+            write_entry(out, PAGE_NUMBER, SHORT, 2, (index as u32) | ((pages as u32) << 16));
+        }
+        if samples == 4 {
+            //Unassociated alpha
+            write_entry(out, EXTRA_SAMPLES, SHORT, 1, 2);
+        }
+        next_offset = out.len();
+        out.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    Ok(out.len() - start)
+}
@@ -0,0 +1,67 @@
+//!URL validation for `UniformResourceLocatorW` and `UniformResourceLocator`.
+//!
+//!Validation is intentionally shallow: URL must be absolute (i.e. start with scheme) and must
+//!not contain whitespace or control characters, which is what browsers and Explorer expect.
+
+use super::Error;
+
+///Returns URL without surrounding whitespace and trailing null characters.
+pub fn trim(url: &str) -> &str {
+    url.trim_end_matches('\0').trim()
+}
+
+///Returns scheme of URL, if it is present and valid.
+pub fn scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_at(url.find(':')?);
+    let mut chars = scheme.chars();
+    match chars.next() {
+        Some(ch) if ch.is_ascii_alphabetic() => (),
+        _ => return None,
+    }
+
+    match chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.') {
+        true => Some(scheme),
+        false => None,
+    }
+}
+
+///Validates absolute URL.
+pub fn validate(url: &str) -> Result<(), Error> {
+    if url.is_empty() {
+        return Err(Error::Invalid("empty url"));
+    }
+
+    let scheme = match scheme(url) {
+        Some(scheme) => scheme,
+        None => return Err(Error::Invalid("url scheme")),
+    };
+    if url.len() == scheme.len() + 1 {
+        return Err(Error::Invalid("url without content"));
+    }
+    if url.chars().any(|ch| ch.is_whitespace() || ch.is_control()) {
+        return Err(Error::Invalid("url contains whitespace or control character"));
+    }
+
+    Ok(())
+}
+
+///Extracts URL from content of Internet Shortcut (i.e. `.url` file).
+///
+///URL is value of `URL` key within `[InternetShortcut]` section.
+pub fn from_shortcut(content: &str) -> Option<&str> {
+    let mut in_section = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+        } else if in_section {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if key.trim().eq_ignore_ascii_case("URL") => return Some(value.trim()),
+                _ => continue,
+            }
+        }
+    }
+
+    None
+}
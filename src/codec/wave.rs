@@ -0,0 +1,295 @@
+//!RIFF/WAVE codec, i.e. `CF_WAVE` and `CF_RIFF`.
+//!
+//!Content is `RIFF` chunk of `WAVE` form, containing sub-chunks, each being four character code,
+//!size and data padded to even size. Required are `fmt ` describing samples, followed by `data`
+//!with samples themselves. Optional `LIST` chunk of `INFO` type contains metadata.
+
+use super::{Error, slice, u16_le, u32_le};
+use super::riff::{self, Chunks};
+
+use alloc::vec::Vec;
+
+///`WAVE_FORMAT_PCM`
+pub const WAVE_FORMAT_PCM: u16 = 0x0001;
+///`WAVE_FORMAT_IEEE_FLOAT`
+pub const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+///`WAVE_FORMAT_EXTENSIBLE`
+pub const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+//Sub-format GUID of `WAVE_FORMAT_EXTENSIBLE` is format tag followed by this suffix.
+const SUBFORMAT_SUFFIX: [u8; 14] = [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Format of samples.
+pub enum SampleFormat {
+    ///Integer PCM, unsigned for 8 bits and signed otherwise.
+    Pcm,
+    ///IEEE floating point.
+    Float,
+    ///Other format, such as ADPCM, identified by its tag.
+    Other(u16),
+}
+
+impl SampleFormat {
+    #[inline]
+    fn from_tag(tag: u16) -> Self {
+        match tag {
+            WAVE_FORMAT_PCM => SampleFormat::Pcm,
+            WAVE_FORMAT_IEEE_FLOAT => SampleFormat::Float,
+            tag => SampleFormat::Other(tag),
+        }
+    }
+
+    #[inline]
+    fn tag(self) -> u16 {
+        match self {
+            SampleFormat::Pcm => WAVE_FORMAT_PCM,
+            SampleFormat::Float => WAVE_FORMAT_IEEE_FLOAT,
+            SampleFormat::Other(tag) => tag,
+        }
+    }
+}
+
+//Returns number of whole bytes, containing sample.
+#[inline]
+fn container_bytes(bits: u16) -> usize {
+    match bits % 8 {
+        0 => bits as usize / 8,
+        _ => bits as usize / 8 + 1,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Description of samples.
+pub struct Spec {
+    ///Format of samples.
+    pub format: SampleFormat,
+    ///Number of channels.
+    pub channels: u16,
+    ///Number of frames per second.
+    pub sample_rate: u32,
+    ///Size of sample in bits.
+    pub bits_per_sample: u16,
+}
+
+impl Spec {
+    #[inline]
+    ///Returns size of frame in bytes, i.e. one sample of each channel.
+    pub fn block_align(&self) -> usize {
+        self.channels as usize * container_bytes(self.bits_per_sample)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.channels == 0 || self.sample_rate == 0 || self.bits_per_sample == 0 {
+            return Err(Error::Invalid("wave format"));
+        }
+
+        match (self.format, self.bits_per_sample) {
+            (SampleFormat::Pcm, 1..=32) | (SampleFormat::Float, 32) | (SampleFormat::Float, 64) | (SampleFormat::Other(_), _) => Ok(()),
+            _ => Err(Error::Invalid("bits per sample")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+///Validated WAVE content.
+pub struct Wave<'a> {
+    ///Description of samples.
+    pub spec: Spec,
+    ///Size of frame in bytes.
+    pub block_align: u16,
+    ///Number of significant bits in sample, which can be less than container size.
+    pub valid_bits: u16,
+    ///Speaker positions, as in `SPEAKER_*`, if format is extensible.
+    pub channel_mask: Option<u32>,
+    ///Interleaved samples, as little endian values.
+    pub samples: &'a [u8],
+    info: &'a [u8],
+}
+
+impl<'a> Wave<'a> {
+    #[inline]
+    ///Returns number of frames.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.block_align as usize
+    }
+
+    #[inline]
+    ///Returns iterator over metadata of `LIST` `INFO` chunk, as four character code and value.
+    pub fn info(&self) -> Info<'a> {
+        Info {
+            data: self.info,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///Iterator over metadata of [Wave](struct.Wave.html), such as `INAM` (name) or `ISFT` (software).
+pub struct Info<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Info<'a> {
+    type Item = ([u8; 4], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ((id, value), rest) = riff::chunk(self.data).ok()?;
+        self.data = rest;
+        //Value is null terminated string
+        let len = value.iter().position(|byte| *byte == 0).unwrap_or(value.len());
+        Some((id, &value[..len]))
+    }
+}
+
+fn parse_fmt(data: &[u8]) -> Result<(Spec, u16, u16, Option<u32>), Error> {
+    let tag = u16_le(data, 0)?;
+    let channels = u16_le(data, 2)?;
+    let sample_rate = u32_le(data, 4)?;
+    let block_align = u16_le(data, 12)?;
+    let bits_per_sample = u16_le(data, 14)?;
+
+    let (format, valid_bits, channel_mask) = match tag {
+        WAVE_FORMAT_EXTENSIBLE => {
+            if u16_le(data, 16)? < 22 {
+                return Err(Error::Invalid("extensible format size"));
+            }
+            let sub_format = slice(data, 24, 16)?;
+            if sub_format[2..] != SUBFORMAT_SUFFIX {
+                return Err(Error::Unsupported("extensible sub-format"));
+            }
+            let valid_bits = match u16_le(data, 18)? {
+                0 => bits_per_sample,
+                valid_bits if valid_bits <= bits_per_sample => valid_bits,
+                _ => return Err(Error::Invalid("valid bits per sample")),
+            };
+            (SampleFormat::from_tag(u16_le(sub_format, 0)?), valid_bits, Some(u32_le(data, 20)?))
+        },
+        tag => (SampleFormat::from_tag(tag), bits_per_sample, None),
+    };
+
+    let spec = Spec {
+        format,
+        channels,
+        sample_rate,
+        bits_per_sample,
+    };
+    spec.validate()?;
+
+    match format {
+        SampleFormat::Other(_) if block_align > 0 => (),
+        SampleFormat::Other(_) => return Err(Error::Invalid("block align")),
+        _ if block_align as usize == spec.block_align() => (),
+        _ => return Err(Error::Invalid("block align")),
+    }
+
+    Ok((spec, block_align, valid_bits, channel_mask))
+}
+
+///Parses RIFF/WAVE content.
+///
+///Fails if `fmt ` chunk is missing or inconsistent, or if `data` chunk is missing.
+///Samples not forming complete frame at the end are ignored.
+pub fn parse(data: &[u8]) -> Result<Wave<'_>, Error> {
+    let ((id, content), _) = riff::chunk(data)?;
+    if id != *b"RIFF" {
+        return Err(Error::Invalid("not RIFF"));
+    }
+    if slice(content, 0, 4)? != b"WAVE" {
+        return Err(Error::Unsupported("RIFF form"));
+    }
+
+    let mut fmt = None;
+    let mut samples = None;
+    let mut info: &[u8] = &[];
+    for chunk in Chunks::new(&content[4..]) {
+        let (id, content) = chunk?;
+        match &id {
+            b"fmt " => fmt = Some(parse_fmt(content)?),
+            b"data" => match fmt {
+                Some(_) => samples = Some(content),
+                None => return Err(Error::Invalid("data precedes fmt chunk")),
+            },
+            b"LIST" if content.starts_with(b"INFO") => info = &content[4..],
+            _ => (),
+        }
+    }
+
+    let (spec, block_align, valid_bits, channel_mask) = fmt.ok_or(Error::Invalid("missing fmt chunk"))?;
+    let samples = samples.ok_or(Error::Invalid("missing data chunk"))?;
+    let samples = &samples[..samples.len() - samples.len() % block_align as usize];
+
+    Ok(Wave {
+        spec,
+        block_align,
+        valid_bits,
+        channel_mask,
+        samples,
+        info,
+    })
+}
+
+///Writes RIFF/WAVE content with `samples` described by `spec`.
+///
+///`WAVE_FORMAT_EXTENSIBLE` is used for more than two channels, more than 16 bits per sample or
+///bits per sample not filling whole bytes.
+///Fails if `spec` is invalid, its format is `Other`, or `samples` is not whole number of frames.
+///
+///Returns number of written bytes.
+pub fn write(out: &mut Vec<u8>, spec: &Spec, samples: &[u8]) -> Result<usize, Error> {
+    spec.validate()?;
+    if let SampleFormat::Other(_) = spec.format {
+        return Err(Error::Unsupported("sample format"));
+    }
+
+    let block_align = spec.block_align();
+    if block_align > u16::MAX as usize {
+        return Err(Error::Invalid("block align"));
+    }
+    let incomplete = samples.len() % block_align;
+    if incomplete > 0 {
+        return Err(Error::Invalid("samples are not whole number of frames"));
+    }
+
+    let container_bits = container_bytes(spec.bits_per_sample) as u16 * 8;
+    let extensible = spec.channels > 2 || spec.bits_per_sample > 16 || spec.bits_per_sample != container_bits;
+    let fmt_size = if extensible { 40 } else { 16 };
+    let padding = samples.len() % 2;
+    let riff_size = (4 + 8 + fmt_size + 8 + samples.len() + padding) as u64;
+    if riff_size > u32::MAX as u64 {
+        return Err(Error::Invalid("samples are too large"));
+    }
+
+    let start = out.len();
+    out.reserve(riff_size as usize + 8);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(riff_size as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&(fmt_size as u32).to_le_bytes());
+    let tag = if extensible { WAVE_FORMAT_EXTENSIBLE } else { spec.format.tag() };
+    out.extend_from_slice(&tag.to_le_bytes());
+    out.extend_from_slice(&spec.channels.to_le_bytes());
+    out.extend_from_slice(&spec.sample_rate.to_le_bytes());
+    out.extend_from_slice(&((spec.sample_rate as u64 * block_align as u64) as u32).to_le_bytes());
+    out.extend_from_slice(&(block_align as u16).to_le_bytes());
+    //Container size, as valid bits are specified separately.
+    out.extend_from_slice(&container_bits.to_le_bytes());
+    if extensible {
+        out.extend_from_slice(&22u16.to_le_bytes());
+        out.extend_from_slice(&spec.bits_per_sample.to_le_bytes());
+        //Channels are not assigned to speakers
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&spec.format.tag().to_le_bytes());
+        out.extend_from_slice(&SUBFORMAT_SUFFIX);
+    }
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    out.extend_from_slice(samples);
+    if padding > 0 {
+        out.push(0);
+    }
+
+    Ok(out.len() - start)
+}
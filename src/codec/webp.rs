@@ -0,0 +1,159 @@
+//!WebP stream validator.
+//!
+//!RIFF chunks are walked and headers of image data are checked without decoding it, so that
+//!stream, including animation, can be passed through unchanged.
+
+use super::{Error, slice, u32_le};
+use super::riff::Chunks;
+
+///Lossy image data chunk.
+pub const VP8: [u8; 4] = *b"VP8 ";
+///Lossless image data chunk.
+pub const VP8L: [u8; 4] = *b"VP8L";
+///Extended format chunk.
+pub const VP8X: [u8; 4] = *b"VP8X";
+///Animation parameters chunk.
+pub const ANIM: [u8; 4] = *b"ANIM";
+///Animation frame chunk.
+pub const ANMF: [u8; 4] = *b"ANMF";
+
+const VP8X_ANIMATION: u8 = 0x02;
+const VP8X_ALPHA: u8 = 0x10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Validated WebP stream.
+pub struct WebP {
+    ///Width of canvas in pixels.
+    pub width: u32,
+    ///Height of canvas in pixels.
+    pub height: u32,
+    ///Whether stream is animated.
+    pub animated: bool,
+    ///Whether image has alpha channel.
+    pub alpha: bool,
+    ///Whether image data is lossless.
+    pub lossless: bool,
+    ///Size of RIFF container, excluding trailing data.
+    pub size: usize,
+}
+
+#[inline]
+fn u24_le(data: &[u8], offset: usize) -> Result<u32, Error> {
+    let bytes = slice(data, offset, 3)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+}
+
+//Parses header of lossy image data as dimensions.
+fn vp8(data: &[u8]) -> Result<(u32, u32), Error> {
+    let header = slice(data, 0, 10)?;
+    //Frame tag with key frame flag cleared, followed by start code
+    if header[0] & 1 != 0 {
+        return Err(Error::Invalid("VP8 key frame"));
+    }
+    if header[3..6] != [0x9D, 0x01, 0x2A] {
+        return Err(Error::Invalid("VP8 start code"));
+    }
+
+    let width = u16::from_le_bytes([header[6], header[7]]) & 0x3FFF;
+    let height = u16::from_le_bytes([header[8], header[9]]) & 0x3FFF;
+    match width > 0 && height > 0 {
+        true => Ok((width as u32, height as u32)),
+        false => Err(Error::Invalid("VP8 dimensions")),
+    }
+}
+
+//Parses header of lossless image data as dimensions and alpha.
+fn vp8l(data: &[u8]) -> Result<(u32, u32, bool), Error> {
+    if slice(data, 0, 1)?[0] != 0x2F {
+        return Err(Error::Invalid("VP8L signature"));
+    }
+
+    let bits = u32_le(data, 1)?;
+    if bits >> 29 != 0 {
+        return Err(Error::Unsupported("VP8L version"));
+    }
+    Ok(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1, bits & (1 << 28) != 0))
+}
+
+///Validates WebP stream, walking chunks of its RIFF container.
+///
+///Simple format must start with image data, while extended format must contain either image
+///data or animation frames, as indicated by its flags.
+pub fn parse(data: &[u8]) -> Result<WebP, Error> {
+    let header = slice(data, 0, 12)?;
+    if &header[..4] != b"RIFF" || &header[8..] != b"WEBP" {
+        return Err(Error::Invalid("WebP signature"));
+    }
+    let size = (u32_le(header, 4)? as usize).checked_add(8).ok_or(Error::Truncated)?;
+    let body = slice(data, 12, size.checked_sub(12).ok_or(Error::Invalid("RIFF size"))?)?;
+
+    let mut result = None;
+    let mut extended = None;
+    let mut has_image = false;
+    let mut first = true;
+    for chunk in Chunks::new(body) {
+        let (kind, chunk) = chunk?;
+        match (kind, first) {
+            (VP8X, true) => {
+                let flags = slice(chunk, 0, 10)?[0];
+                extended = Some(flags);
+                result = Some(WebP {
+                    width: u24_le(chunk, 4)? + 1,
+                    height: u24_le(chunk, 7)? + 1,
+                    animated: flags & VP8X_ANIMATION != 0,
+                    alpha: flags & VP8X_ALPHA != 0,
+                    lossless: false,
+                    size,
+                });
+            },
+            (VP8, first) => {
+                let (width, height) = vp8(chunk)?;
+                match (first, result.as_mut()) {
+                    (true, _) => result = Some(WebP {
+                        width,
+                        height,
+                        animated: false,
+                        alpha: false,
+                        lossless: false,
+                        size,
+                    }),
+                    (false, Some(webp)) if !webp.animated => has_image = true,
+                    _ => return Err(Error::Invalid("WebP chunk")),
+                }
+            },
+            (VP8L, first) => {
+                let (width, height, alpha) = vp8l(chunk)?;
+                match (first, result.as_mut()) {
+                    (true, _) => result = Some(WebP {
+                        width,
+                        height,
+                        animated: false,
+                        alpha,
+                        lossless: true,
+                        size,
+                    }),
+                    (false, Some(webp)) if !webp.animated => {
+                        webp.lossless = true;
+                        has_image = true;
+                    },
+                    _ => return Err(Error::Invalid("WebP chunk")),
+                }
+            },
+            (ANMF, false) if matches!(extended, Some(flags) if flags & VP8X_ANIMATION != 0) => has_image = true,
+            (_, true) => return Err(Error::Invalid("WebP chunk")),
+            _ => (),
+        }
+
+        first = false;
+
+        //Simple format consists of image data only
+        if extended.is_none() {
+            break;
+        }
+    }
+
+    match result {
+        Some(webp) if extended.is_none() || has_image => Ok(webp),
+        _ => Err(Error::Invalid("WebP without image")),
+    }
+}
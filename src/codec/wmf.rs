@@ -0,0 +1,355 @@
+//!Windows metafile (WMF) codec, i.e. metafile of `CF_METAFILEPICT`.
+//!
+//!Content is `METAHEADER` followed by records, each starting with size in words and function,
+//!with `META_EOF` last. Files are commonly prefixed with Aldus placeable header, specifying
+//!bounds and resolution, which is not part of metafile itself and is never used by clipboard.
+
+use super::{Error, Point, Rect, slice, u16_le, u32_le};
+
+use alloc::vec::Vec;
+
+///Key of Aldus placeable header.
+pub const PLACEABLE_KEY: u32 = 0x9AC6_CDD7;
+///Size of Aldus placeable header.
+pub const PLACEABLE_SIZE: usize = 22;
+///Size of `METAHEADER`.
+pub const HEADER_SIZE: usize = 18;
+
+///`META_EOF`
+pub const META_EOF: u16 = 0x0000;
+///`META_SETWINDOWORG`
+pub const META_SETWINDOWORG: u16 = 0x020B;
+///`META_SETWINDOWEXT`
+pub const META_SETWINDOWEXT: u16 = 0x020C;
+
+///Logical unit is pixel.
+pub const MM_TEXT: i32 = 1;
+///Logical unit is 0.1 millimeter.
+pub const MM_LOMETRIC: i32 = 2;
+///Logical unit is 0.01 millimeter.
+pub const MM_HIMETRIC: i32 = 3;
+///Logical unit is 0.01 inch.
+pub const MM_LOENGLISH: i32 = 4;
+///Logical unit is 0.001 inch.
+pub const MM_HIENGLISH: i32 = 5;
+///Logical unit is 1/1440 inch.
+pub const MM_TWIPS: i32 = 6;
+///Logical units are specified by metafile, with equally scaled axes.
+pub const MM_ISOTROPIC: i32 = 7;
+///Logical units are specified by metafile.
+pub const MM_ANISOTROPIC: i32 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Aldus placeable header.
+pub struct Placeable {
+    ///Bounds of picture in logical units of metafile.
+    pub bounds: Rect,
+    ///Number of logical units per inch.
+    pub inch: u16,
+}
+
+impl Placeable {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let header = slice(data, 0, PLACEABLE_SIZE)?;
+        let coordinate = |offset| u16_le(header, offset).map(|value| value as i16 as i32);
+
+        Ok(Self {
+            bounds: Rect {
+                left: coordinate(6)?,
+                top: coordinate(8)?,
+                right: coordinate(10)?,
+                bottom: coordinate(12)?,
+            },
+            inch: u16_le(header, 14)?,
+        })
+    }
+
+    ///Writes header, including its checksum.
+    ///
+    ///Fails if bounds do not fit into 16 bits or if `inch` is zero.
+    pub fn write(&self, out: &mut Vec<u8>) -> Result<(), Error> {
+        let bounds = [self.bounds.left, self.bounds.top, self.bounds.right, self.bounds.bottom];
+        if self.inch == 0 || bounds.iter().any(|value| *value < i16::MIN as i32 || *value > i16::MAX as i32) {
+            return Err(Error::Invalid("placeable header"));
+        }
+
+        let mut words = [0u16; 10];
+        words[0] = PLACEABLE_KEY as u16;
+        words[1] = (PLACEABLE_KEY >> 16) as u16;
+        for (word, value) in words[3..7].iter_mut().zip(bounds.iter()) {
+            *word = *value as i16 as u16;
+        }
+        words[7] = self.inch;
+
+        for word in words.iter() {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        let checksum = words.iter().fold(0, |checksum, word| checksum ^ word);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Metafile header, as in `METAHEADER`.
+pub struct Header {
+    ///Type of metafile: 1 for memory and 2 for disk.
+    pub kind: u16,
+    ///Version: `0x0100` or `0x0300`.
+    pub version: u16,
+    ///Size of metafile in bytes.
+    pub size: usize,
+    ///Maximum number of objects used simultaneously.
+    pub objects: u16,
+    ///Size of largest record in bytes.
+    pub max_record: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Record of metafile.
+pub struct Record<'a> {
+    ///Function, as in `META_*` values.
+    pub function: u16,
+    ///Parameters following size and function.
+    pub data: &'a [u8],
+}
+
+#[derive(Clone, Debug)]
+///Iterator over records of [Wmf](struct.Wmf.html).
+pub struct Records<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (record, rest) = record(self.data).ok()?;
+        self.data = rest;
+        Some(record)
+    }
+}
+
+//Splits record from rest of data.
+fn record(data: &[u8]) -> Result<(Record<'_>, &[u8]), Error> {
+    let size = (u32_le(data, 0)? as usize).checked_mul(2).ok_or(Error::Truncated)?;
+    if size < 6 {
+        return Err(Error::Invalid("record size"));
+    }
+    let record = slice(data, 0, size)?;
+    Ok((Record { function: u16_le(record, 4)?, data: &record[6..] }, &data[size..]))
+}
+
+#[derive(Clone, Copy, Debug)]
+///Validated metafile.
+pub struct Wmf<'a> {
+    ///Placeable header, if present.
+    pub placeable: Option<Placeable>,
+    ///Parsed header.
+    pub header: Header,
+    data: &'a [u8],
+}
+
+impl<'a> Wmf<'a> {
+    #[inline]
+    ///Returns metafile without placeable header and trailing bytes beyond size specified by header,
+    ///as expected by `SetMetaFileBitsEx`.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    #[inline]
+    ///Returns iterator over records, excluding header and including `META_EOF`.
+    pub fn records(&self) -> Records<'a> {
+        Records {
+            data: &self.data[HEADER_SIZE..],
+        }
+    }
+
+    ///Returns window, as last set by `META_SETWINDOWORG` and `META_SETWINDOWEXT`.
+    ///
+    ///Returns `None` if window extent is never set.
+    pub fn window(&self) -> Option<Rect> {
+        let mut origin = Point::default();
+        let mut extent = None;
+        for record in self.records() {
+            //Parameters are in reverse order, i.e. `y` first
+            let point = || -> Option<Point> {
+                Some(Point {
+                    x: u16_le(record.data, 2).ok()? as i16 as i32,
+                    y: u16_le(record.data, 0).ok()? as i16 as i32,
+                })
+            };
+            match record.function {
+                META_SETWINDOWORG => origin = point().unwrap_or(origin),
+                META_SETWINDOWEXT => extent = point().or(extent),
+                _ => (),
+            }
+        }
+
+        extent.map(|extent| Rect {
+            left: origin.x,
+            top: origin.y,
+            right: origin.x + extent.x,
+            bottom: origin.y + extent.y,
+        })
+    }
+}
+
+///Parses metafile, optionally prefixed with placeable header.
+///
+///Fails if header is invalid, if any record exceeds size specified by header or if last record is
+///not `META_EOF`. Checksum of placeable header is not verified, as it is commonly wrong.
+pub fn parse(data: &[u8]) -> Result<Wmf<'_>, Error> {
+    let (placeable, data) = match u32_le(data, 0)? {
+        PLACEABLE_KEY => (Some(Placeable::parse(data)?), &data[PLACEABLE_SIZE..]),
+        _ => (None, data),
+    };
+
+    let header = slice(data, 0, HEADER_SIZE)?;
+    let header = Header {
+        kind: u16_le(header, 0)?,
+        version: u16_le(header, 4)?,
+        size: (u32_le(header, 6)? as usize).checked_mul(2).ok_or(Error::Truncated)?,
+        objects: u16_le(header, 10)?,
+        max_record: (u32_le(header, 12)? as usize).checked_mul(2).ok_or(Error::Truncated)?,
+    };
+    match header.kind {
+        1 | 2 => (),
+        _ => return Err(Error::Invalid("metafile type")),
+    }
+    if u16_le(data, 2)? as usize * 2 != HEADER_SIZE {
+        return Err(Error::Invalid("metafile header size"));
+    }
+    match header.version {
+        0x0100 | 0x0300 => (),
+        _ => return Err(Error::Unsupported("metafile version")),
+    }
+    if header.size < HEADER_SIZE {
+        return Err(Error::Invalid("metafile size"));
+    }
+    let data = slice(data, 0, header.size)?;
+
+    let mut rest = &data[HEADER_SIZE..];
+    let mut last = None;
+    while !rest.is_empty() {
+        if last == Some(META_EOF) {
+            return Err(Error::Invalid("record after META_EOF"));
+        }
+
+        let (record, after) = record(rest)?;
+        if rest.len() - after.len() > header.max_record {
+            return Err(Error::Invalid("record exceeds maximum size"));
+        }
+        last = Some(record.function);
+        rest = after;
+    }
+    if last != Some(META_EOF) {
+        return Err(Error::Invalid("missing META_EOF"));
+    }
+
+    Ok(Wmf {
+        placeable,
+        header,
+        data,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+///Metafile picture, as in `METAFILEPICT`.
+pub struct Picture {
+    ///Mapping mode, one of `MM_*` values.
+    pub mapping_mode: i32,
+    ///Size of picture in units of mapping mode, except for `MM_ISOTROPIC` and `MM_ANISOTROPIC`
+    ///where it is suggested size in 0.01 millimeter units, if positive.
+    pub extent: Point,
+    ///Metafile without placeable header.
+    pub metafile: Vec<u8>,
+}
+
+impl Picture {
+    ///Creates picture from metafile with placeable header, as stored in `.wmf` files.
+    ///
+    ///Picture uses `MM_ANISOTROPIC`, with extent calculated from bounds and resolution.
+    pub fn from_placeable(data: &[u8]) -> Result<Self, Error> {
+        let wmf = parse(data)?;
+        let placeable = wmf.placeable.ok_or(Error::Invalid("missing placeable header"))?;
+        if placeable.inch == 0 {
+            return Err(Error::Invalid("placeable header"));
+        }
+
+        let bounds = placeable.bounds;
+        let himetric = |size: i32| (size.abs() as i64 * 2540 / placeable.inch as i64) as i32;
+        Ok(Self {
+            mapping_mode: MM_ANISOTROPIC,
+            extent: Point {
+                x: himetric(bounds.right - bounds.left),
+                y: himetric(bounds.bottom - bounds.top),
+            },
+            metafile: wmf.as_bytes().to_vec(),
+        })
+    }
+
+    ///Returns placeable header, describing picture.
+    ///
+    ///For `MM_ISOTROPIC` and `MM_ANISOTROPIC` bounds are window of metafile, with resolution
+    ///derived from extent or assumed to be 1440 units per inch if extent is not positive.
+    ///Otherwise bounds are extent, with resolution of mapping mode, assuming 96 pixels per inch.
+    pub fn placeable(&self) -> Result<Placeable, Error> {
+        let wmf = parse(&self.metafile)?;
+        if let Some(placeable) = wmf.placeable {
+            return Ok(placeable);
+        }
+
+        let (bounds, inch) = match self.mapping_mode {
+            MM_ISOTROPIC | MM_ANISOTROPIC => {
+                let window = wmf.window().ok_or(Error::Invalid("metafile without window extent"))?;
+                let width = (window.right - window.left).abs() as i64;
+                let inch = match self.extent.x {
+                    x if x > 0 && width > 0 => width * 2540 / x as i64,
+                    _ => 1440,
+                };
+                (window, inch)
+            },
+            mode => {
+                let inch = match mode {
+                    MM_TEXT => 96,
+                    MM_LOMETRIC => 254,
+                    MM_HIMETRIC => 2540,
+                    MM_LOENGLISH => 100,
+                    MM_HIENGLISH => 1000,
+                    MM_TWIPS => 1440,
+                    _ => return Err(Error::Unsupported("mapping mode")),
+                };
+                let bounds = Rect {
+                    left: 0,
+                    top: 0,
+                    right: self.extent.x,
+                    bottom: self.extent.y,
+                };
+                (bounds, inch)
+            },
+        };
+
+        match inch {
+            1..=0xFFFF => Ok(Placeable {
+                bounds,
+                inch: inch as u16,
+            }),
+            _ => Err(Error::Invalid("placeable header")),
+        }
+    }
+
+    ///Writes metafile prefixed with placeable header, as stored in `.wmf` files.
+    ///
+    ///Returns number of written bytes.
+    pub fn write_placeable(&self, out: &mut Vec<u8>) -> Result<usize, Error> {
+        let placeable = self.placeable()?;
+        let wmf = parse(&self.metafile)?;
+
+        let start = out.len();
+        placeable.write(out)?;
+        out.extend_from_slice(wmf.as_bytes());
+        Ok(out.len() - start)
+    }
+}
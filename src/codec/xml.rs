@@ -0,0 +1,264 @@
+//Minimal XML reader, sufficient for clipboard formats based on XML.
+//
+//Only predefined and numeric entities are supported: DTD is skipped without processing, so no
+//entity expansion can happen. Names are returned without namespace prefix.
+
+use super::Error;
+
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::borrow::Cow;
+
+pub(crate) enum Token<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<(&'a str, Cow<'a, str>)>,
+        //Element has no content, i.e. there is no matching end.
+        empty: bool,
+    },
+    End(&'a str),
+    Text(Cow<'a, str>),
+}
+
+//Strips namespace prefix.
+#[inline]
+pub(crate) fn local_name(name: &str) -> &str {
+    match name.rfind(':') {
+        Some(idx) => &name[idx + 1..],
+        None => name,
+    }
+}
+
+pub(crate) fn unescape(text: &str) -> Result<Cow<'_, str>, Error> {
+    if !text.contains('&') {
+        return Ok(Cow::Borrowed(text));
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let end = rest.find(';').ok_or(Error::Invalid("entity is not terminated"))?;
+        let entity = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let ch = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    return Err(Error::Unsupported("entity"));
+                };
+                code.and_then(core::char::from_u32).ok_or(Error::Invalid("character reference"))?
+            }
+        };
+        result.push(ch);
+    }
+    result.push_str(rest);
+
+    Ok(Cow::Owned(result))
+}
+
+#[inline]
+fn is_name_char(ch: char) -> bool {
+    !ch.is_whitespace() && !matches!(ch, '/' | '>' | '=' | '<' | '"' | '\'')
+}
+
+fn name(text: &str) -> Result<(&str, &str), Error> {
+    let len = text.find(|ch| !is_name_char(ch)).unwrap_or(text.len());
+    match len {
+        0 => Err(Error::Invalid("element or attribute name")),
+        len => Ok(text.split_at(len)),
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    rest: &'a str,
+    stack: Vec<&'a str>,
+    root_closed: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Self {
+            rest: text.strip_prefix('\u{FEFF}').unwrap_or(text),
+            stack: Vec::new(),
+            root_closed: false,
+        }
+    }
+
+    //Skips past `end`, failing if it is missing.
+    fn skip(&mut self, end: &str) -> Result<&'a str, Error> {
+        let idx = self.rest.find(end).ok_or(Error::Truncated)?;
+        let skipped = &self.rest[..idx];
+        self.rest = &self.rest[idx + end.len()..];
+        Ok(skipped)
+    }
+
+    fn start_tag(&mut self) -> Result<Token<'a>, Error> {
+        let (tag_name, mut rest) = name(self.rest)?;
+        let mut attributes = Vec::new();
+
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix("/>") {
+                self.rest = after;
+                return self.element_start(tag_name, attributes, true);
+            } else if let Some(after) = rest.strip_prefix('>') {
+                self.rest = after;
+                return self.element_start(tag_name, attributes, false);
+            } else if rest.is_empty() {
+                return Err(Error::Truncated);
+            }
+
+            let (attribute, after) = name(rest)?;
+            let after = after.trim_start().strip_prefix('=').ok_or(Error::Invalid("attribute without value"))?.trim_start();
+            let quote = match after.chars().next() {
+                Some(quote) if quote == '"' || quote == '\'' => quote,
+                Some(_) => return Err(Error::Invalid("attribute value is not quoted")),
+                None => return Err(Error::Truncated),
+            };
+            let after = &after[1..];
+            let end = after.find(quote).ok_or(Error::Truncated)?;
+            let value = &after[..end];
+            if value.contains('<') {
+                return Err(Error::Invalid("attribute value contains '<'"));
+            }
+
+            attributes.push((local_name(attribute), unescape(value)?));
+            rest = &after[end + 1..];
+        }
+    }
+
+    fn element_start(&mut self, name: &'a str, attributes: Vec<(&'a str, Cow<'a, str>)>, empty: bool) -> Result<Token<'a>, Error> {
+        if self.root_closed {
+            return Err(Error::Invalid("content after root element"));
+        }
+
+        if empty {
+            self.root_closed = self.stack.is_empty();
+        } else {
+            self.stack.push(name);
+        }
+
+        Ok(Token::Start {
+            name: local_name(name),
+            attributes,
+            empty,
+        })
+    }
+
+    fn end_tag(&mut self) -> Result<Token<'a>, Error> {
+        let content = self.skip(">")?;
+        let name = content.trim_end();
+        match self.stack.pop() {
+            Some(start) if start == name => {
+                self.root_closed = self.stack.is_empty();
+                Ok(Token::End(local_name(name)))
+            },
+            _ => Err(Error::Invalid("mismatched end tag")),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, Error> {
+        loop {
+            if self.rest.is_empty() {
+                return match self.root_closed {
+                    true => Ok(None),
+                    false => Err(Error::Truncated),
+                };
+            }
+
+            if let Some(rest) = self.rest.strip_prefix("<?") {
+                self.rest = rest;
+                self.skip("?>")?;
+            } else if let Some(rest) = self.rest.strip_prefix("<!--") {
+                self.rest = rest;
+                self.skip("-->")?;
+            } else if let Some(rest) = self.rest.strip_prefix("<![CDATA[") {
+                self.rest = rest;
+                let text = self.skip("]]>")?;
+                if self.stack.is_empty() {
+                    return Err(Error::Invalid("text outside of root element"));
+                }
+                return Ok(Some(Token::Text(Cow::Borrowed(text))));
+            } else if let Some(rest) = self.rest.strip_prefix("<!") {
+                //DOCTYPE, possibly with internal subset
+                self.rest = rest;
+                match (self.rest.find('['), self.rest.find('>')) {
+                    (Some(subset), Some(end)) if subset < end => {
+                        self.skip("]")?;
+                        self.skip(">")?;
+                    },
+                    _ => {
+                        self.skip(">")?;
+                    }
+                }
+            } else if let Some(rest) = self.rest.strip_prefix("</") {
+                self.rest = rest;
+                return self.end_tag().map(Some);
+            } else if let Some(rest) = self.rest.strip_prefix('<') {
+                self.rest = rest;
+                return self.start_tag().map(Some);
+            } else {
+                let len = self.rest.find('<').unwrap_or(self.rest.len());
+                let (text, rest) = self.rest.split_at(len);
+                self.rest = rest;
+
+                if self.stack.is_empty() {
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    return Err(Error::Invalid("text outside of root element"));
+                }
+                return Ok(Some(Token::Text(unescape(text)?)));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(token) => token.map(Ok),
+            Err(error) => {
+                //Stop after error
+                self.rest = "";
+                self.root_closed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+//Appends `text` escaped for use as content or attribute value.
+//
+//Fails on characters that are not allowed in XML.
+pub(crate) fn escape(out: &mut String, text: &str) -> Result<(), Error> {
+    for ch in text.chars() {
+        match ch {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' => out.push_str("&#9;"),
+            ch if (ch as u32) < 0x20 || ch == '\u{FFFE}' || ch == '\u{FFFF}' => return Err(Error::Invalid("character is not allowed in XML")),
+            ch => out.push(ch),
+        }
+    }
+
+    Ok(())
+}
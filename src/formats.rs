@@ -75,6 +75,51 @@ pub const CF_UNICODETEXT: u32 = winuser::CF_UNICODETEXT;
 ///Represents audio data in one of the standard wave formats.
 pub const CF_WAVE: u32 = winuser::CF_WAVE;
 
+
+///Name of registered format, presence of which makes clipboard monitors to ignore content.
+pub const EXCLUDE_CLIPBOARD_CONTENT_FROM_MONITOR_PROCESSING: &str = "ExcludeClipboardContentFromMonitorProcessing";
+///Name of registered format, which controls whether content can be stored in clipboard history.
+///
+///Content is excluded when format is set to DWORD `0`.
+pub const CAN_INCLUDE_IN_CLIPBOARD_HISTORY: &str = "CanIncludeInClipboardHistory";
+///Name of registered format, which controls whether content can be synced to cloud clipboard.
+///
+///Content is excluded when format is set to DWORD `0`.
+pub const CAN_UPLOAD_TO_CLOUD_CLIPBOARD: &str = "CanUploadToCloudClipboard";
+
+///Name of registered format, containing `FILEGROUPDESCRIPTORW` of virtual files.
+pub const FILE_GROUP_DESCRIPTOR_W: &str = "FileGroupDescriptorW";
+///Name of registered format, containing `FILEGROUPDESCRIPTORA` of virtual files.
+pub const FILE_GROUP_DESCRIPTOR: &str = "FileGroupDescriptor";
+///Name of registered format, containing content of virtual file by its index.
+pub const FILE_CONTENTS: &str = "FileContents";
+///Name of registered format, containing `CIDA` of shell items.
+pub const SHELL_IDLIST_ARRAY: &str = "Shell IDList Array";
+///Name of registered format, containing comma-separated values in system code page.
+pub const CSV: &str = "Csv";
+///Name of registered format, containing SpreadsheetML 2003 in UTF-8.
+pub const XML_SPREADSHEET: &str = "XML Spreadsheet";
+///Name of registered format, containing wide URL.
+pub const UNIFORM_RESOURCE_LOCATOR_W: &str = "UniformResourceLocatorW";
+///Name of registered format, containing URL in system code page.
+pub const UNIFORM_RESOURCE_LOCATOR: &str = "UniformResourceLocator";
+///Name of registered format, containing PNG stream.
+pub const PNG: &str = "PNG";
+///Name of registered format, containing JPEG stream.
+pub const JFIF: &str = "JFIF";
+///Name of registered format, containing JPEG stream, as used by browsers.
+pub const IMAGE_JPEG: &str = "image/jpeg";
+///Name of registered format, containing GIF stream.
+pub const GIF: &str = "GIF";
+///Name of registered format, containing GIF stream, as used by browsers.
+pub const IMAGE_GIF: &str = "image/gif";
+///Name of registered format, containing WebP stream.
+pub const IMAGE_WEBP: &str = "image/webp";
+///Name of registered format, containing SVG document.
+pub const SVG: &str = "SVG";
+///Name of registered format, containing SVG document, as used by browsers.
+pub const IMAGE_SVG_XML: &str = "image/svg+xml";
+
 ///Format to write/read from clipboard as raw bytes
 ///
 ///Has to be initialized with format `id`
@@ -94,6 +139,17 @@ impl Getter<alloc::vec::Vec<u8>> for RawData {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Getter<zeroize::Zeroizing<alloc::vec::Vec<u8>>> for RawData {
+    #[inline]
+    fn read_clipboard(&self, out: &mut zeroize::Zeroizing<alloc::vec::Vec<u8>>) -> SysResult<usize> {
+        let mut buffer = zeroize::Zeroizing::new(alloc::vec::Vec::new());
+        let result = crate::raw::get_vec(self.0, &mut buffer)?;
+        crate::utils::append_wiped(out, &buffer);
+        Ok(result)
+    }
+}
+
 ///Format to read/write unicode string.
 ///
 ///Refer to `Getter` and `Setter`
@@ -113,6 +169,28 @@ impl Getter<alloc::string::String> for Unicode {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Getter<zeroize::Zeroizing<alloc::vec::Vec<u8>>> for Unicode {
+    #[inline]
+    fn read_clipboard(&self, out: &mut zeroize::Zeroizing<alloc::vec::Vec<u8>>) -> SysResult<usize> {
+        let mut buffer = zeroize::Zeroizing::new(alloc::vec::Vec::new());
+        let result = crate::raw::get_string(&mut buffer)?;
+        crate::utils::append_wiped(out, &buffer);
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Getter<zeroize::Zeroizing<alloc::string::String>> for Unicode {
+    #[inline]
+    fn read_clipboard(&self, out: &mut zeroize::Zeroizing<alloc::string::String>) -> SysResult<usize> {
+        let mut buffer = zeroize::Zeroizing::new(alloc::vec::Vec::new());
+        let result = crate::raw::get_string(&mut buffer)?;
+        crate::utils::append_wiped(unsafe { out.as_mut_vec() }, &buffer);
+        Ok(result)
+    }
+}
+
 impl<T: AsRef<str>> Setter<T> for Unicode {
     #[inline(always)]
     fn write_clipboard(&self, data: &T) -> SysResult<()> {
@@ -125,6 +203,8 @@ impl<T: AsRef<str>> Setter<T> for Unicode {
 ///Corresponds to `CF_HDROP`
 ///
 ///`read_clipboard` returns number of file names
+///
+///There is no `Setter`, list is written by [set_file_list](../raw/fn.set_file_list.html), including empty one.
 pub struct FileList;
 
 impl Getter<alloc::vec::Vec<alloc::string::String>> for FileList {
@@ -134,9 +214,37 @@ impl Getter<alloc::vec::Vec<alloc::string::String>> for FileList {
     }
 }
 
+#[cfg(feature = "std")]
+impl FileList {
+    #[inline(always)]
+    ///Creates iterator over paths on clipboard, decoding them one at a time.
+    ///
+    ///Paths preserve exact UTF-16 of file names, and list is copied, so clipboard can change while iterating.
+    pub fn iter(&self, clip: &crate::Clipboard) -> SysResult<crate::raw::FileListIter> {
+        crate::raw::iter_file_list(clip)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Getter<alloc::vec::Vec<std::path::PathBuf>> for FileList {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<std::path::PathBuf>) -> SysResult<usize> {
+        crate::raw::get_file_list_os(out)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Getter<alloc::vec::Vec<std::ffi::OsString>> for FileList {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<std::ffi::OsString>) -> SysResult<usize> {
+        crate::raw::get_file_list_os(out)
+    }
+}
+
 ///Format for bitmap images i.e. `CF_BITMAP`.
 ///
-///Both `Getter` and `Setter` expects image as header and rgb payload
+///Both `Getter` and `Setter` expects image as header and rgb payload, or as
+///[Image](../codec/image/struct.Image.html).
 pub struct Bitmap;
 
 impl Getter<alloc::vec::Vec<u8>> for Bitmap {
@@ -152,3 +260,544 @@ impl<T: AsRef<[u8]>> Setter<T> for Bitmap {
         crate::raw::set_bitmap(data.as_ref())
     }
 }
+
+impl Getter<crate::codec::image::Image> for Bitmap {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_bitmap_image(out)
+    }
+}
+
+impl Setter<crate::codec::image::Image> for Bitmap {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::image::Image) -> SysResult<()> {
+        crate::raw::set_bitmap_image(data)
+    }
+}
+
+///Format for device independent bitmaps, i.e. `CF_DIB`.
+///
+///Both `Getter` and `Setter` operate on [Image](../codec/image/struct.Image.html). Image is written
+///with 32 bits per pixel, but without alpha channel nor profile, for which use
+///[DibV5](struct.DibV5.html).
+///
+///`read_clipboard` returns number of bytes of pixels.
+pub struct Dib;
+
+impl Getter<crate::codec::image::Image> for Dib {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_dib_image(CF_DIB, out)
+    }
+}
+
+impl Setter<crate::codec::image::Image> for Dib {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::image::Image) -> SysResult<()> {
+        crate::raw::set_dib_image(CF_DIB, data)
+    }
+}
+
+///Format for device independent bitmaps with color space, i.e. `CF_DIBV5`.
+///
+///Both `Getter` and `Setter` operate on [Image](../codec/image/struct.Image.html), preserving its
+///alpha channel, resolution and embedded ICC profile.
+///
+///`read_clipboard` returns number of bytes of pixels.
+pub struct DibV5;
+
+impl Getter<crate::codec::image::Image> for DibV5 {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_dib_image(CF_DIBV5, out)
+    }
+}
+
+impl Setter<crate::codec::image::Image> for DibV5 {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::image::Image) -> SysResult<()> {
+        crate::raw::set_dib_image(CF_DIBV5, data)
+    }
+}
+
+///Format for PNG images, i.e. registered [PNG](constant.PNG.html).
+///
+///Both `Getter` and `Setter` operate on [Image](../codec/image/struct.Image.html), preserving its
+///resolution and embedded ICC profile. PNG is decoded by [codec::png](../codec/png/index.html),
+///while it is written compressed only with `image` feature.
+///
+///`read_clipboard` returns number of bytes of pixels.
+pub struct Png;
+
+impl Getter<crate::codec::image::Image> for Png {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_png_image(out)
+    }
+}
+
+impl Setter<crate::codec::image::Image> for Png {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::image::Image) -> SysResult<()> {
+        crate::raw::set_png_image(data)
+    }
+}
+
+///Format for JPEG images, i.e. registered [IMAGE_JPEG](constant.IMAGE_JPEG.html) and
+///[JFIF](constant.JFIF.html).
+///
+///Both `Getter` and `Setter` operate on JPEG stream, which is validated, but passed unchanged.
+///With `image` feature, `Getter` can also decode it as [Image](../codec/image/struct.Image.html).
+///
+///`read_clipboard` returns number of bytes.
+pub struct Jpeg;
+
+impl Getter<alloc::vec::Vec<u8>> for Jpeg {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_jpeg(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for Jpeg {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_jpeg(data.as_ref())
+    }
+}
+
+#[cfg(feature = "image")]
+impl Getter<crate::codec::image::Image> for Jpeg {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_jpeg_image(out)
+    }
+}
+
+///Format for GIF images, i.e. registered [IMAGE_GIF](constant.IMAGE_GIF.html) and
+///[GIF](constant.GIF.html).
+///
+///Both `Getter` and `Setter` operate on GIF stream, which is validated, but passed unchanged,
+///keeping animation. With `image` feature, `Getter` can also decode its first frame as
+///[Image](../codec/image/struct.Image.html).
+///
+///`read_clipboard` returns number of bytes.
+pub struct Gif;
+
+impl Getter<alloc::vec::Vec<u8>> for Gif {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_gif(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for Gif {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_gif(data.as_ref())
+    }
+}
+
+#[cfg(feature = "image")]
+impl Getter<crate::codec::image::Image> for Gif {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_gif_image(out)
+    }
+}
+
+///Format for WebP images, i.e. registered [IMAGE_WEBP](constant.IMAGE_WEBP.html).
+///
+///Both `Getter` and `Setter` operate on WebP stream, which is validated, but passed unchanged,
+///keeping animation. With `image` feature, `Getter` can also decode lossy image as
+///[Image](../codec/image/struct.Image.html).
+///
+///`read_clipboard` returns number of bytes.
+pub struct WebP;
+
+impl Getter<alloc::vec::Vec<u8>> for WebP {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_webp(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for WebP {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_webp(data.as_ref())
+    }
+}
+
+#[cfg(feature = "image")]
+impl Getter<crate::codec::image::Image> for WebP {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::image::Image) -> SysResult<usize> {
+        crate::raw::get_webp_image(out)
+    }
+}
+
+///Format for SVG documents, i.e. registered [IMAGE_SVG_XML](constant.IMAGE_SVG_XML.html) and
+///[SVG](constant.SVG.html).
+///
+///Both `Getter` and `Setter` operate on UTF-8 document, which is validated, but passed unchanged.
+///UTF-16 document, as placed by some applications, is converted when read. Its dimensions can be
+///extracted by [codec::svg](../codec/svg/index.html).
+///
+///`read_clipboard` returns number of bytes.
+pub struct Svg;
+
+impl Svg {
+    ///Places SVG document onto clipboard together with its rendered `fallback`, written as
+    ///`CF_DIBV5` for applications without SVG support.
+    pub fn write_with_fallback(&self, data: &str, fallback: &crate::codec::image::Image) -> SysResult<()> {
+        crate::raw::set_svg(data, Some(fallback))
+    }
+}
+
+impl Getter<alloc::string::String> for Svg {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::string::String) -> SysResult<usize> {
+        crate::raw::get_svg(out)
+    }
+}
+
+impl<T: AsRef<str>> Setter<T> for Svg {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_svg(data.as_ref(), None)
+    }
+}
+
+///Wrapper over format, which marks written content as sensitive (e.g. passwords).
+///
+///After writing inner format, it places privacy markers within the same session, so that content
+///is excluded from clipboard history, cloud clipboard and clipboard monitors.
+///
+///Use [is_sensitive](../raw/fn.is_sensitive.html) to check whether current content is marked.
+pub struct Sensitive<F>(pub F);
+
+impl<T: ?Sized, F: Setter<T>> Setter<T> for Sensitive<F> {
+    #[inline]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        self.0.write_clipboard(data)?;
+        crate::raw::set_sensitive_markers()
+    }
+}
+
+impl<T, F: Getter<T>> Getter<T> for Sensitive<F> {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut T) -> SysResult<usize> {
+        self.0.read_clipboard(out)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+///Virtual file descriptor.
+pub struct VirtualFile {
+    ///File information.
+    pub info: crate::codec::filegroup::FileInfo,
+    ///File name, which may contain relative path.
+    pub name: alloc::string::String,
+}
+
+///Format for virtual files (generated by e.g. mail clients and archive viewers), which content is
+///not stored on disk.
+///
+///Corresponds to `FileGroupDescriptorW` (or `FileGroupDescriptor`) with `FileContents`.
+///
+///`read_clipboard` returns number of descriptors, while content of each file is read separately by
+///[read_contents](#method.read_contents).
+pub struct VirtualFiles;
+
+impl VirtualFiles {
+    #[inline(always)]
+    ///Reads content of file at `idx`, appending it to `out`.
+    ///
+    ///Unlike other formats, clipboard must NOT be opened. Refer to [get_file_contents](../raw/fn.get_file_contents.html).
+    pub fn read_contents(&self, idx: usize, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_file_contents(idx, out)
+    }
+
+    #[inline(always)]
+    ///Places virtual files with their content onto clipboard.
+    ///
+    ///Unlike other formats, clipboard must NOT be opened. Refer to [set_virtual_files](../raw/fn.set_virtual_files.html).
+    pub fn write_files<T: AsRef<[u8]>>(&self, files: &[(VirtualFile, T)]) -> SysResult<()> {
+        crate::raw::set_virtual_files(files)
+    }
+}
+
+impl Getter<alloc::vec::Vec<VirtualFile>> for VirtualFiles {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<VirtualFile>) -> SysResult<usize> {
+        crate::raw::get_virtual_files(out)
+    }
+}
+
+///Format for shell items (generated by Explorer), including virtual ones (e.g. Control Panel
+///entries), that have no file system path.
+///
+///Corresponds to `Shell IDList Array`.
+///
+///`read_clipboard` validates and returns raw `CIDA`, which can be parsed by
+///[idlist](../codec/idlist/index.html).
+pub struct ShellIdList;
+
+impl Getter<alloc::vec::Vec<u8>> for ShellIdList {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_shell_id_list(out)
+    }
+}
+
+///Format for URL (generated by browsers when copying link).
+///
+///Corresponds to `UniformResourceLocatorW` and `UniformResourceLocator`, with fallback to Internet
+///Shortcut (i.e. `.url` file) in `CF_HDROP` when reading.
+///
+///`Setter` writes both variants together with `CF_UNICODETEXT`, so URL can be pasted as text too.
+///Both `Getter` and `Setter` validate URL.
+///
+///`read_clipboard` returns number of bytes in URL.
+pub struct Url;
+
+impl Getter<alloc::string::String> for Url {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::string::String) -> SysResult<usize> {
+        crate::raw::get_url(out)
+    }
+}
+
+impl<T: AsRef<str>> Setter<T> for Url {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_url(data.as_ref())
+    }
+}
+
+///Format for spreadsheet tables (generated by Excel when copying range of cells).
+///
+///Corresponds to `Csv` with fallback to tab-separated `CF_UNICODETEXT` when reading.
+///
+///`Setter` writes both, so table is pasted as cells.
+///
+///`read_clipboard` returns number of rows.
+pub struct Table;
+
+impl Getter<alloc::vec::Vec<alloc::vec::Vec<alloc::string::String>>> for Table {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<alloc::vec::Vec<alloc::string::String>>) -> SysResult<usize> {
+        crate::raw::get_table(out)
+    }
+}
+
+impl<T: AsRef<str>> Setter<[alloc::vec::Vec<T>]> for Table {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &[alloc::vec::Vec<T>]) -> SysResult<()> {
+        crate::raw::set_table(data)
+    }
+}
+
+impl<T: AsRef<str>> Setter<[&[T]]> for Table {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &[&[T]]) -> SysResult<()> {
+        crate::raw::set_table(data)
+    }
+}
+
+///Format for Symbolic Link spreadsheet interchange, i.e. `CF_SYLK`.
+///
+///Cells are read with their values, formulas and basic formatting.
+///
+///`read_clipboard` returns number of cells.
+pub struct Sylk;
+
+impl Getter<crate::codec::grid::Grid> for Sylk {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::grid::Grid) -> SysResult<usize> {
+        crate::raw::get_sylk(out)
+    }
+}
+
+impl Setter<crate::codec::grid::Grid> for Sylk {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::grid::Grid) -> SysResult<()> {
+        crate::raw::set_sylk(data)
+    }
+}
+
+///Format for Data Interchange Format, i.e. `CF_DIF`.
+///
+///Table is read as rows of typed values.
+///
+///`read_clipboard` returns number of rows.
+pub struct Dif;
+
+impl Getter<alloc::vec::Vec<alloc::vec::Vec<crate::codec::grid::Value>>> for Dif {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<alloc::vec::Vec<crate::codec::grid::Value>>) -> SysResult<usize> {
+        crate::raw::get_dif(out)
+    }
+}
+
+impl Setter<[alloc::vec::Vec<crate::codec::grid::Value>]> for Dif {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &[alloc::vec::Vec<crate::codec::grid::Value>]) -> SysResult<()> {
+        crate::raw::set_dif(data)
+    }
+}
+
+impl Setter<[&[crate::codec::grid::Value]]> for Dif {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &[&[crate::codec::grid::Value]]) -> SysResult<()> {
+        crate::raw::set_dif(data)
+    }
+}
+
+///Format for Excel `XML Spreadsheet` (SpreadsheetML 2003).
+///
+///Unlike text based formats, it preserves types of values, formulas, merged cells and basic
+///formatting.
+///
+///`read_clipboard` returns number of worksheets.
+pub struct XmlSpreadsheet;
+
+impl Getter<crate::codec::spreadsheet::Workbook> for XmlSpreadsheet {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::spreadsheet::Workbook) -> SysResult<usize> {
+        crate::raw::get_xml_spreadsheet(out)
+    }
+}
+
+impl Setter<crate::codec::spreadsheet::Workbook> for XmlSpreadsheet {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::spreadsheet::Workbook) -> SysResult<()> {
+        crate::raw::set_xml_spreadsheet(data)
+    }
+}
+
+///Format for audio clips, i.e. `CF_WAVE` with fallback to `CF_RIFF` when reading.
+///
+///Both `Getter` and `Setter` operate on RIFF/WAVE content, which is validated, but passed
+///unchanged. Use [codec::wave](../codec/wave/index.html) to access samples.
+///
+///`read_clipboard` returns number of bytes.
+pub struct Wave;
+
+impl Wave {
+    ///Places PCM or IEEE float `samples`, described by `spec`, onto clipboard as RIFF/WAVE content.
+    pub fn write_samples(&self, spec: &crate::codec::wave::Spec, samples: &[u8]) -> SysResult<()> {
+        let mut data = alloc::vec::Vec::new();
+        crate::codec::wave::write(&mut data, spec, samples)?;
+        crate::raw::set(CF_WAVE, &data)
+    }
+}
+
+impl Getter<alloc::vec::Vec<u8>> for Wave {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_wave(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for Wave {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_wave(data.as_ref())
+    }
+}
+
+///Format for scanned and faxed images, i.e. `CF_TIFF`.
+///
+///Both `Getter` and `Setter` operate on TIFF content, which is validated, but passed unchanged.
+///Pages can be converted to and from BMP files, as used by [Bitmap](struct.Bitmap.html).
+///
+///`read_clipboard` returns number of bytes.
+pub struct Tiff;
+
+impl Tiff {
+    ///Reads first page as BMP file, appending it to `out`.
+    ///
+    ///Returns number of appended bytes.
+    pub fn read_bitmap(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        let mut bitmaps = alloc::vec::Vec::new();
+        crate::raw::get_tiff_bitmaps(&mut bitmaps, 1)?;
+        let bitmap = bitmaps.pop().unwrap_or_default();
+        out.extend_from_slice(&bitmap);
+        Ok(bitmap.len())
+    }
+
+    ///Reads all pages as BMP files, appending them to `out`.
+    ///
+    ///Returns number of appended pages.
+    pub fn read_bitmaps(&self, out: &mut alloc::vec::Vec<alloc::vec::Vec<u8>>) -> SysResult<usize> {
+        crate::raw::get_tiff_bitmaps(out, usize::MAX)
+    }
+
+    ///Places BMP files onto clipboard as pages of TIFF content.
+    pub fn write_bitmaps<T: AsRef<[u8]>>(&self, bitmaps: &[T]) -> SysResult<()> {
+        crate::raw::set_tiff_bitmaps(bitmaps)
+    }
+}
+
+impl Getter<alloc::vec::Vec<u8>> for Tiff {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_tiff(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for Tiff {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_tiff(data.as_ref())
+    }
+}
+
+///Format for vector pictures, i.e. `CF_ENHMETAFILE`.
+///
+///Both `Getter` and `Setter` operate on content of enhanced metafile, which is converted to and
+///from metafile handle. Content being set is validated first. Use
+///[codec::emf](../codec/emf/index.html) to inspect header and records.
+///
+///`read_clipboard` returns number of bytes.
+pub struct EnhMetafile;
+
+impl Getter<alloc::vec::Vec<u8>> for EnhMetafile {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+        crate::raw::get_enhmetafile(out)
+    }
+}
+
+impl<T: AsRef<[u8]>> Setter<T> for EnhMetafile {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &T) -> SysResult<()> {
+        crate::raw::set_enhmetafile(data.as_ref())
+    }
+}
+
+///Format for legacy vector pictures, i.e. `CF_METAFILEPICT`.
+///
+///Both `Getter` and `Setter` operate on [Picture](../codec/wmf/struct.Picture.html), consisting of
+///mapping mode, extent and metafile content, which is converted to and from metafile handle.
+///Metafile being set is validated first. Use [Picture::write_placeable](../codec/wmf/struct.Picture.html#method.write_placeable)
+///to export it as `.wmf` file.
+///
+///`read_clipboard` returns number of metafile bytes.
+pub struct MetafilePict;
+
+impl Getter<crate::codec::wmf::Picture> for MetafilePict {
+    #[inline(always)]
+    fn read_clipboard(&self, out: &mut crate::codec::wmf::Picture) -> SysResult<usize> {
+        crate::raw::get_metafile_pict(out)
+    }
+}
+
+impl Setter<crate::codec::wmf::Picture> for MetafilePict {
+    #[inline(always)]
+    fn write_clipboard(&self, data: &crate::codec::wmf::Picture) -> SysResult<()> {
+        crate::raw::set_metafile_pict(data)
+    }
+}
@@ -1,4 +1,3 @@
-#![cfg(windows)]
 //! This crate provide simple means to operate with Windows clipboard.
 //!
 //!# Note keeping Clipboard around:
@@ -9,7 +8,9 @@
 //!
 //!# Features
 //!
-//! - `std` - Enables usage of `std`, including `std::error::Error` trait.
+//! - `std` - Enables usage of `std`, including `std::error::Error` trait and [auto_clear](auto_clear/index.html) timer.
+//! - `zeroize` - Wipes intermediate buffers and enables reading into `Zeroizing` storage, that is wiped on drop.
+//! - `image` - Uses `image` crate to compress PNG produced by image getters and setters. Without it, built-in codec writes PNG without compression, keeping crate `no_std`.
 //!
 //!# Clipboard
 //!
@@ -23,8 +24,23 @@
 //!
 //! - [RawData](formats/struct.RawData.html) - Reads raw bytes from specified format.
 //! - [Unicode](formats/struct.Unicode.html) - Reads unicode string from clipboard.
-//! - [Bitmap](formats/struct.Bitmap.html) - Reads RGB data of image on clipboard.
-//! - [FileList](formats/struct.FileList.html) - Reads list of files from clipboard.
+//! - [Bitmap](formats/struct.Bitmap.html) - Reads RGB data of image on clipboard, also as [Image](codec/image/struct.Image.html).
+//! - [Dib](formats/struct.Dib.html) and [DibV5](formats/struct.DibV5.html) - Reads device independent bitmap as image.
+//! - [Png](formats/struct.Png.html) - Reads PNG image as image.
+//! - [Jpeg](formats/struct.Jpeg.html), [Gif](formats/struct.Gif.html) and [WebP](formats/struct.WebP.html) - Reads encoded image as it is, or decodes it with `image` feature.
+//! - [Svg](formats/struct.Svg.html) - Reads SVG document, converting it from UTF-16 if needed.
+//! - [FileList](formats/struct.FileList.html) - Reads list of files from clipboard, with `std` also as `PathBuf` or `OsString`.
+//! - [VirtualFiles](formats/struct.VirtualFiles.html) - Reads descriptors of virtual files, with content read by index.
+//! - [ShellIdList](formats/struct.ShellIdList.html) - Reads shell items, including those without file system path.
+//! - [Url](formats/struct.Url.html) - Reads URL.
+//! - [Table](formats/struct.Table.html) - Reads spreadsheet cells.
+//! - [Sylk](formats/struct.Sylk.html) - Reads typed spreadsheet cells.
+//! - [Dif](formats/struct.Dif.html) - Reads table of typed values.
+//! - [XmlSpreadsheet](formats/struct.XmlSpreadsheet.html) - Reads Excel workbook fragment.
+//! - [Wave](formats/struct.Wave.html) - Reads audio clip.
+//! - [Tiff](formats/struct.Tiff.html) - Reads TIFF image, which can be converted to bitmaps.
+//! - [EnhMetafile](formats/struct.EnhMetafile.html) - Reads enhanced metafile.
+//! - [MetafilePict](formats/struct.MetafilePict.html) - Reads metafile picture.
 //!
 //! Depending on format, getter can extract data into various data types.
 //!
@@ -34,14 +50,29 @@
 //!
 //! - [RawData](formats/struct.RawData.html) - Writes raw bytes onto specified format.
 //! - [Unicode](formats/struct.Unicode.html) - Writes unicode string onto clipboard.
-//! - [Bitmap](formats/struct.Bitmap.html) - Writes RGB data of image on clipboard.
+//! - [Bitmap](formats/struct.Bitmap.html) - Writes RGB data of image on clipboard, also from [Image](codec/image/struct.Image.html).
+//! - [Dib](formats/struct.Dib.html) and [DibV5](formats/struct.DibV5.html) - Writes image as device independent bitmap.
+//! - [Png](formats/struct.Png.html) - Writes image as PNG.
+//! - [Jpeg](formats/struct.Jpeg.html), [Gif](formats/struct.Gif.html) and [WebP](formats/struct.WebP.html) - Writes encoded image as it is.
+//! - [Svg](formats/struct.Svg.html) - Writes SVG document, optionally with rendered image.
+//! - [Url](formats/struct.Url.html) - Writes URL, that can be pasted as link or text.
+//! - [Table](formats/struct.Table.html) - Writes spreadsheet cells.
+//! - [Sylk](formats/struct.Sylk.html) - Writes typed spreadsheet cells.
+//! - [Dif](formats/struct.Dif.html) - Writes table of typed values.
+//! - [XmlSpreadsheet](formats/struct.XmlSpreadsheet.html) - Writes Excel workbook fragment.
+//! - [Wave](formats/struct.Wave.html) - Writes audio clip.
+//! - [Tiff](formats/struct.Tiff.html) - Writes TIFF image, which can be converted from bitmaps.
+//! - [EnhMetafile](formats/struct.EnhMetafile.html) - Writes enhanced metafile.
+//! - [MetafilePict](formats/struct.MetafilePict.html) - Writes metafile picture.
+//! - [Sensitive](formats/struct.Sensitive.html) - Writes inner format, excluding it from clipboard history and cloud clipboard.
 //!
 //! Default setters are generic over type allowing anything that can be referenced as byte slice or
 //! `str`
 //!
 //!## Manually lock clipboard
 //!
-//!```
+#![cfg_attr(windows, doc = "```")]
+#![cfg_attr(not(windows), doc = "```ignore")]
 //!use clipboard_win::{Clipboard, formats, Getter, Setter};
 //!
 //!const SAMPLE: &str = "MY loli sample ^^";
@@ -67,7 +98,8 @@
 //!
 //!## Simplified API
 //!
-//!```
+#![cfg_attr(windows, doc = "```")]
+#![cfg_attr(not(windows), doc = "```ignore")]
 //!use clipboard_win::{formats, get_clipboard, set_clipboard};
 //!
 //!let text = "my sample ><";
@@ -83,18 +115,35 @@
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::style))]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod auto_clear;
+pub mod codec;
+pub mod limits;
+#[cfg(windows)]
 pub mod formats;
+#[cfg(windows)]
 pub mod raw;
+#[cfg(windows)]
 pub(crate) mod utils;
+#[cfg(windows)]
+mod ole;
 
-pub use raw::{get_owner, empty, seq_num, size, is_format_avail, register_format, count_formats, EnumFormats};
+#[cfg(windows)]
+pub use raw::{get_owner, empty, seq_num, size, is_format_avail, is_sensitive, register_format, count_formats, EnumFormats};
+#[cfg(windows)]
 pub use formats::Unicode;
+#[cfg(all(windows, feature = "zeroize"))]
+pub use zeroize::Zeroizing;
 
+#[cfg(windows)]
 pub use error_code::SystemError;
+#[cfg(windows)]
 ///Alias to result used by this crate
 pub type SysResult<T> = Result<T, error_code::SystemError>;
 
+#[cfg(windows)]
 ///Clipboard instance, which allows to perform clipboard ops.
 ///
 ///# Note:
@@ -111,6 +160,7 @@ pub struct Clipboard {
     _dummy: ()
 }
 
+#[cfg(windows)]
 impl Clipboard {
     #[inline(always)]
     ///Attempts to open clipboard, returning clipboard instance on success.
@@ -148,12 +198,14 @@ impl Clipboard {
     }
 }
 
+#[cfg(windows)]
 impl Drop for Clipboard {
     fn drop(&mut self) {
         let _ = raw::close();
     }
 }
 
+#[cfg(windows)]
 ///Describes format getter, specifying data type as type param
 ///
 ///Default implementations only perform write, without opening/closing clipboard
@@ -162,14 +214,16 @@ pub trait Getter<Type> {
     fn read_clipboard(&self, out: &mut Type) -> SysResult<usize>;
 }
 
+#[cfg(windows)]
 ///Describes format setter, specifying data type as type param
 ///
 ///Default implementations only perform write, without opening/closing clipboard
-pub trait Setter<Type> {
+pub trait Setter<Type: ?Sized> {
     ///Writes content of `data` onto clipboard, returning whether it was successful or not
     fn write_clipboard(&self, data: &Type) -> SysResult<()>;
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Runs provided callable with open clipboard, returning whether clipboard was open successfully.
 ///
@@ -180,6 +234,7 @@ pub fn with_clipboard<F: FnMut()>(mut cb: F) -> SysResult<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Runs provided callable with open clipboard, returning whether clipboard was open successfully.
 ///
@@ -191,6 +246,7 @@ pub fn with_clipboard_attempts<F: FnMut()>(num: usize, mut cb: F) -> SysResult<(
     Ok(())
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Retrieve data from clipboard.
 pub fn get<R: Default, T: Getter<R>>(format: T) -> SysResult<R> {
@@ -198,6 +254,7 @@ pub fn get<R: Default, T: Getter<R>>(format: T) -> SysResult<R> {
     format.read_clipboard(&mut result).map(|_| result)
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Shortcut to retrieve data from clipboard.
 ///
@@ -207,12 +264,14 @@ pub fn get_clipboard<R: Default, T: Getter<R>>(format: T) -> SysResult<R> {
     get(format)
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Set data onto clipboard.
 pub fn set<R, T: Setter<R>>(format: T, data: R) -> SysResult<()> {
     format.write_clipboard(&data)
 }
 
+#[cfg(windows)]
 #[inline(always)]
 ///Shortcut to set data onto clipboard.
 ///
@@ -222,6 +281,7 @@ pub fn set_clipboard<R, T: Setter<R>>(format: T, data: R) -> SysResult<()> {
     set(format, data)
 }
 
+#[cfg(windows)]
 ///Shortcut to retrieve string from clipboard.
 ///
 ///It opens clipboard and gets string, if possible.
@@ -230,6 +290,7 @@ pub fn get_clipboard_string() -> SysResult<alloc::string::String> {
     get_clipboard(Unicode)
 }
 
+#[cfg(windows)]
 ///Shortcut to set string onto clipboard.
 ///
 ///It opens clipboard and attempts to set string.
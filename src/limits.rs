@@ -0,0 +1,210 @@
+//!Limits on size of clipboard content being read.
+//!
+//!Clipboard content is provided by other applications, so its size can be arbitrary large.
+//!Limits allow to reject such content before any allocation happens.
+//!
+//!Getters check process wide limits, which can be configured via [set](fn.set.html).
+//!By default there are no limits.
+
+use core::fmt;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+///Maximum number of format specific limits within [Limits](struct.Limits.html).
+pub const MAX_FORMAT_LIMITS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Error indicating that content exceeds limit.
+pub struct TooLarge {
+    ///Size of content, either in bytes or pixels.
+    pub size: u64,
+    ///Limit that has been exceeded.
+    pub limit: u64,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Clipboard content is too large: {} exceeds limit of {}", self.size, self.limit)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TooLarge {
+}
+
+#[cfg(windows)]
+impl From<TooLarge> for error_code::SystemError {
+    #[inline(always)]
+    fn from(_: TooLarge) -> Self {
+        error_code::SystemError::new(winapi::shared::winerror::ERROR_FILE_TOO_LARGE as _)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Limits on size of content.
+///
+///Consists of:
+///
+///- Global limit, that applies to any format.
+///- Format specific limits, that can only further restrict global one.
+///- Pixel limit for images, that applies to `width * height` before image is decoded.
+pub struct Limits {
+    max_size: usize,
+    max_pixels: u64,
+    formats: [(u32, usize); MAX_FORMAT_LIMITS],
+    formats_len: usize,
+}
+
+impl Limits {
+    ///Limits that allow any size.
+    pub const UNLIMITED: Self = Self::new(usize::MAX);
+
+    #[inline]
+    ///Creates new instance with global limit of `max_size` bytes, and no pixel limit.
+    pub const fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            max_pixels: u64::MAX,
+            formats: [(0, 0); MAX_FORMAT_LIMITS],
+            formats_len: 0,
+        }
+    }
+
+    #[inline]
+    ///Sets limit on number of pixels in image.
+    pub const fn with_max_pixels(mut self, max_pixels: u64) -> Self {
+        self.max_pixels = max_pixels;
+        self
+    }
+
+    ///Sets limit of `max_size` bytes for `format`, replacing previous one if any.
+    ///
+    ///# Panics
+    ///
+    ///If there are already [MAX_FORMAT_LIMITS](constant.MAX_FORMAT_LIMITS.html) different formats.
+    pub const fn with_format(mut self, format: u32, max_size: usize) -> Self {
+        let mut idx = 0;
+        while idx < self.formats_len {
+            if self.formats[idx].0 == format {
+                self.formats[idx].1 = max_size;
+                return self;
+            }
+            idx += 1;
+        }
+
+        if self.formats_len == MAX_FORMAT_LIMITS {
+            panic!("Too many format specific limits");
+        }
+
+        self.formats[self.formats_len] = (format, max_size);
+        self.formats_len += 1;
+        self
+    }
+
+    ///Returns maximum number of bytes allowed for `format`.
+    pub const fn max_size(&self, format: u32) -> usize {
+        let mut idx = 0;
+        while idx < self.formats_len {
+            if self.formats[idx].0 == format {
+                let max_size = self.formats[idx].1;
+                return if max_size < self.max_size {
+                    max_size
+                } else {
+                    self.max_size
+                };
+            }
+            idx += 1;
+        }
+
+        self.max_size
+    }
+
+    #[inline]
+    ///Returns maximum number of pixels allowed in image.
+    pub const fn max_pixels(&self) -> u64 {
+        self.max_pixels
+    }
+
+    #[inline]
+    ///Checks whether `size` bytes of `format` are within limits.
+    pub fn check_size(&self, format: u32, size: usize) -> Result<(), TooLarge> {
+        let limit = self.max_size(format);
+        match size > limit {
+            true => Err(TooLarge {
+                size: size as u64,
+                limit: limit as u64,
+            }),
+            false => Ok(()),
+        }
+    }
+
+    #[inline]
+    ///Checks whether image of `width` by `height` pixels is within limits.
+    pub fn check_pixels(&self, width: u32, height: u32) -> Result<(), TooLarge> {
+        let size = width as u64 * height as u64;
+        match size > self.max_pixels {
+            true => Err(TooLarge {
+                size,
+                limit: self.max_pixels,
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Default for Limits {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+struct Global {
+    lock: AtomicBool,
+    limits: UnsafeCell<Limits>,
+}
+
+//Access to limits is guarded by lock.
+unsafe impl Sync for Global {}
+
+impl Global {
+    #[inline]
+    fn with<R, F: FnOnce(&mut Limits) -> R>(&self, cb: F) -> R {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+
+        let result = cb(unsafe { &mut *self.limits.get() });
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+}
+
+static GLOBAL: Global = Global {
+    lock: AtomicBool::new(false),
+    limits: UnsafeCell::new(Limits::UNLIMITED),
+};
+
+#[inline]
+///Sets process wide limits, used by getters.
+pub fn set(limits: Limits) {
+    GLOBAL.with(|global| *global = limits)
+}
+
+#[inline]
+///Returns process wide limits, used by getters.
+pub fn get() -> Limits {
+    GLOBAL.with(|global| *global)
+}
+
+#[inline]
+///Checks `size` bytes of `format` against process wide limits.
+pub fn check_size(format: u32, size: usize) -> Result<(), TooLarge> {
+    get().check_size(format, size)
+}
+
+#[inline]
+///Checks image of `width` by `height` pixels against process wide limits.
+pub fn check_pixels(width: u32, height: u32) -> Result<(), TooLarge> {
+    get().check_pixels(width, height)
+}
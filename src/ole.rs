@@ -0,0 +1,344 @@
+//!OLE clipboard access.
+//!
+//!Required for indexed formats (i.e. `FileContents`), which cannot be accessed through plain
+//!clipboard API.
+//!
+//!OLE opens clipboard on its own, so it must not be opened by caller.
+
+use winapi::ctypes::c_void;
+use winapi::shared::guiddef::{REFIID, IsEqualGUID};
+use winapi::shared::minwindef::{BOOL, DWORD, ULONG, UINT};
+use winapi::shared::winerror::{HRESULT, S_OK, E_INVALIDARG, E_NOINTERFACE, E_NOTIMPL, DV_E_FORMATETC, DV_E_LINDEX, DV_E_TYMED, DV_E_DVASPECT, OLE_E_ADVISENOTSUPPORTED, DATA_S_SAMEFORMATETC};
+use winapi::shared::wtypes::DVASPECT_CONTENT;
+use winapi::um::objidl::{IDataObject, IDataObjectVtbl, IEnumFORMATETC, IAdviseSink, IEnumSTATDATA, FORMATETC, STGMEDIUM, TYMED_HGLOBAL, TYMED_ISTREAM, DATADIR_GET};
+use winapi::um::objidlbase::IStream;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winapi::Interface;
+
+use error_code::SystemError;
+
+use core::{ptr, slice};
+use core::sync::atomic::{AtomicU32, Ordering};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::SysResult;
+use crate::utils::RawMem;
+
+#[link(name = "ole32")]
+extern "system" {
+    fn OleInitialize(reserved: *mut c_void) -> HRESULT;
+    fn OleUninitialize();
+    fn OleGetClipboard(data: *mut *mut IDataObject) -> HRESULT;
+    fn OleSetClipboard(data: *mut IDataObject) -> HRESULT;
+    fn ReleaseStgMedium(medium: *mut STGMEDIUM);
+}
+
+#[link(name = "shell32")]
+extern "system" {
+    fn SHCreateStdEnumFmtEtc(len: UINT, formats: *const FORMATETC, result: *mut *mut IEnumFORMATETC) -> HRESULT;
+}
+
+#[inline(always)]
+fn check(result: HRESULT) -> SysResult<()> {
+    match result {
+        result if result < 0 => Err(SystemError::new(result as _)),
+        _ => Ok(()),
+    }
+}
+
+#[inline]
+fn format_etc(format: u32, lindex: i32, tymed: DWORD) -> FORMATETC {
+    FORMATETC {
+        cfFormat: format as _,
+        ptd: ptr::null(),
+        dwAspect: DVASPECT_CONTENT,
+        lindex,
+        tymed,
+    }
+}
+
+//Initializes OLE for duration of scope, unless it has been initialized already.
+struct Ole;
+
+impl Ole {
+    fn new() -> SysResult<Self> {
+        check(unsafe { OleInitialize(ptr::null_mut()) }).map(|_| Self)
+    }
+}
+
+impl Drop for Ole {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe { OleUninitialize() }
+    }
+}
+
+struct Medium(STGMEDIUM);
+
+impl Drop for Medium {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe { ReleaseStgMedium(&mut self.0) }
+    }
+}
+
+///Reads item `lindex` of `format` from OLE clipboard, appending it to `out`.
+///
+///Returns number of appended bytes.
+pub fn get_indexed(format: u32, lindex: i32, out: &mut Vec<u8>) -> SysResult<usize> {
+    let _ole = Ole::new()?;
+
+    let mut data = ptr::null_mut();
+    check(unsafe { OleGetClipboard(&mut data) })?;
+    let data = crate::utils::Scope(data, release_data_object);
+
+    let format_etc = format_etc(format, lindex, TYMED_HGLOBAL | TYMED_ISTREAM);
+    let mut medium = unsafe { core::mem::zeroed::<STGMEDIUM>() };
+    check(unsafe { (*data.0).GetData(&format_etc, &mut medium) })?;
+    let medium = Medium(medium);
+
+    match medium.0.tymed {
+        TYMED_HGLOBAL => {
+            let mem = RawMem::from_borrowed(ptr::NonNull::new(medium.0.u as *mut c_void).ok_or_else(|| SystemError::new(E_INVALIDARG as _))?);
+            let size = mem.size();
+            crate::limits::check_size(format, size)?;
+            if size == 0 {
+                return Ok(0);
+            }
+
+            let (ptr, _lock) = mem.lock()?;
+            out.extend_from_slice(unsafe { slice::from_raw_parts(ptr.as_ptr() as *const u8, size) });
+            Ok(size)
+        },
+        TYMED_ISTREAM => {
+            let stream = medium.0.u as *mut IStream;
+            let limits = crate::limits::get();
+            let start = out.len();
+            let mut buffer = alloc::vec![0u8; 4096];
+
+            let result = loop {
+                let mut read = 0;
+                if let Err(error) = check(unsafe { (*stream).Read(buffer.as_mut_ptr() as _, buffer.len() as ULONG, &mut read) }) {
+                    break Err(error);
+                }
+                if read == 0 {
+                    break Ok(out.len() - start);
+                }
+
+                if let Err(error) = limits.check_size(format, out.len() - start + read as usize) {
+                    break Err(error.into());
+                }
+                out.extend_from_slice(&buffer[..read as usize]);
+            };
+
+            crate::utils::wipe(&mut buffer);
+            if result.is_err() {
+                out.truncate(start);
+            }
+            result
+        },
+        _ => Err(SystemError::new(DV_E_TYMED as _)),
+    }
+}
+
+fn release_data_object(data: *mut IDataObject) {
+    if !data.is_null() {
+        unsafe {
+            (*data).Release();
+        }
+    }
+}
+
+///Places data object onto OLE clipboard, serving `data` under `format` and indexed `items`
+///under `indexed_format`.
+///
+///Content is provided on demand, so calling thread must process messages while it is on clipboard.
+pub fn set_indexed(format: u32, data: Vec<u8>, indexed_format: u32, items: Vec<Vec<u8>>) -> SysResult<()> {
+    let object = Box::into_raw(Box::new(DataObject {
+        vtbl: &DATA_OBJECT_VTBL,
+        refs: AtomicU32::new(1),
+        format,
+        data,
+        indexed_format,
+        items,
+    }));
+
+    //OLE keeps its own reference
+    let result = unsafe { OleSetClipboard(object as *mut IDataObject) };
+    release_data_object(object as *mut IDataObject);
+    check(result)
+}
+
+#[repr(C)]
+struct DataObject {
+    vtbl: *const IDataObjectVtbl,
+    refs: AtomicU32,
+    format: u32,
+    data: Vec<u8>,
+    indexed_format: u32,
+    items: Vec<Vec<u8>>,
+}
+
+impl DataObject {
+    //Returns item, that matches requested format.
+    fn item(&self, format_etc: *const FORMATETC) -> Result<&[u8], HRESULT> {
+        let format_etc = match unsafe { format_etc.as_ref() } {
+            Some(format_etc) => format_etc,
+            None => return Err(E_INVALIDARG),
+        };
+
+        if format_etc.dwAspect != DVASPECT_CONTENT {
+            return Err(DV_E_DVASPECT);
+        }
+        if format_etc.tymed & TYMED_HGLOBAL == 0 {
+            return Err(DV_E_TYMED);
+        }
+
+        match format_etc.cfFormat as u32 {
+            format if format == self.format => Ok(&self.data),
+            format if format == self.indexed_format => match format_etc.lindex {
+                -1 => self.items.first().map(|item| item.as_slice()).ok_or(DV_E_LINDEX),
+                lindex => self.items.get(lindex as usize).map(|item| item.as_slice()).ok_or(DV_E_LINDEX),
+            },
+            _ => Err(DV_E_FORMATETC),
+        }
+    }
+}
+
+impl Drop for DataObject {
+    fn drop(&mut self) {
+        crate::utils::wipe(&mut self.data);
+        for item in self.items.iter_mut() {
+            crate::utils::wipe(item);
+        }
+    }
+}
+
+static DATA_OBJECT_VTBL: IDataObjectVtbl = IDataObjectVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: query_interface,
+        AddRef: add_ref,
+        Release: release,
+    },
+    GetData: get_data,
+    GetDataHere: get_data_here,
+    QueryGetData: query_get_data,
+    GetCanonicalFormatEtc: get_canonical_format_etc,
+    SetData: set_data,
+    EnumFormatEtc: enum_format_etc,
+    DAdvise: d_advise,
+    DUnadvise: d_unadvise,
+    EnumDAdvise: enum_d_advise,
+};
+
+unsafe extern "system" fn query_interface(this: *mut IUnknown, riid: REFIID, result: *mut *mut c_void) -> HRESULT {
+    if result.is_null() {
+        return E_INVALIDARG;
+    }
+
+    if IsEqualGUID(&*riid, &IUnknown::uuidof()) || IsEqualGUID(&*riid, &IDataObject::uuidof()) {
+        add_ref(this);
+        *result = this as _;
+        S_OK
+    } else {
+        *result = ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+    (*(this as *const DataObject)).refs.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+    let refs = (*(this as *const DataObject)).refs.fetch_sub(1, Ordering::Release) - 1;
+    if refs == 0 {
+        core::sync::atomic::fence(Ordering::Acquire);
+        drop(Box::from_raw(this as *mut DataObject));
+    }
+    refs
+}
+
+unsafe extern "system" fn get_data(this: *mut IDataObject, format_etc: *const FORMATETC, medium: *mut STGMEDIUM) -> HRESULT {
+    if medium.is_null() {
+        return E_INVALIDARG;
+    }
+
+    let item = match (*(this as *const DataObject)).item(format_etc) {
+        Ok(item) => item,
+        Err(error) => return error,
+    };
+
+    let mem = match RawMem::new_global_mem(item.len()) {
+        Ok(mem) => mem,
+        Err(error) => return error.raw_code() as _,
+    };
+    if !item.is_empty() {
+        match mem.lock() {
+            Ok((ptr, _lock)) => ptr::copy_nonoverlapping(item.as_ptr(), ptr.as_ptr() as *mut u8, item.len()),
+            Err(error) => return error.raw_code() as _,
+        }
+    }
+
+    (*medium).tymed = TYMED_HGLOBAL;
+    (*medium).u = mem.get() as _;
+    (*medium).pUnkForRelease = ptr::null_mut();
+    //Receiver takes ownership
+    mem.release();
+    S_OK
+}
+
+unsafe extern "system" fn get_data_here(_: *mut IDataObject, _: *const FORMATETC, _: *mut STGMEDIUM) -> HRESULT {
+    E_NOTIMPL
+}
+
+unsafe extern "system" fn query_get_data(this: *mut IDataObject, format_etc: *const FORMATETC) -> HRESULT {
+    match (*(this as *const DataObject)).item(format_etc) {
+        Ok(_) => S_OK,
+        Err(error) => error,
+    }
+}
+
+unsafe extern "system" fn get_canonical_format_etc(_: *mut IDataObject, format_etc: *const FORMATETC, result: *mut FORMATETC) -> HRESULT {
+    if format_etc.is_null() || result.is_null() {
+        return E_INVALIDARG;
+    }
+
+    *result = *format_etc;
+    (*result).ptd = ptr::null();
+    DATA_S_SAMEFORMATETC
+}
+
+unsafe extern "system" fn set_data(_: *mut IDataObject, _: *const FORMATETC, _: *const FORMATETC, _: BOOL) -> HRESULT {
+    E_NOTIMPL
+}
+
+unsafe extern "system" fn enum_format_etc(this: *mut IDataObject, direction: DWORD, result: *mut *mut IEnumFORMATETC) -> HRESULT {
+    if result.is_null() {
+        return E_INVALIDARG;
+    }
+    if direction != DATADIR_GET {
+        *result = ptr::null_mut();
+        return E_NOTIMPL;
+    }
+
+    let this = &*(this as *const DataObject);
+    let formats = [
+        format_etc(this.format, -1, TYMED_HGLOBAL),
+        format_etc(this.indexed_format, -1, TYMED_HGLOBAL),
+    ];
+    SHCreateStdEnumFmtEtc(formats.len() as UINT, formats.as_ptr(), result)
+}
+
+unsafe extern "system" fn d_advise(_: *mut IDataObject, _: *const FORMATETC, _: DWORD, _: *const IAdviseSink, _: *mut DWORD) -> HRESULT {
+    OLE_E_ADVISENOTSUPPORTED
+}
+
+unsafe extern "system" fn d_unadvise(_: *mut IDataObject, _: DWORD) -> HRESULT {
+    OLE_E_ADVISENOTSUPPORTED
+}
+
+unsafe extern "system" fn enum_d_advise(_: *mut IDataObject, _: *const *const IEnumSTATDATA) -> HRESULT {
+    OLE_E_ADVISENOTSUPPORTED
+}
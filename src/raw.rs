@@ -14,11 +14,9 @@ use winapi::um::winuser::{OpenClipboard, CloseClipboard, EmptyClipboard, GetClip
 use winapi::um::winbase::{GlobalSize, GlobalLock, GlobalUnlock};
 use winapi::ctypes::{c_int, c_uint, c_void};
 use winapi::um::stringapiset::{MultiByteToWideChar, WideCharToMultiByte};
-use winapi::um::winnls::CP_UTF8;
-use winapi::um::shellapi::{DragQueryFileW};
-use winapi::um::wingdi::{GetObjectW, GetDIBits, CreateDIBitmap, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BITMAPV5HEADER, RGBQUAD, BI_RGB, DIB_RGB_COLORS, BITMAPFILEHEADER, CBM_INIT};
-use winapi::shared::windef::{HDC};
-use winapi::shared::winerror::ERROR_INCORRECT_SIZE;
+use winapi::um::winnls::{CP_UTF8, CP_ACP};
+use winapi::um::wingdi::{GetObjectW, GetDeviceCaps, LOGPIXELSX, LOGPIXELSY, GetDIBits, CreateDIBitmap, GetEnhMetaFileBits, SetEnhMetaFileBits, DeleteEnhMetaFile, GetMetaFileBitsEx, SetMetaFileBitsEx, DeleteMetaFile, DeleteObject, BITMAP, BITMAPINFO, BITMAPINFOHEADER, METAFILEPICT, RGBQUAD, BI_RGB, DIB_RGB_COLORS, CBM_INIT};
+use winapi::shared::windef::{HDC, HBITMAP};
 
 use str_buf::StrBuf;
 use error_code::SystemError;
@@ -30,11 +28,12 @@ use alloc::string::String;
 use alloc::borrow::ToOwned;
 use alloc::format;
 
+#[cfg(feature = "image")]
 use image::{load_from_memory_with_format, ImageFormat, DynamicImage};
 
 extern crate core;
 
-use crate::{SysResult, formats};
+use crate::{SysResult, formats, codec};
 use crate::utils::{RawMem};
 
 #[inline(always)]
@@ -44,6 +43,13 @@ fn free_dc(data: HDC) {
     }
 }
 
+#[inline(always)]
+fn free_bitmap(data: HBITMAP) {
+    unsafe {
+        DeleteObject(data as _);
+    }
+}
+
 #[inline(always)]
 ///Opens clipboard.
 ///
@@ -74,6 +80,7 @@ pub fn open() -> SysResult<()> {
 ///# Post-conditions (if successful):
 ///
 ///* Clipboard can be accessed for read and write operations.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn open_for(owner: winapi::shared::windef::HWND) -> SysResult<()> {
     match unsafe { OpenClipboard(owner) } {
         0 => Err(SystemError::last()),
@@ -135,7 +142,7 @@ pub fn seq_num() -> Option<NonZeroU32> {
 ///
 ///Size in bytes if format presents on clipboard.
 ///
-///# Unsafety:
+///# Safety
 ///
 ///In some cases, clipboard content might be so invalid that it crashes on `GlobalSize` (e.g.
 ///Bitmap)
@@ -160,6 +167,8 @@ pub unsafe fn size_unsafe(format: u32) -> Option<NonZeroUsize> {
 ///# Returns:
 ///
 ///Size in bytes if format is presents on clipboard.
+///
+///For enhanced metafile it is size of metafile content, rather than of handle.
 pub fn size(format: u32) -> Option<NonZeroUsize> {
     let clipboard_data = unsafe {GetClipboardData(format)};
 
@@ -167,6 +176,10 @@ pub fn size(format: u32) -> Option<NonZeroUsize> {
         return None
     }
 
+    if format == formats::CF_ENHMETAFILE || format == formats::CF_DSPENHMETAFILE {
+        return NonZeroUsize::new(unsafe { GetEnhMetaFileBits(clipboard_data as _, 0, ptr::null_mut()) } as usize);
+    }
+
     unsafe {
         if GlobalLock(clipboard_data).is_null() {
             return None;
@@ -189,7 +202,7 @@ pub fn size(format: u32) -> Option<NonZeroUsize> {
 ///
 ///* [open()](fn.open.html) has been called.
 pub fn get_clipboard_data(format: c_uint) -> SysResult<ptr::NonNull<c_void>> {
-    let ptr = unsafe { GetClipboardData(format) as *mut c_void };
+    let ptr = unsafe { GetClipboardData(format) };
     match ptr::NonNull::new(ptr) {
         Some(ptr) => Ok(ptr),
         None => Err(SystemError::last()),
@@ -209,313 +222,1486 @@ pub fn is_format_avail(format: c_uint) -> bool {
 pub fn count_formats() -> Option<usize> {
     let result = unsafe { CountClipboardFormats() };
 
-    if result == 0 {
-        if !SystemError::last().is_zero() {
-            return None
-        }
+    if result == 0 && !SystemError::last().is_zero() {
+        return None
+    }
+
+    Some(result as usize)
+}
+
+///Copies raw bytes from clipboard with specified `format`
+///
+///Returns number of copied bytes on success, otherwise 0.
+///
+///It is safe to pass uninit memory
+///
+///Zero-length data or empty `out` results in 0 bytes copied.
+pub fn get(format: u32, out: &mut [u8]) -> SysResult<usize> {
+    let size = out.len();
+    let out_ptr = out.as_mut_ptr();
+
+    let ptr = RawMem::from_borrowed(get_clipboard_data(format)?);
+
+    //Empty memory is discarded and cannot be locked.
+    let data_size = cmp::min(ptr.size(), size);
+    if data_size == 0 {
+        return Ok(0);
+    }
+
+    let result = unsafe {
+        let (data_ptr, _lock) = ptr.lock()?;
+        ptr::copy_nonoverlapping(data_ptr.as_ptr() as *const u8, out_ptr, data_size);
+        data_size
+    };
+
+    Ok(result)
+}
+
+///Copies raw bytes from clipboard with specified `format`, appending to `out` buffer.
+///
+///Returns number of copied bytes on success, otherwise 0.
+///
+///Fails with `ERROR_FILE_TOO_LARGE` if content exceeds [limits](../limits/index.html).
+pub fn get_vec(format: u32, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let ptr = RawMem::from_borrowed(get_clipboard_data(format)?);
+
+    let data_size = ptr.size();
+    if data_size == 0 {
+        return Ok(0);
+    }
+    crate::limits::check_size(format, data_size)?;
+
+    let result = unsafe {
+        let (data_ptr, _lock) = ptr.lock()?;
+
+        out.reserve(data_size as usize);
+        let storage_cursor = out.len();
+        let storage_ptr = out.as_mut_ptr().add(out.len()) as *mut _;
+
+        ptr::copy_nonoverlapping(data_ptr.as_ptr() as *const u8, storage_ptr, data_size);
+        out.set_len(storage_cursor + data_size as usize);
+
+        data_size
+    };
+
+    Ok(result)
+}
+
+fn global_from_bytes(data: &[u8]) -> SysResult<RawMem> {
+    let size = data.len();
+
+    let mem = RawMem::new_global_mem(size)?;
+
+    //Zero-length memory is allocated as discarded, so there is nothing to lock.
+    if size > 0 {
+        let (ptr, _lock) = mem.lock()?;
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr() as _, size) };
+    }
+
+    Ok(mem)
+}
+
+fn set_global(format: u32, mem: RawMem) -> SysResult<()> {
+    if unsafe { !SetClipboardData(format, mem.get()).is_null() } {
+        //SetClipboardData takes ownership
+        mem.release();
+        return Ok(());
+    }
+
+    Err(error_code::SystemError::last())
+}
+
+///Copies raw bytes onto clipboard with specified `format`, returning whether it was successful.
+///
+///Empty `data` is placed onto clipboard as zero-length item.
+pub fn set(format: u32, data: &[u8]) -> SysResult<()> {
+    let mem = global_from_bytes(data)?;
+    let _ = empty();
+    set_global(format, mem)
+}
+
+///Copies raw bytes onto clipboard with specified `format`, without emptying clipboard first.
+///
+///Allows to place multiple formats onto clipboard while it is open.
+pub fn set_without_clear(format: u32, data: &[u8]) -> SysResult<()> {
+    set_global(format, global_from_bytes(data)?)
+}
+
+///Copies raw bytes from clipboard with specified `format`, appending to `out` buffer.
+///
+///Returns number of copied bytes on success, otherwise 0.
+///
+///Fails with `ERROR_FILE_TOO_LARGE` if content exceeds [limits](../limits/index.html).
+pub fn get_string(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let ptr = RawMem::from_borrowed(get_clipboard_data(formats::CF_UNICODETEXT)?);
+
+    let data_size = ptr.size() / mem::size_of::<u16>();
+    if data_size == 0 {
+        return Ok(0);
+    }
+    crate::limits::check_size(formats::CF_UNICODETEXT, ptr.size())?;
+
+    let result = unsafe {
+        let (data_ptr, _lock) = ptr.lock()?;
+        let storage_req_size = WideCharToMultiByte(CP_UTF8, 0, data_ptr.as_ptr() as _, data_size as _, ptr::null_mut(), 0, ptr::null(), ptr::null_mut());
+
+        if storage_req_size == 0 {
+            return Err(SystemError::last());
+        }
+
+        let storage_cursor = out.len();
+        out.reserve(storage_req_size as usize);
+        let storage_ptr = out.as_mut_ptr().add(storage_cursor) as *mut _;
+        WideCharToMultiByte(CP_UTF8, 0, data_ptr.as_ptr() as _, data_size as _, storage_ptr, storage_req_size, ptr::null(), ptr::null_mut());
+        out.set_len(storage_cursor + storage_req_size as usize);
+
+        //It seems WinAPI always supposed to have at the end null char.
+        //But just to be safe let's check for it and only then remove.
+        if let Some(null_idx) = out.iter().skip(storage_cursor).position(|b| *b == b'\0') {
+            out.set_len(storage_cursor + null_idx);
+        }
+
+        out.len() - storage_cursor
+    };
+
+    Ok(result)
+}
+
+//Allocates null terminated wide string.
+fn wide_global(data: &str) -> SysResult<RawMem> {
+    //MultiByteToWideChar fails on empty input, so only null char is written in such case.
+    let size = match data.len() {
+        0 => 0,
+        len => match unsafe { MultiByteToWideChar(CP_UTF8, 0, data.as_ptr() as *const _, len as _, ptr::null_mut(), 0) } {
+            0 => return Err(error_code::SystemError::last()),
+            size => size,
+        }
+    };
+
+    let mem = RawMem::new_global_mem((mem::size_of::<u16>() * (size as usize + 1)) as _)?;
+    {
+        let (ptr, _lock) = mem.lock()?;
+        let ptr = ptr.as_ptr() as *mut u16;
+        unsafe {
+            if size > 0 {
+                MultiByteToWideChar(CP_UTF8, 0, data.as_ptr() as *const _, data.len() as _, ptr, size);
+            }
+            ptr::write(ptr.offset(size as isize), 0);
+        }
+    }
+
+    Ok(mem)
+}
+
+//Allocates null terminated string in system code page, replacing characters that cannot be represented.
+fn ansi_global(data: &str) -> SysResult<RawMem> {
+    let wide = crate::utils::Wiped(data.encode_utf16().collect::<alloc::vec::Vec<_>>());
+    let size = match wide.len() {
+        0 => 0,
+        len => match unsafe { WideCharToMultiByte(CP_ACP, 0, wide.as_ptr(), len as _, ptr::null_mut(), 0, ptr::null(), ptr::null_mut()) } {
+            0 => return Err(error_code::SystemError::last()),
+            size => size,
+        }
+    };
+
+    let mem = RawMem::new_global_mem(size as usize + 1)?;
+    {
+        let (ptr, _lock) = mem.lock()?;
+        let ptr = ptr.as_ptr() as *mut i8;
+        unsafe {
+            if size > 0 {
+                WideCharToMultiByte(CP_ACP, 0, wide.as_ptr(), wide.len() as _, ptr, size, ptr::null(), ptr::null_mut());
+            }
+            ptr::write(ptr.offset(size as isize), 0);
+        }
+    }
+
+    Ok(mem)
+}
+
+///Copies unicode string onto clipboard, performing necessary conversions, returning true on
+///success.
+///
+///Empty string is placed onto clipboard as single null character.
+pub fn set_string(data: &str) -> SysResult<()> {
+    let mem = wide_global(data)?;
+    let _ = empty();
+    set_global(formats::CF_UNICODETEXT, mem)
+}
+
+///Retrieves URL from clipboard, appending it to `out`.
+///
+///Wide `UniformResourceLocatorW` is preferred, with ANSI `UniformResourceLocator` decoded using
+///system code page otherwise. If neither is available, URL is taken from single Internet Shortcut
+///(i.e. `.url` file) in file list, which requires `std` feature.
+///
+///Returns number of appended bytes.
+///Invalid URL fails with `ERROR_INVALID_DATA`.
+pub fn get_url(out: &mut String) -> SysResult<usize> {
+    let wide_format = registered_format(formats::UNIFORM_RESOURCE_LOCATOR_W)?;
+    let ansi_format = registered_format(formats::UNIFORM_RESOURCE_LOCATOR)?;
+
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    let mut buffer = crate::utils::Wiped(alloc::vec::Vec::new());
+    let shortcut = match is_format_avail(wide_format) {
+        true => {
+            get_vec(wide_format, &mut data)?;
+            //Exact capacity avoids leaving unwiped copies behind when growing
+            buffer.reserve_exact(data.len() / 2);
+            buffer.extend(data.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).take_while(|unit| *unit != 0));
+            false
+        },
+        false => {
+            let shortcut = !is_format_avail(ansi_format) && get_shortcut(ansi_format, &mut data)?;
+            let len = match shortcut {
+                true => data.len(),
+                false => {
+                    get_vec(ansi_format, &mut data)?;
+                    data.iter().position(|byte| *byte == 0).unwrap_or(data.len())
+                }
+            };
+            ansi_to_wide(&data[..len], &mut buffer)?;
+            shortcut
+        }
+    };
+
+    let url = match String::from_utf16(&buffer) {
+        Ok(url) => crate::utils::WipedString(url),
+        Err(_) => return Err(codec::Error::Invalid("url is not valid UTF-16").into()),
+    };
+
+    let trimmed = match shortcut {
+        true => codec::url::from_shortcut(&url).ok_or(codec::Error::Invalid("shortcut without url"))?,
+        false => codec::url::trim(&url),
+    };
+    codec::url::validate(trimmed)?;
+    out.push_str(trimmed);
+    Ok(trimmed.len())
+}
+
+#[cfg(feature = "std")]
+//Reads content of Internet Shortcut, if it is the only file in file list.
+fn get_shortcut(format: u32, out: &mut alloc::vec::Vec<u8>) -> SysResult<bool> {
+    use std::io::Read;
+
+    if !is_format_avail(formats::CF_HDROP) {
+        return Ok(false);
+    }
+
+    let mut files = FileListIter::new()?;
+    let path = match (files.len(), files.next()) {
+        (1, Some(path)) => path?,
+        _ => return Ok(false),
+    };
+    match path.extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("url") => (),
+        _ => return Ok(false),
+    }
+
+    let io_error = |error: std::io::Error| SystemError::new(error.raw_os_error().unwrap_or(winapi::shared::winerror::ERROR_READ_FAULT as _));
+    let mut file = std::fs::File::open(&path).map_err(io_error)?;
+    let size = cmp::min(file.metadata().map_err(io_error)?.len(), usize::MAX as u64) as usize;
+    crate::limits::check_size(format, size)?;
+
+    //Content is read in place, so growing storage cannot leave unwiped copy behind
+    let start = out.len();
+    out.reserve_exact(size);
+    out.resize(start + size, 0);
+    file.read_exact(&mut out[start..]).map_err(io_error)?;
+    Ok(true)
+}
+
+#[cfg(not(feature = "std"))]
+#[inline(always)]
+fn get_shortcut(_format: u32, _out: &mut alloc::vec::Vec<u8>) -> SysResult<bool> {
+    Ok(false)
+}
+
+///Places URL onto clipboard as `UniformResourceLocatorW`, `UniformResourceLocator` and
+///`CF_UNICODETEXT`.
+///
+///URL is validated before clipboard is emptied, failing with `ERROR_INVALID_DATA`.
+///Clipboard is emptied again if any of formats cannot be set, so URL is never placed partially.
+pub fn set_url(url: &str) -> SysResult<()> {
+    let url = codec::url::trim(url);
+    codec::url::validate(url)?;
+
+    let wide_format = registered_format(formats::UNIFORM_RESOURCE_LOCATOR_W)?;
+    let ansi_format = registered_format(formats::UNIFORM_RESOURCE_LOCATOR)?;
+
+    let wide = wide_global(url)?;
+    let ansi = ansi_global(url)?;
+    let text = wide_global(url)?;
+
+    let _ = empty();
+    let result = set_global(wide_format, wide).and_then(|_| set_global(ansi_format, ansi)).and_then(|_| set_global(formats::CF_UNICODETEXT, text));
+    if result.is_err() {
+        let _ = empty();
+    }
+    result
+}
+
+//Retrieves null terminated text in system code page.
+fn get_ansi_text(format: u32) -> SysResult<crate::utils::WipedString> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_vec(format, &mut data)?;
+    let len = data.iter().position(|byte| *byte == 0).unwrap_or(data.len());
+
+    let mut buffer = crate::utils::Wiped(alloc::vec::Vec::new());
+    ansi_to_wide(&data[..len], &mut buffer)?;
+    match String::from_utf16(&buffer) {
+        Ok(text) => Ok(crate::utils::WipedString(text)),
+        Err(_) => Err(codec::Error::Invalid("text is not valid UTF-16").into()),
+    }
+}
+
+///Retrieves table from clipboard, appending its rows to `out`.
+///
+///Registered `Csv` format in system code page is preferred, with delimiter guessed by
+///[sniff](../codec/table/fn.sniff.html), otherwise tab-separated `CF_UNICODETEXT` is used.
+///
+///Returns number of appended rows.
+pub fn get_table(out: &mut alloc::vec::Vec<alloc::vec::Vec<String>>) -> SysResult<usize> {
+    let csv = registered_format(formats::CSV)?;
+    match is_format_avail(csv) {
+        true => {
+            let text = get_ansi_text(csv)?;
+            Ok(codec::table::parse(&text, codec::table::sniff(&text), out))
+        },
+        false => {
+            let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+            get_string(&mut data)?;
+            let text = String::from_utf8_lossy(&data);
+            Ok(codec::table::parse(&text, codec::table::TSV, out))
+        }
+    }
+}
+
+///Places table onto clipboard as `Csv` in system code page and as tab-separated `CF_UNICODETEXT`.
+///
+///Characters not representable in system code page are replaced within `Csv` only.
+///Clipboard is emptied again if any of formats cannot be set, so table is never placed partially.
+pub fn set_table<R: AsRef<[S]>, S: AsRef<str>>(rows: &[R]) -> SysResult<()> {
+    let csv_format = registered_format(formats::CSV)?;
+
+    let mut text = String::new();
+    codec::table::write(&mut text, rows, codec::table::CSV)?;
+    let csv = ansi_global(&text)?;
+    text.clear();
+    codec::table::write(&mut text, rows, codec::table::TSV)?;
+    let tsv = wide_global(&text)?;
+
+    let _ = empty();
+    let result = set_global(csv_format, csv).and_then(|_| set_global(formats::CF_UNICODETEXT, tsv));
+    if result.is_err() {
+        let _ = empty();
+    }
+    result
+}
+
+///Retrieves `CF_SYLK` from clipboard, inserting its cells into `out`.
+///
+///Returns number of read cells.
+pub fn get_sylk(out: &mut codec::grid::Grid) -> SysResult<usize> {
+    let text = get_ansi_text(formats::CF_SYLK)?;
+    let grid = codec::sylk::parse(&text)?;
+    let len = grid.len();
+    out.extend(grid);
+    Ok(len)
+}
+
+///Places `grid` onto clipboard as `CF_SYLK` in system code page.
+pub fn set_sylk(grid: &codec::grid::Grid) -> SysResult<()> {
+    let mut text = String::new();
+    codec::sylk::write(&mut text, grid)?;
+    let mem = ansi_global(&text)?;
+
+    let _ = empty();
+    set_global(formats::CF_SYLK, mem)
+}
+
+///Retrieves `CF_DIF` from clipboard, appending its rows to `out`.
+///
+///Returns number of appended rows.
+pub fn get_dif(out: &mut alloc::vec::Vec<alloc::vec::Vec<codec::grid::Value>>) -> SysResult<usize> {
+    let text = get_ansi_text(formats::CF_DIF)?;
+    codec::dif::parse(&text, out).map_err(Into::into)
+}
+
+///Places `rows` onto clipboard as `CF_DIF` in system code page.
+pub fn set_dif<R: AsRef<[codec::grid::Value]>>(rows: &[R]) -> SysResult<()> {
+    let mut text = String::new();
+    codec::dif::write(&mut text, rows)?;
+    let mem = ansi_global(&text)?;
+
+    let _ = empty();
+    set_global(formats::CF_DIF, mem)
+}
+
+///Retrieves `XML Spreadsheet` from clipboard, appending its worksheets to `out`.
+///
+///Returns number of appended worksheets.
+pub fn get_xml_spreadsheet(out: &mut codec::spreadsheet::Workbook) -> SysResult<usize> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_vec(registered_format(formats::XML_SPREADSHEET)?, &mut data)?;
+
+    let text = core::str::from_utf8(&data).map_err(|_| codec::Error::Invalid("xml is not valid UTF-8"))?;
+    codec::spreadsheet::parse(text, out).map_err(Into::into)
+}
+
+///Places `workbook` onto clipboard as `XML Spreadsheet`.
+pub fn set_xml_spreadsheet(workbook: &codec::spreadsheet::Workbook) -> SysResult<()> {
+    let format = registered_format(formats::XML_SPREADSHEET)?;
+
+    let mut text = String::new();
+    codec::spreadsheet::write(&mut text, workbook)?;
+    set(format, text.as_bytes())
+}
+
+///Retrieves RIFF/WAVE content from clipboard, appending it to `out`.
+///
+///`CF_WAVE` is preferred, with `CF_RIFF` used otherwise.
+///Content is validated, failing with `ERROR_INVALID_DATA` or `ERROR_NOT_SUPPORTED` without appending.
+///
+///Returns number of appended bytes.
+pub fn get_wave(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let format = match is_format_avail(formats::CF_WAVE) {
+        true => formats::CF_WAVE,
+        false => formats::CF_RIFF,
+    };
+
+    let start = out.len();
+    let size = get_vec(format, out)?;
+    if let Err(error) = codec::wave::parse(&out[start..]) {
+        out.truncate(start);
+        return Err(error.into());
+    }
+
+    Ok(size)
+}
+
+///Places RIFF/WAVE content onto clipboard as `CF_WAVE`, validating it first.
+pub fn set_wave(data: &[u8]) -> SysResult<()> {
+    codec::wave::parse(data)?;
+    set(formats::CF_WAVE, data)
+}
+
+///Retrieves enhanced metafile content from clipboard, appending it to `out`.
+///
+///Returns number of appended bytes.
+pub fn get_enhmetafile(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let handle = get_clipboard_data(formats::CF_ENHMETAFILE)?;
+
+    let size = unsafe { GetEnhMetaFileBits(handle.as_ptr() as _, 0, ptr::null_mut()) };
+    if size == 0 {
+        return Err(SystemError::last());
+    }
+    crate::limits::check_size(formats::CF_ENHMETAFILE, size as usize)?;
+
+    let written = unsafe {
+        out.reserve(size as usize);
+        let storage_cursor = out.len();
+        let storage_ptr = out.as_mut_ptr().add(out.len());
+
+        let written = cmp::min(GetEnhMetaFileBits(handle.as_ptr() as _, size, storage_ptr), size);
+        out.set_len(storage_cursor + written as usize);
+        written
+    };
+
+    match written {
+        0 => Err(SystemError::last()),
+        written => Ok(written as usize),
+    }
+}
+
+///Places enhanced metafile content onto clipboard as `CF_ENHMETAFILE`, validating it first.
+pub fn set_enhmetafile(data: &[u8]) -> SysResult<()> {
+    let emf = codec::emf::parse(data)?;
+    let data = emf.as_bytes();
+
+    let handle = unsafe { SetEnhMetaFileBits(data.len() as _, data.as_ptr()) };
+    if handle.is_null() {
+        return Err(SystemError::last());
+    }
+
+    let _ = empty();
+    if unsafe { SetClipboardData(formats::CF_ENHMETAFILE, handle as _).is_null() } {
+        let error = SystemError::last();
+        unsafe {
+            DeleteEnhMetaFile(handle);
+        }
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+///Retrieves metafile picture from clipboard, appending metafile content to `out.metafile`.
+///
+///Mapping mode and extent of `out` are overwritten.
+///
+///Returns number of appended bytes.
+pub fn get_metafile_pict(out: &mut codec::wmf::Picture) -> SysResult<usize> {
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_METAFILEPICT)?);
+    if clipboard_data.size() < mem::size_of::<METAFILEPICT>() {
+        return Err(codec::Error::Truncated.into());
+    }
+
+    let picture = {
+        let (data_ptr, _lock) = clipboard_data.lock()?;
+        unsafe { ptr::read_unaligned(data_ptr.as_ptr() as *const METAFILEPICT) }
+    };
+
+    let size = unsafe { GetMetaFileBitsEx(picture.hMF, 0, ptr::null_mut()) };
+    if size == 0 {
+        return Err(SystemError::last());
+    }
+    crate::limits::check_size(formats::CF_METAFILEPICT, size as usize)?;
+
+    let written = unsafe {
+        let metafile = &mut out.metafile;
+        metafile.reserve(size as usize);
+        let storage_cursor = metafile.len();
+        let storage_ptr = metafile.as_mut_ptr().add(metafile.len());
+
+        let written = cmp::min(GetMetaFileBitsEx(picture.hMF, size, storage_ptr as _), size);
+        metafile.set_len(storage_cursor + written as usize);
+        written
+    };
+
+    if written == 0 {
+        return Err(SystemError::last());
+    }
+
+    out.mapping_mode = picture.mm;
+    out.extent = codec::Point {
+        x: picture.xExt,
+        y: picture.yExt,
+    };
+    Ok(written as usize)
+}
+
+///Places metafile picture onto clipboard as `CF_METAFILEPICT`, validating metafile first.
+///
+///Placeable header of metafile, if any, is stripped.
+pub fn set_metafile_pict(picture: &codec::wmf::Picture) -> SysResult<()> {
+    let wmf = codec::wmf::parse(&picture.metafile)?;
+    let data = wmf.as_bytes();
+
+    let handle = unsafe { SetMetaFileBitsEx(data.len() as _, data.as_ptr()) };
+    if handle.is_null() {
+        return Err(SystemError::last());
+    }
+
+    let result = RawMem::new_global_mem(mem::size_of::<METAFILEPICT>()).and_then(|mem| {
+        {
+            let (ptr, _lock) = mem.lock()?;
+            let value = METAFILEPICT {
+                mm: picture.mapping_mode,
+                xExt: picture.extent.x,
+                yExt: picture.extent.y,
+                hMF: handle,
+            };
+            unsafe { ptr::write_unaligned(ptr.as_ptr() as *mut METAFILEPICT, value) };
+        }
+
+        let _ = empty();
+        set_global(formats::CF_METAFILEPICT, mem)
+    });
+
+    if result.is_err() {
+        unsafe {
+            DeleteMetaFile(handle);
+        }
+    }
+    result
+}
+
+///Retrieves TIFF content from clipboard, appending it to `out`.
+///
+///Header and IFD chain are validated, failing with `ERROR_INVALID_DATA` or `ERROR_NOT_SUPPORTED`
+///without appending.
+///
+///Returns number of appended bytes.
+pub fn get_tiff(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let start = out.len();
+    let size = get_vec(formats::CF_TIFF, out)?;
+    if let Err(error) = codec::tiff::parse(&out[start..]) {
+        out.truncate(start);
+        return Err(error.into());
+    }
+
+    Ok(size)
+}
+
+///Places TIFF content onto clipboard as `CF_TIFF`, validating it first.
+pub fn set_tiff(data: &[u8]) -> SysResult<()> {
+    codec::tiff::parse(data)?;
+    set(formats::CF_TIFF, data)
+}
+
+///Retrieves up to `max_pages` pages of TIFF content from clipboard, appending each as BMP file
+///to `out`.
+///
+///Each page is checked against pixel limit before being decoded.
+///Nothing is appended on error.
+///
+///Returns number of appended pages.
+pub fn get_tiff_bitmaps(out: &mut alloc::vec::Vec<alloc::vec::Vec<u8>>, max_pages: usize) -> SysResult<usize> {
+    let mut data = alloc::vec::Vec::new();
+    get_vec(formats::CF_TIFF, &mut data)?;
+    let tiff = codec::tiff::parse(&data)?;
+
+    let mut bitmaps = alloc::vec::Vec::new();
+    for index in 0..tiff.len().min(max_pages) {
+        let page = tiff.page(index)?;
+        crate::limits::check_pixels(page.width, page.height)?;
+
+        let mut bitmap = alloc::vec::Vec::new();
+        page.write_bitmap(&mut bitmap)?;
+        bitmaps.push(bitmap);
+    }
+
+    let len = bitmaps.len();
+    out.append(&mut bitmaps);
+    Ok(len)
+}
+
+///Places BMP files onto clipboard as pages of `CF_TIFF`.
+pub fn set_tiff_bitmaps<T: AsRef<[u8]>>(bitmaps: &[T]) -> SysResult<()> {
+    let mut data = alloc::vec::Vec::new();
+    codec::tiff::write_bitmaps(&mut data, bitmaps)?;
+    set(formats::CF_TIFF, &data)
+}
+
+///Retrieves file list from clipboard, appending each element to the provided storage.
+///
+///Both wide and ANSI lists are supported, with ANSI names decoded using system code page.
+///
+///Returns number of appended file names.
+///Malformed `DROPFILES` fails with `ERROR_INCORRECT_SIZE` or `ERROR_INVALID_DATA` without appending.
+pub fn get_file_list(out: &mut alloc::vec::Vec<alloc::string::String>) -> SysResult<usize> {
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_HDROP)?);
+
+    let size = match clipboard_data.size() {
+        0 => return Ok(0),
+        size => {
+            crate::limits::check_size(formats::CF_HDROP, size)?;
+            size
+        }
+    };
+
+    let (data_ptr, _lock) = clipboard_data.lock()?;
+    let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, size) };
+    let files = codec::dropfiles::parse(data)?;
+
+    let mut paths = alloc::vec::Vec::with_capacity(files.len());
+    let mut buffer = crate::utils::Wiped(alloc::vec::Vec::new());
+
+    for name in files.files() {
+        let path = match name {
+            codec::dropfiles::Name::Wide(name) => {
+                let mut path = String::new();
+                name.push_lossy(&mut path);
+                path
+            },
+            codec::dropfiles::Name::Ansi(name) => {
+                ansi_to_wide(name, &mut buffer)?;
+                String::from_utf16_lossy(&buffer)
+            },
+        };
+        paths.push(path);
+    }
+
+    out.append(&mut paths);
+    Ok(files.len())
+}
+
+//Decodes string in system code page, replacing content of `out`.
+fn ansi_to_wide(data: &[u8], out: &mut alloc::vec::Vec<u16>) -> SysResult<()> {
+    out.clear();
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    let size = unsafe { MultiByteToWideChar(CP_ACP, 0, data.as_ptr() as *const _, data.len() as c_int, ptr::null_mut(), 0) };
+    if size == 0 {
+        return Err(SystemError::last());
+    }
+
+    out.reserve(size as usize);
+    let size = unsafe { MultiByteToWideChar(CP_ACP, 0, data.as_ptr() as *const _, data.len() as c_int, out.as_mut_ptr(), size) };
+    if size == 0 {
+        return Err(SystemError::last());
+    }
+
+    unsafe {
+        out.set_len(size as usize);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+///Iterator over file list on clipboard, yielding paths with exact UTF-16 preserved.
+///
+///`DROPFILES` is copied on creation, so clipboard can change while iterating, and names are
+///decoded one at a time.
+pub struct FileListIter {
+    data: crate::utils::Wiped<u8>,
+    //Offset of next name within `data`.
+    offset: usize,
+    len: usize,
+    wide: bool,
+    buffer: crate::utils::Wiped<u16>,
+}
+
+#[cfg(feature = "std")]
+impl FileListIter {
+    //Clipboard must be open on creation only.
+    fn new() -> SysResult<Self> {
+        let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_HDROP)?);
+
+        let mut result = Self {
+            data: crate::utils::Wiped(alloc::vec::Vec::new()),
+            offset: 0,
+            len: 0,
+            wide: false,
+            buffer: crate::utils::Wiped(alloc::vec::Vec::new()),
+        };
+
+        let size = match clipboard_data.size() {
+            0 => return Ok(result),
+            size => {
+                crate::limits::check_size(formats::CF_HDROP, size)?;
+                size
+            }
+        };
+
+        {
+            let (data_ptr, _lock) = clipboard_data.lock()?;
+            let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, size) };
+            result.data.extend_from_slice(data);
+        }
+
+        let files = codec::dropfiles::parse(&result.data)?;
+        result.len = files.len();
+        result.wide = files.wide;
+        result.offset = codec::u32_le(&result.data, 0)? as usize;
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Iterator for FileListIter {
+    type Item = SysResult<std::path::PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use std::os::windows::ffi::OsStringExt;
+
+        if self.len == 0 {
+            return None;
+        }
+
+        //List is validated on creation, so every name is terminated.
+        let char_size = if self.wide { 2 } else { 1 };
+        let list = &self.data[self.offset..];
+        let name_len = codec::dropfiles::name_len(list, char_size)?;
+        let name = &list[..name_len];
+        self.offset += name_len + char_size;
+        self.len -= 1;
+
+        match self.wide {
+            true => {
+                crate::utils::wipe(&mut self.buffer);
+                self.buffer.reserve(name_len / 2);
+                self.buffer.extend(codec::Wide(name).units());
+            },
+            false => if let Err(error) = ansi_to_wide(name, &mut self.buffer) {
+                return Some(Err(error));
+            },
+        }
+
+        Some(Ok(std::ffi::OsString::from_wide(&self.buffer).into()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ExactSizeIterator for FileListIter {
+}
+
+#[cfg(feature = "std")]
+#[inline]
+///Creates iterator over file list on clipboard, which must be open only when creating it.
+pub fn iter_file_list(_clip: &crate::Clipboard) -> SysResult<FileListIter> {
+    FileListIter::new()
+}
+
+#[cfg(feature = "std")]
+///Retrieves file list from clipboard, appending each path to the provided storage.
+///
+///Unlike [get_file_list](fn.get_file_list.html) names are not converted to UTF-8, so paths with
+///unpaired surrogates are preserved.
+///
+///Returns number of appended paths.
+pub fn get_file_list_os<T: From<std::path::PathBuf>>(out: &mut alloc::vec::Vec<T>) -> SysResult<usize> {
+    let files = FileListIter::new()?;
+    let len = files.len();
+    out.reserve(len);
+
+    for path in files {
+        out.push(path?.into());
+    }
+
+    Ok(len)
+}
+
+#[repr(C)]
+#[allow(non_snake_case, clippy::upper_case_acronyms)]
+struct DROPFILES {
+    pFiles: u32,
+    pt: winapi::shared::windef::POINT,
+    fNC: winapi::shared::minwindef::BOOL,
+    fWide: winapi::shared::minwindef::BOOL,
+}
+
+///Sets list of files onto clipboard, using wide `CF_HDROP` representation.
+///
+///Empty list is placed onto clipboard as `DROPFILES` with no file names.
+pub fn set_file_list<T: AsRef<str>>(paths: &[T]) -> SysResult<()> {
+    const HEADER_SIZE: usize = mem::size_of::<DROPFILES>();
+
+    //Each name is null terminated, with extra null char at the end of list.
+    let list_size = paths.iter().fold(1, |size, path| size + path.as_ref().encode_utf16().count() + 1);
+
+    let mem = RawMem::new_global_mem(HEADER_SIZE + list_size * mem::size_of::<u16>())?;
+    {
+        let (ptr, _lock) = mem.lock()?;
+        unsafe {
+            let header = ptr.as_ptr() as *mut DROPFILES;
+            (*header).pFiles = HEADER_SIZE as _;
+            (*header).fWide = 1;
+
+            //Memory is zero initialized so only names need to be written.
+            let mut cursor = (ptr.as_ptr() as *mut u8).add(HEADER_SIZE) as *mut u16;
+            for path in paths {
+                for ch in path.as_ref().encode_utf16() {
+                    ptr::write(cursor, ch);
+                    cursor = cursor.add(1);
+                }
+                cursor = cursor.add(1);
+            }
+        }
+    }
+
+    let _ = empty();
+    set_global(formats::CF_HDROP, mem)
+}
+
+#[inline]
+fn registered_format(name: &str) -> SysResult<u32> {
+    register_format(name).map(|format| format.get()).ok_or_else(SystemError::last)
+}
+
+///Retrieves `CIDA` of shell items from clipboard, appending it to `out`.
+///
+///Returns number of appended bytes.
+///Malformed `CIDA` fails with `ERROR_INCORRECT_SIZE` or `ERROR_INVALID_DATA`, leaving `out` intact.
+pub fn get_shell_id_list(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let format = registered_format(formats::SHELL_IDLIST_ARRAY)?;
+
+    let start = out.len();
+    let size = get_vec(format, out)?;
+    if let Err(error) = codec::idlist::parse(&out[start..]) {
+        out.truncate(start);
+        return Err(error.into());
+    }
+
+    Ok(size)
+}
+
+///Retrieves descriptors of virtual files from clipboard, appending each to the provided storage.
+///
+///Wide `FileGroupDescriptorW` is preferred, with ANSI `FileGroupDescriptor` used otherwise.
+///Index of descriptor is index of its content, that can be read by
+///[get_file_contents](fn.get_file_contents.html).
+///
+///Returns number of appended descriptors, which is 0 for empty content.
+///Malformed descriptors fail without appending.
+pub fn get_virtual_files(out: &mut alloc::vec::Vec<formats::VirtualFile>) -> SysResult<usize> {
+    let wide_format = registered_format(formats::FILE_GROUP_DESCRIPTOR_W)?;
+    let (format, wide) = match is_format_avail(wide_format) {
+        true => (wide_format, true),
+        false => (registered_format(formats::FILE_GROUP_DESCRIPTOR)?, false),
+    };
+
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(format)?);
+
+    let size = match clipboard_data.size() {
+        0 => return Ok(0),
+        size => {
+            crate::limits::check_size(format, size)?;
+            size
+        }
+    };
+
+    let (data_ptr, _lock) = clipboard_data.lock()?;
+    let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, size) };
+    let group = codec::filegroup::parse(data, wide)?;
+
+    let mut files = alloc::vec::Vec::with_capacity(group.len());
+    let mut buffer = crate::utils::Wiped(alloc::vec::Vec::new());
+    for descriptor in group.iter() {
+        let name = match descriptor.name {
+            codec::Name::Wide(name) => {
+                let mut result = String::new();
+                name.push_lossy(&mut result);
+                result
+            },
+            codec::Name::Ansi(name) => {
+                ansi_to_wide(name, &mut buffer)?;
+                String::from_utf16_lossy(&buffer)
+            },
+        };
+
+        files.push(formats::VirtualFile {
+            info: descriptor.info,
+            name,
+        });
+    }
+
+    out.append(&mut files);
+    Ok(group.len())
+}
+
+///Retrieves content of virtual file at `lindex`, appending it to `out`.
+///
+///Content is read through OLE clipboard, as it is the only way to access item by index.
+///Content in memory can be larger than file itself, in which case it should be truncated to file size.
+///
+///Returns number of appended bytes.
+///
+///# Pre-conditions:
+///
+///* Clipboard is not opened by [open()](fn.open.html), as OLE opens it on its own.
+pub fn get_file_contents(lindex: usize, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let format = registered_format(formats::FILE_CONTENTS)?;
+    if lindex > i32::MAX as usize {
+        return Err(SystemError::new(winapi::shared::winerror::DV_E_LINDEX as _));
+    }
+
+    crate::ole::get_indexed(format, lindex as i32, out)
+}
+
+///Places virtual files onto clipboard, described by `FileGroupDescriptorW` with content of each
+///file as indexed `FileContents`.
+///
+///File size is set from length of content.
+///
+///Content is provided by calling thread on demand, so it must process messages while content is on
+///clipboard.
+///
+///# Pre-conditions:
+///
+///* OLE is initialized on calling thread (i.e. by `OleInitialize`).
+///* Clipboard is not opened by [open()](fn.open.html), as OLE opens it on its own.
+pub fn set_virtual_files<T: AsRef<[u8]>>(files: &[(formats::VirtualFile, T)]) -> SysResult<()> {
+    let group_format = registered_format(formats::FILE_GROUP_DESCRIPTOR_W)?;
+    let contents_format = registered_format(formats::FILE_CONTENTS)?;
+
+    let infos = files.iter().map(|(file, content)| file.info.with_file_size(content.as_ref().len() as u64)).collect::<alloc::vec::Vec<_>>();
+    let mut group = alloc::vec::Vec::new();
+    codec::filegroup::write_wide(&mut group, infos.iter().zip(files.iter().map(|(file, _)| file.name.as_str())))?;
+
+    let contents = files.iter().map(|(_, content)| content.as_ref().to_owned()).collect();
+    crate::ole::set_indexed(group_format, group, contents_format, contents)
+}
+
+
+/// Reads PNG image, appending image to the `out` vector and returning number
+/// of bytes read on success.
+///
+/// Image is re-encoded, preserving its color space and resolution. Without `image` feature it is
+/// written without compression.
+///
+/// Malformed image fails without appending.
+pub fn get_png(out: &mut alloc::vec::Vec<u8>, id: u32) -> SysResult<usize> {
+    // copy the image, checking its size against limits
+    let mut buffer = crate::utils::Wiped(alloc::vec::Vec::new());
+    let rawsize = get_vec(id, &mut buffer)?;
+
+    // reject malformed image or image exceeding pixel limit before decoding it
+    let header = codec::png::header(&buffer)?;
+    crate::limits::check_pixels(header.width, header.height)?;
+
+    #[cfg(feature = "image")]
+    {
+        // color space and resolution chunks are dropped by decoder, so they are copied as they are
+        let mut chunks = alloc::vec::Vec::new();
+        if let Ok(source) = codec::png::chunks(&buffer) {
+            for chunk in source.filter(|chunk| [codec::png::ICCP, codec::png::SRGB, codec::png::PHYS].contains(&chunk.kind)) {
+                chunks.extend_from_slice(chunk.bytes);
+            }
+        }
+
+        let dynimg: DynamicImage = match load_from_memory_with_format(buffer.as_mut_slice(), ImageFormat::Png) {
+            Ok(di) => di,
+            Err(_) => return Err(SystemError::new(1310))
+        };
+
+        write_png(&dynimg, &chunks, out).map_err(|_| SystemError::new(1308))?;
+    }
+
+    #[cfg(not(feature = "image"))]
+    {
+        let mut image = codec::png::read_image(&buffer)?;
+        codec::png::write_image(out, &image)?;
+        crate::utils::wipe(&mut image.pixels);
+    }
+
+    Ok(rawsize)
+}
+
+#[cfg(feature = "image")]
+//Encodes image as PNG, inserting `chunks` after its header.
+fn write_png(image: &DynamicImage, chunks: &[u8], out: &mut alloc::vec::Vec<u8>) -> SysResult<()> {
+    if chunks.is_empty() {
+        return image.write_to(out, ImageFormat::Png).map_err(|_| SystemError::new(1308));
+    }
+
+    let mut png = alloc::vec::Vec::new();
+    image.write_to(&mut png, ImageFormat::Png).map_err(|_| SystemError::new(1308))?;
+    codec::png::insert_chunks(&png, &[codec::png::ICCP, codec::png::SRGB, codec::png::PHYS], chunks, out)?;
+    crate::utils::wipe(&mut png);
+    Ok(())
+}
+
+#[cfg(feature = "image")]
+//Encodes image as compressed PNG, including its profile and resolution.
+fn write_png_image(image: &codec::image::Image, out: &mut alloc::vec::Vec<u8>) -> SysResult<()> {
+    image.validate()?;
+
+    let mut pixels = alloc::vec::Vec::with_capacity(image.width as usize * image.height as usize * 4);
+    for y in 0..image.height {
+        pixels.extend_from_slice(image.row(y));
+    }
+    if image.format == codec::image::PixelFormat::Bgra8 {
+        codec::image::swap_red_blue(&mut pixels);
+    }
+    let pixels = match image::RgbaImage::from_raw(image.width, image.height, pixels) {
+        Some(pixels) => DynamicImage::ImageRgba8(pixels),
+        None => return Err(codec::Error::Truncated.into()),
+    };
+
+    let mut chunks = alloc::vec::Vec::new();
+    if let Some(profile) = image.icc_profile.as_ref() {
+        codec::png::write_iccp(&mut chunks, "ICC profile", profile)?;
+    }
+    if let Some(resolution) = image.resolution {
+        codec::png::write_phys(&mut chunks, resolution)?;
     }
+    write_png(&pixels, &chunks, out)
+}
 
-    Some(result as usize)
+#[cfg(not(feature = "image"))]
+//Encodes image as uncompressed PNG, including its profile and resolution.
+fn write_png_image(image: &codec::image::Image, out: &mut alloc::vec::Vec<u8>) -> SysResult<()> {
+    codec::png::write_image(out, image)?;
+    Ok(())
 }
 
-///Copies raw bytes from clipboard with specified `format`
+///Reads registered `PNG` format as RGBA image, including its resolution and embedded profile.
 ///
-///Returns number of copied bytes on success, otherwise 0.
+///Image is checked against pixel limit before being decoded.
 ///
-///It is safe to pass uninit memory
-pub fn get(format: u32, out: &mut [u8]) -> SysResult<usize> {
-    let size = out.len();
-    debug_assert!(size > 0);
-    let out_ptr = out.as_mut_ptr();
-
-    let ptr = RawMem::from_borrowed(get_clipboard_data(format)?);
+///Returns number of bytes of pixels.
+pub fn get_png_image(out: &mut codec::image::Image) -> SysResult<usize> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_vec(registered_format(formats::PNG)?, &mut data)?;
 
-    let result = unsafe {
-        let (data_ptr, _lock) = ptr.lock()?;
-        let data_size = cmp::min(GlobalSize(ptr.get()) as usize, size);
-        ptr::copy_nonoverlapping(data_ptr.as_ptr() as *const u8, out_ptr, data_size);
-        data_size
-    };
+    let header = codec::png::header(&data)?;
+    crate::limits::check_pixels(header.width, header.height)?;
 
-    Ok(result)
+    *out = codec::png::read_image(&data)?;
+    Ok(out.pixels.len())
 }
 
-///Copies raw bytes from clipboard with specified `format`, appending to `out` buffer.
+///Places image onto clipboard as registered `PNG` format, including its resolution and profile.
 ///
-///Returns number of copied bytes on success, otherwise 0.
-pub fn get_vec(format: u32, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
-    let ptr = RawMem::from_borrowed(get_clipboard_data(format)?);
-
-    let result = unsafe {
-        let (data_ptr, _lock) = ptr.lock()?;
-        let data_size = GlobalSize(ptr.get()) as usize;
-
-        out.reserve(data_size as usize);
-        let storage_cursor = out.len();
-        let storage_ptr = out.as_mut_ptr().add(out.len()) as *mut _;
-
-        ptr::copy_nonoverlapping(data_ptr.as_ptr() as *const u8, storage_ptr, data_size);
-        out.set_len(storage_cursor + data_size as usize);
+///Without `image` feature, PNG is written without compression.
+pub fn set_png_image(image: &codec::image::Image) -> SysResult<()> {
+    let mut buffer = alloc::vec::Vec::new();
+    write_png_image(image, &mut buffer)?;
+    let result = set(registered_format(formats::PNG)?, &buffer);
+    crate::utils::wipe(&mut buffer);
+    result
+}
 
-        data_size
-    };
+//Returns first available format out of registered `names`, falling back to the first one.
+fn available_format(names: &[&str]) -> SysResult<u32> {
+    for name in names {
+        let format = registered_format(name)?;
+        if is_format_avail(format) {
+            return Ok(format);
+        }
+    }
 
-    Ok(result)
+    registered_format(names[0])
 }
 
-///Copies raw bytes onto clipboard with specified `format`, returning whether it was successful.
-pub fn set(format: u32, data: &[u8]) -> SysResult<()> {
-    let size = data.len();
-    debug_assert!(size > 0);
-
-    let mem = RawMem::new_global_mem(size)?;
+//Retrieves encoded image from first available format out of `names`, appending it to `out`
+//without trailing data.
+fn get_encoded(names: &[&str], parse: fn(&[u8]) -> Result<usize, codec::Error>, out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let start = out.len();
+    get_vec(available_format(names)?, out)?;
+    match parse(&out[start..]) {
+        Ok(size) => {
+            out.truncate(start + size);
+            Ok(size)
+        },
+        Err(error) => {
+            out.truncate(start);
+            Err(error.into())
+        },
+    }
+}
 
-    {
-        let (ptr, _lock) = mem.lock()?;
-        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr() as _, size) };
+//Places encoded image onto clipboard as every format out of `names`, validating it first.
+//Clipboard is emptied again if any of formats cannot be set.
+fn set_encoded(names: &[&str], data: &[u8], parse: fn(&[u8]) -> Result<usize, codec::Error>) -> SysResult<()> {
+    parse(data)?;
+    set(registered_format(names[0])?, data)?;
+    let result = names[1..].iter().try_for_each(|name| set_without_clear(registered_format(name)?, data));
+    if result.is_err() {
+        let _ = empty();
     }
+    result
+}
 
-    let _ = empty();
+const JPEG_FORMATS: [&str; 2] = [formats::IMAGE_JPEG, formats::JFIF];
+const GIF_FORMATS: [&str; 2] = [formats::IMAGE_GIF, formats::GIF];
+const WEBP_FORMATS: [&str; 1] = [formats::IMAGE_WEBP];
 
-    if unsafe { !SetClipboardData(format, mem.get()).is_null() } {
-        //SetClipboardData takes ownership
-        mem.release();
-        return Ok(());
-    }
+#[inline]
+fn jpeg_size(data: &[u8]) -> Result<usize, codec::Error> {
+    codec::jpeg::parse(data).map(|jpeg| jpeg.size)
+}
 
-    Err(error_code::SystemError::last())
+#[inline]
+fn gif_size(data: &[u8]) -> Result<usize, codec::Error> {
+    codec::gif::parse(data).map(|gif| gif.size)
 }
 
-///Copies raw bytes from clipboard with specified `format`, appending to `out` buffer.
-///
-///Returns number of copied bytes on success, otherwise 0.
-pub fn get_string(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
-    let ptr = RawMem::from_borrowed(get_clipboard_data(formats::CF_UNICODETEXT)?);
+#[inline]
+fn webp_size(data: &[u8]) -> Result<usize, codec::Error> {
+    codec::webp::parse(data).map(|webp| webp.size)
+}
 
-    let result = unsafe {
-        let (data_ptr, _lock) = ptr.lock()?;
-        let data_size = GlobalSize(ptr.get()) as usize / mem::size_of::<u16>();
-        let storage_req_size = WideCharToMultiByte(CP_UTF8, 0, data_ptr.as_ptr() as _, data_size as _, ptr::null_mut(), 0, ptr::null(), ptr::null_mut());
+///Retrieves JPEG stream from registered `image/jpeg` format, or `JFIF` otherwise, appending it
+///to `out`.
+///
+///Stream is validated, failing with `ERROR_INVALID_DATA` or `ERROR_NOT_SUPPORTED` without
+///appending. Trailing data after end of image is dropped.
+///
+///Returns number of appended bytes.
+pub fn get_jpeg(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    get_encoded(&JPEG_FORMATS, jpeg_size, out)
+}
 
-        if storage_req_size == 0 {
-            return Err(SystemError::last());
-        }
+///Places JPEG stream onto clipboard as registered `image/jpeg` and `JFIF` formats, validating it
+///first.
+pub fn set_jpeg(data: &[u8]) -> SysResult<()> {
+    set_encoded(&JPEG_FORMATS, data, jpeg_size)
+}
 
-        let storage_cursor = out.len();
-        out.reserve(storage_req_size as usize);
-        let storage_ptr = out.as_mut_ptr().add(storage_cursor) as *mut _;
-        WideCharToMultiByte(CP_UTF8, 0, data_ptr.as_ptr() as _, data_size as _, storage_ptr, storage_req_size, ptr::null(), ptr::null_mut());
-        out.set_len(storage_cursor + storage_req_size as usize);
+///Retrieves GIF stream from registered `image/gif` format, or `GIF` otherwise, appending it to
+///`out`.
+///
+///Stream is validated, failing with `ERROR_INVALID_DATA` without appending. Every frame of
+///animation is kept, while trailing data after trailer is dropped.
+///
+///Returns number of appended bytes.
+pub fn get_gif(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    get_encoded(&GIF_FORMATS, gif_size, out)
+}
 
-        //It seems WinAPI always supposed to have at the end null char.
-        //But just to be safe let's check for it and only then remove.
-        if let Some(null_idx) = out.iter().skip(storage_cursor).position(|b| *b == b'\0') {
-            out.set_len(storage_cursor + null_idx);
-        }
+///Places GIF stream onto clipboard as registered `image/gif` and `GIF` formats, validating it
+///first.
+pub fn set_gif(data: &[u8]) -> SysResult<()> {
+    set_encoded(&GIF_FORMATS, data, gif_size)
+}
 
-        out.len() - storage_cursor
-    };
+///Retrieves WebP stream from registered `image/webp` format, appending it to `out`.
+///
+///Stream is validated, failing with `ERROR_INVALID_DATA` or `ERROR_NOT_SUPPORTED` without
+///appending. Trailing data after RIFF container is dropped.
+///
+///Returns number of appended bytes.
+pub fn get_webp(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    get_encoded(&WEBP_FORMATS, webp_size, out)
+}
 
-    Ok(result)
+///Places WebP stream onto clipboard as registered `image/webp` format, validating it first.
+pub fn set_webp(data: &[u8]) -> SysResult<()> {
+    set_encoded(&WEBP_FORMATS, data, webp_size)
 }
 
-///Copies unicode string onto clipboard, performing necessary conversions, returning true on
-///success.
-pub fn set_string(data: &str) -> SysResult<()> {
-    debug_assert!(data.len() > 0);
+const SVG_FORMATS: [&str; 2] = [formats::IMAGE_SVG_XML, formats::SVG];
 
-    let size = unsafe {
-        MultiByteToWideChar(CP_UTF8, 0, data.as_ptr() as *const _, data.len() as _, ptr::null_mut(), 0)
-    };
+///Retrieves SVG document from registered `image/svg+xml` format, or `SVG` otherwise, appending it
+///to `out`.
+///
+///UTF-16 document is converted, while byte order mark is dropped. Document is validated, failing
+///with `ERROR_INVALID_DATA` or `ERROR_NOT_SUPPORTED` without appending.
+///
+///Returns number of appended bytes.
+pub fn get_svg(out: &mut String) -> SysResult<usize> {
+    let mut data = alloc::vec::Vec::new();
+    get_vec(available_format(&SVG_FORMATS)?, &mut data)?;
+
+    let mut text = String::new();
+    codec::svg::decode(&data, &mut text)?;
+    codec::svg::parse(&text)?;
+    out.push_str(&text);
+    Ok(text.len())
+}
 
-    if size != 0 {
-        let mem = RawMem::new_global_mem((mem::size_of::<u16>() * (size as usize + 1)) as _)?;
-        {
-            let (ptr, _lock) = mem.lock()?;
-            let ptr = ptr.as_ptr() as *mut u16;
-            unsafe {
-                MultiByteToWideChar(CP_UTF8, 0, data.as_ptr() as *const _, data.len() as _, ptr, size);
-                ptr::write(ptr.offset(size as isize), 0);
-            }
-        }
+///Places SVG document onto clipboard as registered `image/svg+xml` and `SVG` formats, validating
+///it first.
+///
+///Optional `fallback` is rendered document, which is placed as `CF_DIBV5`.
+///Clipboard is emptied again if any of formats cannot be set, so document is never placed partially.
+pub fn set_svg(data: &str, fallback: Option<&codec::image::Image>) -> SysResult<()> {
+    let data = data.strip_prefix('\u{FEFF}').unwrap_or(data);
+    codec::svg::parse(data)?;
+
+    let mut bitmap = crate::utils::Wiped(alloc::vec::Vec::new());
+    if let Some(image) = fallback {
+        image.write_dibv5(&mut bitmap)?;
+    }
 
+    set(registered_format(SVG_FORMATS[0])?, data.as_bytes())?;
+    let result = registered_format(SVG_FORMATS[1]).and_then(|format| set_without_clear(format, data.as_bytes())).and_then(|_| match fallback {
+        Some(_) => set_without_clear(formats::CF_DIBV5, &bitmap),
+        None => Ok(()),
+    });
+    if result.is_err() {
         let _ = empty();
-
-        if unsafe { !SetClipboardData(formats::CF_UNICODETEXT, mem.get()).is_null() } {
-            //SetClipboardData takes ownership
-            mem.release();
-            return Ok(());
-        }
     }
+    result
+}
 
-    Err(error_code::SystemError::last())
+#[cfg(feature = "image")]
+//Decodes validated stream as RGBA image, wiping `data` afterwards.
+fn decode_image(data: &mut alloc::vec::Vec<u8>, format: ImageFormat, out: &mut codec::image::Image) -> SysResult<usize> {
+    let image = load_from_memory_with_format(data, format);
+    crate::utils::wipe(data);
+
+    let pixels = image.map_err(|_| codec::Error::Invalid("image data"))?.into_rgba8();
+    let mut image = codec::image::Image::new(pixels.width(), pixels.height(), codec::image::PixelFormat::Rgba8)?;
+    image.pixels = pixels.into_raw();
+
+    *out = image;
+    Ok(out.pixels.len())
 }
 
-///Retrieves file list from clipboard, appending each element to the provided storage.
+#[cfg(feature = "image")]
+///Reads JPEG stream as RGBA image, including resolution of its JFIF header.
 ///
-///Returns number of appended file names.
-pub fn get_file_list(out: &mut alloc::vec::Vec<alloc::string::String>) -> SysResult<usize> {
-    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_HDROP)?);
-
-    let (_data_ptr, _lock) = clipboard_data.lock()?;
+///Image is checked against pixel limit before being decoded.
+///
+///Returns number of bytes of pixels.
+pub fn get_jpeg_image(out: &mut codec::image::Image) -> SysResult<usize> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_jpeg(&mut data)?;
 
-    let num_files = unsafe { DragQueryFileW(clipboard_data.get() as _, u32::MAX, ptr::null_mut(), 0) };
-    out.reserve(num_files as usize);
+    let jpeg = codec::jpeg::parse(&data)?;
+    crate::limits::check_pixels(jpeg.width as u32, jpeg.height as u32)?;
 
-    let mut buffer = alloc::vec::Vec::new();
+    let size = decode_image(&mut data, ImageFormat::Jpeg, out)?;
+    out.resolution = jpeg.resolution;
+    Ok(size)
+}
 
-    for idx in 0..num_files {
-        let required_size_no_null = unsafe { DragQueryFileW(clipboard_data.get() as _, idx, ptr::null_mut(), 0) };
-        if required_size_no_null == 0 {
-            return Err(SystemError::last());
-        }
+#[cfg(feature = "image")]
+///Reads first frame of GIF stream as RGBA image.
+///
+///Image is checked against pixel limit before being decoded.
+///
+///Returns number of bytes of pixels.
+pub fn get_gif_image(out: &mut codec::image::Image) -> SysResult<usize> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_gif(&mut data)?;
 
-        let required_size = required_size_no_null + 1;
-        buffer.reserve(required_size as usize);
+    let gif = codec::gif::parse(&data)?;
+    crate::limits::check_pixels(gif.width as u32, gif.height as u32)?;
 
-        if unsafe { DragQueryFileW(clipboard_data.get() as _, idx, buffer.as_mut_ptr(), required_size) == 0 } {
-            return Err(SystemError::last());
-        }
+    decode_image(&mut data, ImageFormat::Gif, out)
+}
 
-        unsafe {
-            buffer.set_len(required_size_no_null as usize);
-        }
-        out.push(alloc::string::String::from_utf16_lossy(&buffer));
+#[cfg(feature = "image")]
+///Reads WebP stream as RGBA image.
+///
+///Image is checked against pixel limit before being decoded. Only lossy images without alpha
+///channel nor animation can be decoded, others fail with `ERROR_NOT_SUPPORTED`.
+///
+///Returns number of bytes of pixels.
+pub fn get_webp_image(out: &mut codec::image::Image) -> SysResult<usize> {
+    let mut data = crate::utils::Wiped(alloc::vec::Vec::new());
+    get_webp(&mut data)?;
+
+    let webp = codec::webp::parse(&data)?;
+    if webp.lossless || webp.alpha || webp.animated {
+        return Err(codec::Error::Unsupported("WebP decoding of lossless, alpha or animation").into());
     }
+    crate::limits::check_pixels(webp.width, webp.height)?;
 
-    Ok(num_files as usize)
+    decode_image(&mut data, ImageFormat::WebP, out)
 }
 
-
-/// Reads PNG image, appending image to the `out` vector and returning number
+/// Reads DIBV5 image, appending image to the `out` vector and returning number
 /// of bytes read on success.
-pub fn get_png(out: &mut alloc::vec::Vec<u8>, id: u32) -> SysResult<usize> {
+///
+/// Image is converted to PNG, preserving its embedded profile and resolution. Without `image`
+/// feature, it is written without compression.
+pub fn get_dibv5(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_DIBV5)?);
 
     // get the raw size of the image in memory
-    let rawsize = size(id).unwrap().get();
+    let rawsize = clipboard_data.size();
+    crate::limits::check_size(formats::CF_DIBV5, rawsize)?;
 
-    // get the specified format
-    let clipboard_data = get_clipboard_data(id)?;
-    let lockptr: *mut c_void;
-    unsafe {
-        // Windows recommends to obtain a locked pointer and use that
-        lockptr = GlobalLock(clipboard_data.as_ptr());
-        if lockptr.is_null() {
-            return Err(SystemError::new(1309));
+    #[cfg(feature = "image")]
+    {
+        let mut filebuffer = alloc::vec::Vec::new();
+        let mut chunks = alloc::vec::Vec::new();
+        {
+            // Windows recommends to obtain a locked pointer and use that
+            let (data_ptr, _lock) = clipboard_data.lock()?;
+            let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, rawsize) };
+
+            // validate header against data, before it is handed to decoder
+            let dib = codec::bmp::parse_dib(data)?;
+
+            // reject image exceeding pixel limit before decoding it
+            crate::limits::check_pixels(dib.header.width as u32, dib.header.abs_height())?;
+
+            // embedded profile and resolution are carried over as chunks, as decoder ignores them
+            if let Some(codec::bmp::Profile::Embedded(profile)) = dib.profile {
+                codec::png::write_iccp(&mut chunks, "ICC profile", profile)?;
+            }
+            if let Some(resolution) = dib.resolution() {
+                codec::png::write_phys(&mut chunks, resolution)?;
+            }
+
+            // prepend BMP file header to the whole image structure
+            codec::bmp::dib_to_file(data, &mut filebuffer)?;
         }
-    }
 
-    let mut buffer = alloc::vec::Vec::new();
-    unsafe {
-        let imagebuffer: &mut [u8] = core::slice::from_raw_parts_mut(lockptr as *mut u8, rawsize as usize);
-        buffer.extend_from_slice(&imagebuffer);
+        let dynimg: DynamicImage = match load_from_memory_with_format(filebuffer.as_mut_slice(), ImageFormat::Bmp) {
+            Ok(di) => di,
+            Err(_) => return Err(SystemError::new(1310))
+        };
+        crate::utils::wipe(&mut filebuffer);
 
-        // now we can release the lock
-        GlobalUnlock(clipboard_data.as_ptr());
+        write_png(&dynimg, &chunks, out).map_err(|_| SystemError::new(1311))?;
     }
 
-    let dynimg: DynamicImage = match load_from_memory_with_format(buffer.as_mut_slice(), ImageFormat::Png) {
-        Ok(di) => di,
-        Err(err) => panic!("DynamicImage from memory failed: {:?}", err),
-    };
+    #[cfg(not(feature = "image"))]
+    {
+        let mut image = {
+            let (data_ptr, _lock) = clipboard_data.lock()?;
+            let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, rawsize) };
+
+            let dib = codec::bmp::parse_dib(data)?;
+            crate::limits::check_pixels(dib.header.width as u32, dib.header.abs_height())?;
+            codec::image::Image::from_dib(&dib)?
+        };
 
-    match dynimg.write_to(out, ImageFormat::Png) {
-        Ok(_) => Ok(rawsize),
-        Err(_) => return Err(SystemError::new(1308))
+        let result = codec::png::write_image(out, &image);
+        crate::utils::wipe(&mut image.pixels);
+        result?;
     }
+
+    Ok(rawsize)
 }
 
-/// Reads DIBV5 image, appending image to the `out` vector and returning number
-/// of bytes read on success.
-pub fn get_dibv5(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
+///Retrieves color profile of `CF_DIBV5` image.
+///
+///Returns `None` if image has no embedded or linked profile.
+pub fn get_dibv5_profile() -> SysResult<Option<codec::bmp::ProfileBuf>> {
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(formats::CF_DIBV5)?);
 
-    // get the raw size of the image in memory
-    let rawsize = size(formats::CF_DIBV5).unwrap().get();
+    let rawsize = clipboard_data.size();
+    crate::limits::check_size(formats::CF_DIBV5, rawsize)?;
 
-    let clipboard_data = get_clipboard_data(formats::CF_DIBV5)?;
-    let lockptr: *mut c_void;
-    unsafe {
-        // Windows recommends to obtain a locked pointer and use that
-        lockptr = GlobalLock(clipboard_data.as_ptr());
-        if lockptr.is_null() {
-            return Err(SystemError::new(1309));
-        }
-    }
+    let (data_ptr, _lock) = clipboard_data.lock()?;
+    let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, rawsize) };
+    let dib = codec::bmp::parse_dib(data)?;
+    Ok(dib.profile.map(|profile| profile.to_buf()))
+}
 
-    // this allows us to refer to individual elements of the header
-    // information in below calculations
-    let dibv5: BITMAPV5HEADER;
-    unsafe {
-        // get a pointer to the memory segment where the header starts
-        let clipref = lockptr as *mut BITMAPV5HEADER;
-        // clone that into our strcuture, GetObjectW did not work for me
-        // because the CF_DIBV5 is not supported
-        dibv5 = *clipref.clone();
-    }
-
-    /*
-    eprintln!("bV5Size            {:?}", dibv5.bV5Size);
-    eprintln!("bV5Width           {:?}", dibv5.bV5Width);
-    eprintln!("bV5Height          {:?}", dibv5.bV5Height);
-    eprintln!("bV5Planes          {:?}", dibv5.bV5Planes);
-    eprintln!("bV5BitCount        {:?}", dibv5.bV5BitCount);
-    eprintln!("bV5Compression     {:?}", dibv5.bV5Compression);
-    eprintln!("bV5SizeImage       {:?}", dibv5.bV5SizeImage);
-    eprintln!("bV5XPelsPerMeter   {:?}", dibv5.bV5XPelsPerMeter);
-    eprintln!("bV5YPelsPerMeter   {:?}", dibv5.bV5YPelsPerMeter);
-    eprintln!("bV5ClrUsed         {:?}", dibv5.bV5ClrUsed);
-    eprintln!("bV5ClrImportant    {:?}", dibv5.bV5ClrImportant);
-    eprintln!("bV5RedMask         {:?}", dibv5.bV5RedMask);
-    eprintln!("bV5GreenMask       {:?}", dibv5.bV5GreenMask);
-    eprintln!("bV5BlueMask        {:?}", dibv5.bV5BlueMask);
-    eprintln!("bV5AlphaMask       {:?}", dibv5.bV5AlphaMask);
-    eprintln!("bV5CSType          {:?}", dibv5.bV5CSType);
-    eprintln!("bV5GammaRed        {:?}", dibv5.bV5CSType);
-    eprintln!("bV5CSType          {:?}", dibv5.bV5CSType);
-    eprintln!("bV5GammaRed        {:?}", dibv5.bV5GammaRed);
-    eprintln!("bV5GammaGreen      {:?}", dibv5.bV5GammaGreen);
-    eprintln!("bV5GammaBlue       {:?}", dibv5.bV5GammaBlue);
-    eprintln!("bV5Intent          {:?}", dibv5.bV5Intent);
-    eprintln!("bV5ProfileData     {:?}", dibv5.bV5ProfileData);
-    eprintln!("bV5ProfileSize     {:?}", dibv5.bV5ProfileSize);
-    eprintln!("bV5Reserved        {:?}", dibv5.bV5Reserved);
-    */
-
-    let mut filebuffer = alloc::vec::Vec::new();
-
-    // create the BMP file header
-    // the 'BM' signature
-    filebuffer.extend_from_slice(&u16::to_le_bytes(0x4d42));
-    // the file size in total including the file header
-    filebuffer.extend_from_slice(&u32::to_le_bytes(mem::size_of::<BITMAPFILEHEADER>() as u32 + rawsize as u32));
-    // 2 reserved WORDs
-    filebuffer.extend_from_slice(&u32::to_le_bytes(0));
-    // offset to pixel array from start of file header
-    if dibv5.bV5SizeImage == 0 {
-        // BI_RGB images may have set this to zero, then
-        filebuffer.extend_from_slice(&u32::to_le_bytes(mem::size_of::<BITMAPFILEHEADER>() as u32 + mem::size_of::<BITMAPV5HEADER>() as u32));
-    } else {
-        filebuffer.extend_from_slice(&u32::to_le_bytes(mem::size_of::<BITMAPFILEHEADER>() as u32 + (rawsize as u32 - dibv5.bV5SizeImage)));
+///Places bitmap (BMP file) onto clipboard as `CF_DIBV5`, validating it first.
+///
+///Embedded ICC `profile`, if specified, replaces profile of bitmap. Otherwise profile of bitmap
+///with `BITMAPV5HEADER` is preserved, while other bitmaps are marked as sRGB.
+pub fn set_dibv5(data: &[u8], profile: Option<&[u8]>) -> SysResult<()> {
+    let mut dib = codec::bmp::parse_file(data)?;
+    if let Some(profile) = profile {
+        dib.profile = Some(codec::bmp::Profile::Embedded(profile));
     }
 
-    // append the whole image structure including the fileinfoheader and bitmap
-    unsafe {
-        let imagebuffer: &mut [u8] = core::slice::from_raw_parts_mut(lockptr as *mut u8, rawsize as usize);
-        filebuffer.extend_from_slice(&imagebuffer);
+    let mut buffer = alloc::vec::Vec::new();
+    codec::bmp::write_dibv5(&mut buffer, &dib)?;
+    let result = set(formats::CF_DIBV5, &buffer);
+    crate::utils::wipe(&mut buffer);
+    result
+}
 
-        // now we can release the lock
-        GlobalUnlock(clipboard_data.as_ptr());
-    }
+///Reads packed DIB of `format`, i.e. `CF_DIB` or `CF_DIBV5`, as RGBA image.
+///
+///Image is checked against pixel limit before being decoded.
+///
+///Returns number of bytes of pixels.
+pub fn get_dib_image(format: u32, out: &mut codec::image::Image) -> SysResult<usize> {
+    let clipboard_data = RawMem::from_borrowed(get_clipboard_data(format)?);
+
+    let rawsize = clipboard_data.size();
+    crate::limits::check_size(format, rawsize)?;
+
+    let (data_ptr, _lock) = clipboard_data.lock()?;
+    let data = unsafe { slice::from_raw_parts(data_ptr.as_ptr() as *const u8, rawsize) };
+    let dib = codec::bmp::parse_dib(data)?;
+    crate::limits::check_pixels(dib.header.width as u32, dib.header.abs_height())?;
 
-    let dynimg: DynamicImage = match load_from_memory_with_format(filebuffer.as_mut_slice(), ImageFormat::Bmp) {
-        Ok(di) => di,
-        Err(_) => return Err(SystemError::new(1310))
+    *out = codec::image::Image::from_dib(&dib)?;
+    Ok(out.pixels.len())
+}
+
+///Places image onto clipboard as packed DIB of `format`.
+///
+///`CF_DIBV5` is written with alpha mask and profile of image, while any other format is written
+///as `CF_DIB`, which has no alpha channel nor profile.
+pub fn set_dib_image(format: u32, image: &codec::image::Image) -> SysResult<()> {
+    let mut buffer = alloc::vec::Vec::new();
+    let format = match format {
+        formats::CF_DIBV5 => {
+            image.write_dibv5(&mut buffer)?;
+            formats::CF_DIBV5
+        },
+        _ => {
+            image.write_dib(&mut buffer)?;
+            formats::CF_DIB
+        },
     };
 
-    match dynimg.write_to(out, ImageFormat::Png) {
-        Ok(_) => Ok(rawsize),
-        Err(_) => return Err(SystemError::new(1311))
-    }
+    let result = set(format, &buffer);
+    crate::utils::wipe(&mut buffer);
+    result
 }
 
 ///Reads bitmap image, appending image to the `out` vector and returning number of bytes read on
@@ -540,6 +1726,8 @@ pub fn get_bitmap(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
         return Err(SystemError::last());
     }
 
+    crate::limits::check_pixels(bitmap.bmWidth.unsigned_abs(), bitmap.bmHeight.unsigned_abs())?;
+
     let clr_bits = bitmap.bmPlanes * bitmap.bmBitsPixel;
     let clr_bits = if clr_bits == 1 {
         1
@@ -555,66 +1743,48 @@ pub fn get_bitmap(out: &mut alloc::vec::Vec<u8>) -> SysResult<usize> {
         32
     };
 
-    let header_storage = RawMem::new_rust_mem(if clr_bits < 24 {
-        mem::size_of::<BITMAPINFOHEADER>() + mem::size_of::<RGBQUAD>() * (1 << clr_bits)
-    } else {
-        mem::size_of::<BITMAPINFOHEADER>()
-    });
+    let mut info = codec::bmp::InfoHeader::new(bitmap.bmWidth, bitmap.bmHeight, clr_bits)?;
+    let img_size = info.size_image as usize;
+    crate::limits::check_size(formats::CF_BITMAP, img_size)?;
+
+    let header_storage = RawMem::new_rust_mem(mem::size_of::<BITMAPINFOHEADER>() + mem::size_of::<RGBQUAD>() * info.colors_len());
 
     let header = unsafe {
         &mut *(header_storage.get() as *mut BITMAPINFO)
     };
 
     header.bmiHeader.biSize = mem::size_of::<BITMAPINFOHEADER>() as _;
-    header.bmiHeader.biWidth = bitmap.bmWidth;
-    header.bmiHeader.biHeight = bitmap.bmHeight;
-    header.bmiHeader.biPlanes = bitmap.bmPlanes;
-    header.bmiHeader.biBitCount = bitmap.bmBitsPixel;
+    header.bmiHeader.biWidth = info.width;
+    header.bmiHeader.biHeight = info.height;
+    header.bmiHeader.biPlanes = info.planes;
+    header.bmiHeader.biBitCount = info.bit_count;
     header.bmiHeader.biCompression = BI_RGB;
-    if clr_bits < 24 {
-        header.bmiHeader.biClrUsed = 1 << clr_bits;
-    }
-
-    header.bmiHeader.biSizeImage = ((((header.bmiHeader.biWidth * clr_bits + 31) & !31) / 8) * header.bmiHeader.biHeight) as _;
+    header.bmiHeader.biSizeImage = info.size_image;
+    header.bmiHeader.biClrUsed = info.clr_used;
     header.bmiHeader.biClrImportant = 0;
 
-    let img_size = header.bmiHeader.biSizeImage as usize;
     let out_before = out.len();
 
     let dc = crate::utils::Scope(unsafe { GetDC(ptr::null_mut()) }, free_dc);
-    let mut buffer = alloc::vec::Vec::new();
-    buffer.resize(img_size, 0u8);
+    let mut buffer = alloc::vec![0u8; img_size];
+
+    //Bitmap has no resolution of its own, so it is resolution of screen
+    let (dpi_x, dpi_y) = unsafe { (GetDeviceCaps(dc.0, LOGPIXELSX), GetDeviceCaps(dc.0, LOGPIXELSY)) };
+    if dpi_x > 0 && dpi_y > 0 {
+        let (x, y) = codec::Resolution::from_dpi(dpi_x as u32, dpi_y as u32).pels_per_meter();
+        info.x_pels_per_meter = x;
+        info.y_pels_per_meter = y;
+    }
 
     if unsafe { GetDIBits(dc.0, clipboard_data.as_ptr() as _, 0, bitmap.bmHeight as _, buffer.as_mut_ptr() as _, header_storage.get() as _, DIB_RGB_COLORS) } == 0 {
         return Err(SystemError::last());
     }
 
-    //Write header
-    out.extend_from_slice(&u16::to_le_bytes(0x4d42));
-    out.extend_from_slice(&u32::to_le_bytes(mem::size_of::<BITMAPFILEHEADER>() as u32 + header.bmiHeader.biSize + header.bmiHeader.biClrUsed * mem::size_of::<RGBQUAD>() as u32 + header.bmiHeader.biSizeImage));
-    out.extend_from_slice(&u32::to_le_bytes(0)); //2 * u16 of 0
-    out.extend_from_slice(&u32::to_le_bytes(mem::size_of::<BITMAPFILEHEADER>() as u32 + header.bmiHeader.biSize + header.bmiHeader.biClrUsed * mem::size_of::<RGBQUAD>() as u32));
-
-    out.extend_from_slice(&header.bmiHeader.biSize.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biWidth.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biHeight.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biPlanes.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biBitCount.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biCompression.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biSizeImage.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biXPelsPerMeter.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biYPelsPerMeter.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biClrUsed.to_le_bytes());
-    out.extend_from_slice(&header.bmiHeader.biClrImportant.to_le_bytes());
-
-    for color in unsafe { slice::from_raw_parts(header.bmiColors.as_ptr(), header.bmiHeader.biClrUsed as _) } {
-        out.push(color.rgbBlue);
-        out.push(color.rgbGreen);
-        out.push(color.rgbRed);
-        out.push(color.rgbReserved);
-    }
-
-    out.extend_from_slice(&buffer);
+    let colors = unsafe {
+        slice::from_raw_parts(header.bmiColors.as_ptr() as *const u8, info.colors_len() * mem::size_of::<RGBQUAD>())
+    };
+    codec::bmp::write_file(out, &info, colors, &buffer)?;
+    crate::utils::wipe(&mut buffer);
 
     Ok(out.len() - out_before)
 }
@@ -627,52 +1797,78 @@ pub fn set_bitamp(data: &[u8]) -> SysResult<()> {
 
 ///Sets bitmap (header + RGB) onto clipboard, from raw bytes.
 ///
-///Returns `ERROR_INCORRECT_SIZE` if size of data is not valid
+///Headers are validated against data, returning `ERROR_INCORRECT_SIZE` if data is truncated and
+///`ERROR_INVALID_DATA` if headers are not consistent.
 pub fn set_bitmap(data: &[u8]) -> SysResult<()> {
-    const FILE_HEADER_LEN: usize = mem::size_of::<BITMAPFILEHEADER>();
-    const INFO_HEADER_LEN: usize = mem::size_of::<BITMAPINFOHEADER>();
-
-    if data.len() <= (FILE_HEADER_LEN + INFO_HEADER_LEN) {
-        return Err(SystemError::new(ERROR_INCORRECT_SIZE as _));
-    }
-
-    let mut file_header = mem::MaybeUninit::<BITMAPFILEHEADER>::uninit();
-    let mut info_header = mem::MaybeUninit::<BITMAPINFOHEADER>::uninit();
-
-    let (file_header, info_header) = unsafe {
-        ptr::copy_nonoverlapping(data.as_ptr(), file_header.as_mut_ptr() as _, FILE_HEADER_LEN);
-        ptr::copy_nonoverlapping(data.as_ptr().add(FILE_HEADER_LEN), info_header.as_mut_ptr() as _, INFO_HEADER_LEN);
-        (file_header.assume_init(), info_header.assume_init())
-    };
-
-    if data.len() <= file_header.bfOffBits as usize {
-        return Err(SystemError::new(ERROR_INCORRECT_SIZE as _));
-    }
-
-    let bitmap = &data[file_header.bfOffBits as _..];
-
-    if bitmap.len() < info_header.biSizeImage as usize {
-        return Err(SystemError::new(ERROR_INCORRECT_SIZE as _));
-    }
+    let bitmap = codec::bmp::parse_file(data)?;
+    //Copy ensures alignment of headers, and conversion from legacy header.
+    let info = bitmap.bitmap_info();
 
     let dc = crate::utils::Scope(unsafe { GetDC(ptr::null_mut()) }, free_dc);
 
     let handle = unsafe {
-        CreateDIBitmap(dc.0, &info_header as _, CBM_INIT, bitmap.as_ptr() as _, &info_header as *const _ as *const BITMAPINFO, DIB_RGB_COLORS)
+        CreateDIBitmap(dc.0, info.as_ptr() as *const BITMAPINFOHEADER, CBM_INIT, bitmap.pixels.as_ptr() as _, info.as_ptr() as *const BITMAPINFO, DIB_RGB_COLORS)
     };
 
     if handle.is_null() {
         return Err(SystemError::last());
     }
+    let handle = crate::utils::Scope(handle, free_bitmap);
 
     let _ = empty();
-    if unsafe { SetClipboardData(formats::CF_BITMAP, handle as _).is_null() } {
+    if unsafe { SetClipboardData(formats::CF_BITMAP, handle.0 as _).is_null() } {
         return Err(SystemError::last());
     }
 
+    //SetClipboardData takes ownership
+    mem::forget(handle);
     Ok(())
 }
 
+///Reads `CF_BITMAP` as RGBA image.
+///
+///Translucent pixels of bitmap are expected to be premultiplied, as required by `AlphaBlend`,
+///and are converted to straight alpha.
+///
+///Returns number of bytes of pixels.
+pub fn get_bitmap_image(out: &mut codec::image::Image) -> SysResult<usize> {
+    let mut data = alloc::vec::Vec::new();
+    get_bitmap(&mut data)?;
+
+    let result = codec::bmp::parse_file(&data).and_then(|dib| codec::image::Image::from_dib(&dib));
+    crate::utils::wipe(&mut data);
+    let mut image = result?;
+    if !image.is_opaque() {
+        codec::image::unpremultiply(&mut image.pixels);
+    }
+
+    *out = image;
+    Ok(out.pixels.len())
+}
+
+///Places image onto clipboard as `CF_BITMAP`.
+///
+///Translucent pixels are premultiplied, as required by `AlphaBlend`.
+pub fn set_bitmap_image(image: &codec::image::Image) -> SysResult<()> {
+    image.validate()?;
+
+    let mut buffer = alloc::vec::Vec::new();
+    match image.is_opaque() {
+        true => image.write_bitmap(&mut buffer)?,
+        false => {
+            let mut image = image.clone();
+            codec::image::premultiply(&mut image.pixels);
+            let result = image.write_bitmap(&mut buffer);
+            crate::utils::wipe(&mut image.pixels);
+            result?
+        },
+    };
+
+    let result = set_bitmap(&buffer);
+    crate::utils::wipe(&mut buffer);
+    result
+}
+
 ///Enumerator over available clipboard formats.
 ///
 ///# Pre-conditions:
@@ -682,6 +1878,13 @@ pub struct EnumFormats {
     idx: u32
 }
 
+impl Default for EnumFormats {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EnumFormats {
     /// Constructs enumerator over all available formats.
     pub fn new() -> EnumFormats {
@@ -863,6 +2066,10 @@ pub fn format_name_big(format: u32) -> Option<String> {
 ///# Note:
 ///
 ///Custom format identifier is in range `0xC000...0xFFFF`.
+///
+///# Safety
+///
+///`name` must be null terminated.
 pub unsafe fn register_raw_format(name: &[u16]) -> Option<NonZeroU32> {
     debug_assert_eq!(name[name.len()-1], b'\0' as u16);
     NonZeroU32::new(RegisterClipboardFormatW(name.as_ptr()) )
@@ -917,3 +2124,56 @@ pub fn get_owner() -> Option<ptr::NonNull::<winapi::shared::windef::HWND__>> {
         GetClipboardOwner()
     })
 }
+
+///Places privacy markers onto clipboard, asking clipboard history, cloud clipboard and monitors to
+///ignore current content.
+///
+///Markers are added to already present formats, so it should be called after content is set.
+///
+///# Pre-conditions:
+///
+///* [open()](fn.open.html) has been called.
+pub fn set_sensitive_markers() -> SysResult<()> {
+    //Any content will do for exclusion marker, while rest expect DWORD set to 0.
+    const DISABLED: [u8; 4] = 0u32.to_le_bytes();
+
+    for name in &[formats::EXCLUDE_CLIPBOARD_CONTENT_FROM_MONITOR_PROCESSING, formats::CAN_INCLUDE_IN_CLIPBOARD_HISTORY, formats::CAN_UPLOAD_TO_CLOUD_CLIPBOARD] {
+        match register_format(name) {
+            Some(format) => set_without_clear(format.get(), &DISABLED)?,
+            None => return Err(SystemError::last()),
+        }
+    }
+
+    Ok(())
+}
+
+///Determines whether current clipboard content is marked as sensitive.
+///
+///Content is considered sensitive if it is excluded from monitor processing or it is disallowed
+///from either clipboard history or cloud clipboard.
+///
+///# Pre-conditions:
+///
+///* [open()](fn.open.html) has been called.
+pub fn is_sensitive() -> bool {
+    match register_format(formats::EXCLUDE_CLIPBOARD_CONTENT_FROM_MONITOR_PROCESSING) {
+        Some(format) if is_format_avail(format.get()) => return true,
+        _ => (),
+    }
+
+    for name in &[formats::CAN_INCLUDE_IN_CLIPBOARD_HISTORY, formats::CAN_UPLOAD_TO_CLOUD_CLIPBOARD] {
+        let format = match register_format(name) {
+            Some(format) if is_format_avail(format.get()) => format.get(),
+            _ => continue,
+        };
+
+        let mut value = [0u8; 4];
+        if let Ok(4) = get(format, &mut value) {
+            if u32::from_le_bytes(value) == 0 {
+                return true;
+            }
+        }
+    }
+
+    false
+}
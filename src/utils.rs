@@ -6,16 +6,19 @@ use crate::SysResult;
 
 const GHND: winapi::ctypes::c_uint = 0x42;
 
-const BYTES_LAYOUT: alloc::alloc::Layout = alloc::alloc::Layout::new::<u8>();
-
 #[inline]
-fn noop(_: *mut c_void) {
+fn noop(_: (*mut c_void, usize)) {
 }
 
 #[inline]
-fn free_rust_mem(data: *mut c_void) {
+fn free_rust_mem((data, size): (*mut c_void, usize)) {
+    #[cfg(feature = "zeroize")]
+    unsafe {
+        zeroize::Zeroize::zeroize(core::slice::from_raw_parts_mut(data as *mut mem::MaybeUninit<u8>, size));
+    }
+
     unsafe {
-        alloc::alloc::dealloc(data as _, BYTES_LAYOUT)
+        alloc::alloc::dealloc(data as _, alloc::alloc::Layout::array::<u8>(size).expect("To create layout for bytes"))
     }
 }
 
@@ -27,12 +30,93 @@ fn unlock_data(data: *mut c_void) {
 }
 
 #[inline]
-fn free_global_mem(data: *mut c_void) {
+fn free_global_mem((data, _): (*mut c_void, usize)) {
+    //Only memory that has not been passed to system is freed, so wipe it as it may still hold content.
+    #[cfg(feature = "zeroize")]
+    unsafe {
+        let ptr = winapi::um::winbase::GlobalLock(data);
+        if !ptr.is_null() {
+            let size = winapi::um::winbase::GlobalSize(data) as usize;
+            zeroize::Zeroize::zeroize(core::slice::from_raw_parts_mut(ptr as *mut mem::MaybeUninit<u8>, size));
+            winapi::um::winbase::GlobalUnlock(data);
+        }
+    }
+
     unsafe {
         winapi::um::winbase::GlobalFree(data);
     }
 }
 
+#[inline(always)]
+///Wipes whole storage of intermediate buffer, when `zeroize` feature is enabled, leaving it empty.
+pub fn wipe<T: Copy>(buffer: &mut alloc::vec::Vec<T>) {
+    #[cfg(feature = "zeroize")]
+    unsafe {
+        let size = buffer.capacity() * mem::size_of::<T>();
+        zeroize::Zeroize::zeroize(core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut mem::MaybeUninit<u8>, size));
+    }
+
+    buffer.clear();
+}
+
+///Intermediate buffer, which is wiped on drop, including early return on error.
+pub struct Wiped<T: Copy>(pub alloc::vec::Vec<T>);
+
+impl<T: Copy> Drop for Wiped<T> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+impl<T: Copy> core::ops::Deref for Wiped<T> {
+    type Target = alloc::vec::Vec<T>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Copy> core::ops::DerefMut for Wiped<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+///Intermediate text, which is wiped on drop, including early return on error.
+pub struct WipedString(pub alloc::string::String);
+
+impl Drop for WipedString {
+    #[inline(always)]
+    fn drop(&mut self) {
+        wipe(unsafe { self.0.as_mut_vec() });
+    }
+}
+
+impl core::ops::Deref for WipedString {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+///Appends `data` to `out`, wiping previous storage of `out` if it has to grow.
+pub fn append_wiped<T: Copy>(out: &mut alloc::vec::Vec<T>, data: &[T]) {
+    if out.capacity() - out.len() < data.len() {
+        let mut storage = alloc::vec::Vec::with_capacity(out.len() + data.len());
+        storage.extend_from_slice(out);
+        mem::swap(out, &mut storage);
+        wipe(&mut storage);
+    }
+
+    out.extend_from_slice(data);
+}
+
 pub struct Scope<T: Copy>(pub T, pub fn(T));
 
 impl<T: Copy> Drop for Scope<T> {
@@ -42,7 +126,7 @@ impl<T: Copy> Drop for Scope<T> {
     }
 }
 
-pub struct RawMem(Scope<*mut c_void>);
+pub struct RawMem(Scope<(*mut c_void, usize)>);
 
 impl RawMem {
     #[inline(always)]
@@ -51,7 +135,7 @@ impl RawMem {
             alloc::alloc::alloc_zeroed(alloc::alloc::Layout::array::<u8>(size).expect("To create layout for bytes"))
         };
         debug_assert!(!mem.is_null());
-        Self(Scope(mem as _, free_rust_mem))
+        Self(Scope((mem as _, size), free_rust_mem))
     }
 
     #[inline(always)]
@@ -61,19 +145,27 @@ impl RawMem {
             if mem.is_null() {
                 Err(error_code::SystemError::last())
             } else {
-                Ok(Self(Scope(mem, free_global_mem)))
+                Ok(Self(Scope((mem, size), free_global_mem)))
             }
         }
     }
 
     #[inline(always)]
     pub fn from_borrowed(ptr: ptr::NonNull<c_void>) -> Self {
-        Self(Scope(ptr.as_ptr(), noop))
+        Self(Scope((ptr.as_ptr(), 0), noop))
     }
 
     #[inline(always)]
     pub fn get(&self) -> *mut c_void {
-        (self.0).0
+        ((self.0).0).0
+    }
+
+    #[inline(always)]
+    ///Returns size of global memory, which is zero for discarded (i.e. empty) memory.
+    pub fn size(&self) -> usize {
+        unsafe {
+            winapi::um::winbase::GlobalSize(self.get()) as usize
+        }
     }
 
     #[inline(always)]
@@ -0,0 +1,157 @@
+use clipboard_win::auto_clear::{AutoClear, Clock, Snapshot, Action};
+
+use core::cell::Cell;
+use core::num::NonZeroU32;
+use core::time::Duration;
+
+struct FakeClock(Cell<Duration>);
+
+impl FakeClock {
+    fn new() -> Self {
+        Self(Cell::new(Duration::from_secs(100)))
+    }
+
+    fn advance(&self, secs: u64) {
+        self.0.set(self.0.get() + Duration::from_secs(secs));
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.0.get()
+    }
+}
+
+fn snapshot(seq_num: u32, owner: Option<usize>) -> Snapshot {
+    Snapshot {
+        seq_num: NonZeroU32::new(seq_num),
+        owner,
+    }
+}
+
+#[test]
+fn should_clear_unchanged_content_after_deadline() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+    let written = snapshot(5, Some(0xdead));
+
+    assert_eq!(auto.poll(&written), Action::Idle);
+
+    auto.schedule(written, Duration::from_secs(10));
+    assert!(auto.is_pending());
+    assert_eq!(auto.remaining(), Some(Duration::from_secs(10)));
+    assert_eq!(auto.poll(&written), Action::Wait(Duration::from_secs(10)));
+
+    clock.advance(4);
+    assert_eq!(auto.remaining(), Some(Duration::from_secs(6)));
+    assert_eq!(auto.poll(&written), Action::Wait(Duration::from_secs(6)));
+
+    clock.advance(6);
+    assert_eq!(auto.remaining(), Some(Duration::from_secs(0)));
+    assert_eq!(auto.poll(&written), Action::Clear);
+    assert!(!auto.is_pending());
+    assert_eq!(auto.poll(&written), Action::Idle);
+}
+
+#[test]
+fn should_skip_replaced_content() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    auto.schedule(snapshot(5, Some(1)), Duration::from_secs(1));
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(6, Some(1))), Action::Skip);
+    assert!(!auto.is_pending());
+
+    auto.schedule(snapshot(5, Some(1)), Duration::from_secs(1));
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(5, Some(2))), Action::Skip);
+
+    auto.schedule(snapshot(5, Some(1)), Duration::from_secs(1));
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Skip);
+}
+
+#[test]
+fn should_not_compare_before_deadline() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    auto.schedule(snapshot(5, None), Duration::from_secs(3));
+    clock.advance(1);
+    //Content changes meanwhile, but decision is made only at deadline.
+    assert_eq!(auto.poll(&snapshot(6, None)), Action::Wait(Duration::from_secs(2)));
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Clear);
+}
+
+#[test]
+fn should_cancel() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    assert!(!auto.cancel());
+    auto.schedule(snapshot(5, None), Duration::from_secs(1));
+    assert!(auto.cancel());
+    assert!(!auto.is_pending());
+    assert_eq!(auto.remaining(), None);
+
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Idle);
+}
+
+#[test]
+fn should_replace_previous_schedule() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    auto.schedule(snapshot(5, None), Duration::from_secs(1));
+    auto.schedule(snapshot(7, None), Duration::from_secs(5));
+
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(7, None)), Action::Wait(Duration::from_secs(3)));
+    clock.advance(3);
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Skip);
+}
+
+#[test]
+fn should_flush_on_exit() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    assert_eq!(auto.flush(&snapshot(5, None)), Action::Idle);
+
+    auto.schedule(snapshot(5, None), Duration::from_secs(60));
+    assert_eq!(auto.flush(&snapshot(5, None)), Action::Clear);
+    assert!(!auto.is_pending());
+
+    auto.schedule(snapshot(5, None), Duration::from_secs(60));
+    assert_eq!(auto.flush(&snapshot(8, None)), Action::Skip);
+}
+
+#[test]
+fn should_saturate_deadline() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    auto.schedule(snapshot(5, None), Duration::MAX);
+    clock.advance(1_000_000);
+    assert!(matches!(auto.poll(&snapshot(5, None)), Action::Wait(_)));
+}
+
+#[test]
+fn should_postpone_for_retry() {
+    let clock = FakeClock::new();
+    let mut auto = AutoClear::new(&clock);
+
+    assert!(!auto.postpone(Duration::from_secs(1)));
+    assert!(!auto.is_pending());
+
+    auto.schedule(snapshot(5, None), Duration::from_secs(1));
+    clock.advance(1);
+    //Clipboard could not be opened at deadline
+    assert!(auto.postpone(Duration::from_secs(2)));
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Wait(Duration::from_secs(2)));
+    clock.advance(2);
+    assert_eq!(auto.poll(&snapshot(5, None)), Action::Clear);
+}
@@ -0,0 +1,415 @@
+use clipboard_win::codec::{Error, Resolution};
+use clipboard_win::codec::bmp::{self, InfoHeader, Profile, ProfileBuf, BI_RGB, BI_BITFIELDS, BI_RLE8, BI_PNG, FILE_HEADER_SIZE, INFO_HEADER_SIZE, V5_HEADER_SIZE};
+
+fn test_image() -> Vec<u8> {
+    std::fs::read("tests/test-image.bmp").expect("Read test image")
+}
+
+fn info_header(width: i32, height: i32, bit_count: u16, compression: u32, size_image: u32, clr_used: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&bit_count.to_le_bytes());
+    out.extend_from_slice(&compression.to_le_bytes());
+    out.extend_from_slice(&size_image.to_le_bytes());
+    out.extend_from_slice(&[0; 8]);
+    out.extend_from_slice(&clr_used.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out
+}
+
+#[test]
+fn should_parse_file() {
+    let data = test_image();
+    let bitmap = bmp::parse_file(&data).expect("Parse bitmap");
+
+    assert_eq!(bitmap.header.size as usize, INFO_HEADER_SIZE);
+    assert_eq!(bitmap.header.width, 750);
+    assert_eq!(bitmap.header.height, 300);
+    assert_eq!(bitmap.header.bit_count, 32);
+    assert_eq!(bitmap.header.compression, BI_RGB);
+    assert_eq!(bitmap.header.stride(), Ok(750 * 4));
+    assert!(!bitmap.header.is_top_down());
+    assert!(bitmap.colors.is_empty());
+    assert_eq!(bitmap.pixels_offset, FILE_HEADER_SIZE + INFO_HEADER_SIZE);
+    assert_eq!(bitmap.pixels, &data[bitmap.pixels_offset..]);
+    assert_eq!(bitmap.bitmap_info().len() * 4, INFO_HEADER_SIZE);
+}
+
+#[test]
+fn should_convert_dib_to_file() {
+    let data = test_image();
+
+    let dib = bmp::parse_dib(&data[FILE_HEADER_SIZE..]).expect("Parse DIB");
+    assert_eq!(dib.pixels_offset, INFO_HEADER_SIZE);
+
+    let mut out = Vec::new();
+    assert_eq!(bmp::dib_to_file(&data[FILE_HEADER_SIZE..], &mut out), Ok(data.len()));
+    assert_eq!(out, data);
+}
+
+#[test]
+fn should_write_file() {
+    let header = InfoHeader::new(3, 2, 24).expect("Create header");
+    assert_eq!(header.stride(), Ok(12));
+    assert_eq!(header.size_image, 24);
+    assert_eq!(header.clr_used, 0);
+
+    let pixels = [0xAAu8; 24];
+    let mut out = Vec::new();
+    bmp::write_file(&mut out, &header, &[], &pixels).expect("Write bitmap");
+    assert_eq!(out.len(), FILE_HEADER_SIZE + INFO_HEADER_SIZE + pixels.len());
+
+    let bitmap = bmp::parse_file(&out).expect("Parse bitmap");
+    assert_eq!(bitmap.header, header);
+    assert_eq!(bitmap.pixels, &pixels[..]);
+
+    assert_eq!(bmp::write_file(&mut out, &header, &[], &pixels[..23]), Err(Error::Truncated));
+}
+
+#[test]
+fn should_write_palette_file() {
+    let header = InfoHeader::new(9, 1, 1).expect("Create header");
+    assert_eq!(header.clr_used, 2);
+    assert_eq!(header.stride(), Ok(4));
+
+    let colors = [0, 0, 0, 0, 255, 255, 255, 0];
+    let mut out = Vec::new();
+    bmp::write_file(&mut out, &header, &colors, &[0xFF, 0x80, 0, 0]).expect("Write bitmap");
+
+    let bitmap = bmp::parse_file(&out).expect("Parse bitmap");
+    assert_eq!(bitmap.colors, &colors[..]);
+    assert_eq!(bitmap.pixels, &[0xFF, 0x80, 0, 0]);
+
+    assert_eq!(bmp::write_file(&mut out, &header, &colors[..4], &[0xFF, 0x80, 0, 0]), Err(Error::Truncated));
+}
+
+#[test]
+fn should_reject_invalid_headers() {
+    assert!(InfoHeader::new(0, 1, 24).is_err());
+    assert!(InfoHeader::new(1, 0, 24).is_err());
+    assert!(InfoHeader::new(1, 1, 7).is_err());
+    assert!(InfoHeader::new(1, i32::MIN, 24).is_err());
+    assert!(InfoHeader::new(i32::MAX, i32::MAX, 32).is_err());
+
+    let mut dib = info_header(1, 1, 24, BI_RGB, 0, 0);
+    dib[12] = 2;
+    dib.extend_from_slice(&[0; 4]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Invalid("bitmap planes"));
+
+    let mut dib = info_header(1, 1, 24, BI_RGB, 0, 0);
+    dib[0] = 41;
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Unsupported("bitmap header size"));
+
+    let dib = info_header(1, 1, 24, 42, 0, 0);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Unsupported("bitmap compression"));
+
+    let mut dib = info_header(1, 1, 8, BI_RGB, 0, 257);
+    dib.extend_from_slice(&[0; 257 * 4 + 4]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Invalid("bitmap color table size"));
+
+    let mut dib = info_header(2, 2, 24, BI_RGB, 4, 0);
+    dib.extend_from_slice(&[0; 16]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Invalid("bitmap image size"));
+
+    let mut dib = info_header(2, 2, 8, BI_RLE8, 0, 0);
+    dib.extend_from_slice(&[0; 256 * 4 + 16]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Invalid("compressed bitmap without size"));
+
+    let mut dib = info_header(2, -2, 8, BI_RLE8, 4, 0);
+    dib.extend_from_slice(&[0; 256 * 4 + 4]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Invalid("compressed bitmap cannot be top-down"));
+}
+
+#[test]
+fn should_reject_out_of_bounds() {
+    let data = test_image();
+
+    assert_eq!(bmp::parse_file(&data[..data.len() - 1]).unwrap_err(), Error::Truncated);
+    assert_eq!(bmp::parse_file(&data[..FILE_HEADER_SIZE + 20]).unwrap_err(), Error::Truncated);
+    assert_eq!(bmp::parse_file(&data[..5]).unwrap_err(), Error::Truncated);
+    assert_eq!(bmp::parse_dib(&data[FILE_HEADER_SIZE..data.len() - 1]).unwrap_err(), Error::Truncated);
+
+    let mut invalid = data.clone();
+    invalid[0] = b'X';
+    assert_eq!(bmp::parse_file(&invalid).unwrap_err(), Error::Invalid("bitmap file signature"));
+
+    let mut invalid = data.clone();
+    invalid[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(bmp::parse_file(&invalid).unwrap_err(), Error::Truncated);
+
+    let mut invalid = data.clone();
+    invalid[10..14].copy_from_slice(&20u32.to_le_bytes());
+    assert_eq!(bmp::parse_file(&invalid).unwrap_err(), Error::Invalid("bitmap pixels overlap header"));
+
+    let mut invalid = data.clone();
+    //Height of i32::MAX rows
+    invalid[22..26].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert!(bmp::parse_file(&invalid).is_err());
+
+    let mut invalid = data;
+    invalid[18..22].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert!(bmp::parse_file(&invalid).is_err());
+
+    let mut dib = info_header(1, 1, 8, BI_RGB, 0, 0);
+    dib.extend_from_slice(&[0; 255 * 4]);
+    assert_eq!(bmp::parse_dib(&dib).unwrap_err(), Error::Truncated);
+}
+
+#[test]
+fn should_parse_bitfields() {
+    let mut dib = info_header(1, -1, 32, BI_BITFIELDS, 0, 0);
+    dib.extend_from_slice(&0x00FF0000u32.to_le_bytes());
+    dib.extend_from_slice(&0x0000FF00u32.to_le_bytes());
+    dib.extend_from_slice(&0x000000FFu32.to_le_bytes());
+    dib.extend_from_slice(&[1, 2, 3, 4]);
+
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+    assert!(bitmap.header.is_top_down());
+    assert_eq!(bitmap.header.masks, [0x00FF0000, 0x0000FF00, 0x000000FF, 0]);
+    assert_eq!(bitmap.header_bytes.len(), INFO_HEADER_SIZE + 12);
+    assert_eq!(bitmap.pixels_offset, INFO_HEADER_SIZE + 12);
+    assert_eq!(bitmap.pixels, &[1, 2, 3, 4]);
+    assert_eq!(bitmap.bitmap_info().len() * 4, INFO_HEADER_SIZE + 12);
+}
+
+#[test]
+fn should_parse_v5_header() {
+    let mut dib = info_header(1, 1, 32, BI_BITFIELDS, 4, 0);
+    dib[0] = V5_HEADER_SIZE as u8;
+    dib.extend_from_slice(&0x00FF0000u32.to_le_bytes());
+    dib.extend_from_slice(&0x0000FF00u32.to_le_bytes());
+    dib.extend_from_slice(&0x000000FFu32.to_le_bytes());
+    dib.extend_from_slice(&0xFF000000u32.to_le_bytes());
+    dib.resize(V5_HEADER_SIZE, 0);
+    dib.extend_from_slice(&[1, 2, 3, 4]);
+
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+    assert_eq!(bitmap.header.masks, [0x00FF0000, 0x0000FF00, 0x000000FF, 0xFF000000]);
+    assert_eq!(bitmap.pixels_offset, V5_HEADER_SIZE);
+    assert_eq!(bitmap.pixels, &[1, 2, 3, 4]);
+
+    //Truncated header
+    assert_eq!(bmp::parse_dib(&dib[..100]).unwrap_err(), Error::Truncated);
+}
+
+#[test]
+fn should_parse_compressed() {
+    let mut dib = info_header(16, 16, 0, BI_PNG, 3, 0);
+    dib.extend_from_slice(&[1, 2, 3]);
+
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+    assert!(bitmap.header.is_compressed());
+    assert_eq!(bitmap.pixels, &[1, 2, 3]);
+}
+
+#[test]
+fn should_convert_core_header() {
+    let mut file = Vec::new();
+    file.extend_from_slice(b"BM");
+    file.extend_from_slice(&0u32.to_le_bytes());
+    file.extend_from_slice(&0u32.to_le_bytes());
+    file.extend_from_slice(&(FILE_HEADER_SIZE as u32 + 12 + 6).to_le_bytes());
+    file.extend_from_slice(&12u32.to_le_bytes());
+    file.extend_from_slice(&2u16.to_le_bytes());
+    file.extend_from_slice(&1u16.to_le_bytes());
+    file.extend_from_slice(&1u16.to_le_bytes());
+    file.extend_from_slice(&1u16.to_le_bytes());
+    file.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+    file.extend_from_slice(&[0x40, 0, 0, 0]);
+
+    let bitmap = bmp::parse_file(&file).expect("Parse bitmap");
+    assert_eq!(bitmap.header.width, 2);
+    assert_eq!(bitmap.header.colors_len(), 2);
+    assert_eq!(bitmap.colors, &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(bitmap.pixels, &[0x40, 0, 0, 0]);
+
+    let info = bitmap.bitmap_info();
+    let info: Vec<u8> = info.iter().flat_map(|word| word.to_ne_bytes()).collect();
+    assert_eq!(info.len(), INFO_HEADER_SIZE + 8);
+    let header = InfoHeader::parse(&info).expect("Parse converted header");
+    assert_eq!(header.size as usize, INFO_HEADER_SIZE);
+    assert_eq!(header.width, 2);
+    assert_eq!(header.clr_used, 2);
+    assert_eq!(&info[INFO_HEADER_SIZE..], &[1, 2, 3, 0, 4, 5, 6, 0]);
+}
+
+#[test]
+fn should_decode_rgba() {
+    //Bottom-up palette bitmap: second row is stored first
+    let header = InfoHeader::new(2, 2, 1).expect("Create header");
+    let colors = [0, 0, 255, 0, 255, 0, 0, 0];
+    let mut out = Vec::new();
+    bmp::write_file(&mut out, &header, &colors, &[0x40, 0, 0, 0, 0x80, 0, 0, 0]).expect("Write bitmap");
+
+    let mut rgba = Vec::new();
+    assert_eq!(bmp::parse_file(&out).expect("Parse bitmap").to_rgba(&mut rgba), Ok(16));
+    assert_eq!(rgba, [0, 0, 255, 255, 255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 255, 255]);
+
+    //16 bits with default 5-5-5 layout
+    let mut dib = info_header(2, -1, 16, BI_RGB, 0, 0);
+    dib.extend_from_slice(&[0x00, 0x7C, 0x1F, 0x00]);
+    let mut rgba = Vec::new();
+    bmp::parse_dib(&dib).expect("Parse DIB").to_rgba(&mut rgba).expect("Decode");
+    assert_eq!(rgba, [255, 0, 0, 255, 0, 0, 255, 255]);
+
+    //32 bits without alpha is opaque
+    let mut dib = info_header(1, 1, 32, BI_RGB, 0, 0);
+    dib.extend_from_slice(&[1, 2, 3, 0]);
+    let mut rgba = Vec::new();
+    bmp::parse_dib(&dib).expect("Parse DIB").to_rgba(&mut rgba).expect("Decode");
+    assert_eq!(rgba, [3, 2, 1, 255]);
+
+    //Alpha mask of V5 header
+    let mut dib = info_header(1, 1, 32, BI_BITFIELDS, 4, 0);
+    dib[0] = V5_HEADER_SIZE as u8;
+    dib.extend_from_slice(&0x00FF0000u32.to_le_bytes());
+    dib.extend_from_slice(&0x0000FF00u32.to_le_bytes());
+    dib.extend_from_slice(&0x000000FFu32.to_le_bytes());
+    dib.extend_from_slice(&0xFF000000u32.to_le_bytes());
+    dib.resize(V5_HEADER_SIZE, 0);
+    dib.extend_from_slice(&[1, 2, 3, 4]);
+    let mut rgba = Vec::new();
+    bmp::parse_dib(&dib).expect("Parse DIB").to_rgba(&mut rgba).expect("Decode");
+    assert_eq!(rgba, [3, 2, 1, 4]);
+
+    let mut dib = info_header(16, 16, 0, BI_PNG, 3, 0);
+    dib.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(bmp::parse_dib(&dib).expect("Parse DIB").to_rgba(&mut rgba), Err(Error::Unsupported("compressed bitmap")));
+}
+
+//Writes 2x1 32-bit DIB with BITMAPV5HEADER, followed by pixels and profile.
+fn v5_dib(cs_type: u32, profile: &[u8]) -> Vec<u8> {
+    let mut dib = info_header(2, 1, 32, BI_BITFIELDS, 8, 0);
+    dib[0] = V5_HEADER_SIZE as u8;
+    for mask in &[0x00FF0000u32, 0x0000FF00, 0x000000FF, 0xFF000000, cs_type] {
+        dib.extend_from_slice(&mask.to_le_bytes());
+    }
+    dib.resize(108, 0);
+    let profile_offset = match profile.len() {
+        0 => 0,
+        _ => V5_HEADER_SIZE as u32 + 8,
+    };
+    for value in &[bmp::LCS_GM_GRAPHICS, profile_offset, profile.len() as u32, 0] {
+        dib.extend_from_slice(&value.to_le_bytes());
+    }
+    dib.extend_from_slice(&[0x10, 0x20, 0x30, 0xFF, 0x40, 0x50, 0x60, 0x80]);
+    dib.extend_from_slice(profile);
+    dib
+}
+
+#[test]
+fn should_parse_profile() {
+    let profile = b"\0\0\x02\x30ADBE\x02\x10\0\0mntrRGB XYZ ";
+    let dib = v5_dib(bmp::PROFILE_EMBEDDED, profile);
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+    assert_eq!(bitmap.pixels, &[0x10, 0x20, 0x30, 0xFF, 0x40, 0x50, 0x60, 0x80]);
+    assert_eq!(bitmap.profile, Some(Profile::Embedded(&profile[..])));
+    assert_eq!(bitmap.profile.expect("Profile").linked_path(), None);
+    assert_eq!(bitmap.profile.expect("Profile").to_buf(), ProfileBuf::Embedded(profile.to_vec()));
+
+    //Profile offset is relative to header, rather than to BMP file
+    let mut file = Vec::new();
+    bmp::dib_to_file(&dib, &mut file).expect("Convert to file");
+    assert_eq!(bmp::parse_file(&file).expect("Parse file").profile, Some(Profile::Embedded(&profile[..])));
+
+    //Profile is out of reach of GDI, so it is not referenced by BITMAPINFO
+    let info: Vec<u8> = bitmap.bitmap_info().iter().flat_map(|word| word.to_ne_bytes()).collect();
+    assert_eq!(info.len(), V5_HEADER_SIZE);
+    assert_eq!(&info[56..60], &bmp::LCS_SRGB.to_le_bytes());
+    assert_eq!(&info[112..120], &[0; 8]);
+    assert_eq!(&info[..56], &dib[..56]);
+
+    let dib = v5_dib(bmp::PROFILE_LINKED, b"C:\\Profiles\\\x80 Wide\x96gamut.icc\0");
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+    let profile = bitmap.profile.expect("Profile");
+    assert_eq!(profile, Profile::Linked(b"C:\\Profiles\\\x80 Wide\x96gamut.icc"));
+    assert_eq!(profile.linked_path().expect("Path"), "C:\\Profiles\\\u{20AC} Wide\u{2013}gamut.icc");
+    assert_eq!(profile.to_buf(), ProfileBuf::Linked("C:\\Profiles\\\u{20AC} Wide\u{2013}gamut.icc".to_owned()));
+
+    //Color spaces without profile
+    assert_eq!(bmp::parse_dib(&v5_dib(bmp::LCS_SRGB, &[])).expect("Parse DIB").profile, None);
+    assert_eq!(bmp::parse_dib(&v5_dib(bmp::LCS_WINDOWS_COLOR_SPACE, b"ignored")).expect("Parse DIB").profile, None);
+    assert_eq!(bmp::parse_dib(&v5_dib(bmp::PROFILE_EMBEDDED, &[])).expect("Parse DIB").profile, None);
+}
+
+#[test]
+fn should_reject_invalid_profile() {
+    let dib = v5_dib(bmp::PROFILE_EMBEDDED, &[1, 2, 3, 4]);
+    assert_eq!(bmp::parse_dib(&dib[..dib.len() - 1]).unwrap_err(), Error::Truncated);
+
+    let mut invalid = dib.clone();
+    invalid[112] = 100;
+    assert_eq!(bmp::parse_dib(&invalid).unwrap_err(), Error::Invalid("bitmap profile overlaps header"));
+
+    let mut invalid = dib.clone();
+    invalid[116..120].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(bmp::parse_dib(&invalid).unwrap_err(), Error::Truncated);
+}
+
+#[test]
+fn should_write_dibv5() {
+    let profile = b"Wide gamut profile";
+    let dib = v5_dib(bmp::PROFILE_EMBEDDED, profile);
+    let bitmap = bmp::parse_dib(&dib).expect("Parse DIB");
+
+    let mut out = Vec::new();
+    assert_eq!(bmp::write_dibv5(&mut out, &bitmap), Ok(dib.len()));
+    let written = bmp::parse_dib(&out).expect("Parse written DIB");
+    assert_eq!(written.header.size as usize, V5_HEADER_SIZE);
+    assert_eq!(written.header.masks, bitmap.header.masks);
+    assert_eq!(written.pixels, bitmap.pixels);
+    assert_eq!(written.profile, Some(Profile::Embedded(&profile[..])));
+
+    //Linked path is written with terminator
+    let mut linked = bitmap;
+    linked.profile = Some(Profile::Linked(b"sRGB.icc"));
+    out.clear();
+    bmp::write_dibv5(&mut out, &linked).expect("Write");
+    assert_eq!(&out[out.len() - 9..], b"sRGB.icc\0");
+    assert_eq!(bmp::parse_dib(&out).expect("Parse written DIB").profile, linked.profile);
+
+    //Bitmap without profile is marked as sRGB, with color table converted from core header
+    let mut core = Vec::new();
+    core.extend_from_slice(&12u32.to_le_bytes());
+    core.extend_from_slice(&1u16.to_le_bytes());
+    core.extend_from_slice(&1u16.to_le_bytes());
+    core.extend_from_slice(&1u16.to_le_bytes());
+    core.extend_from_slice(&1u16.to_le_bytes());
+    core.extend_from_slice(&[0, 0, 0, 0xFF, 0xFF, 0xFF]);
+    core.extend_from_slice(&[0x80, 0, 0, 0]);
+    let bitmap = bmp::parse_dib(&core).expect("Parse core DIB");
+
+    out.clear();
+    assert_eq!(bmp::write_dibv5(&mut out, &bitmap), Ok(V5_HEADER_SIZE + 8 + 4));
+    assert_eq!(&out[56..60], &bmp::LCS_SRGB.to_le_bytes());
+    let written = bmp::parse_dib(&out).expect("Parse written DIB");
+    assert_eq!(written.header.clr_used, 2);
+    assert_eq!(written.colors, &[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]);
+    assert_eq!(written.pixels, &[0x80, 0, 0, 0]);
+    assert_eq!(written.profile, None);
+}
+
+#[test]
+fn should_map_resolution() {
+    assert_eq!(Resolution::from_dpi(96, 72), Resolution { x: 3780, y: 2835 });
+    assert_eq!(Resolution::from_dpi(300, 600), Resolution { x: 11811, y: 23622 });
+    assert_eq!(Resolution { x: 3780, y: 2835 }.dpi(), (96, 72));
+    assert_eq!(Resolution { x: 3779, y: 11811 }.dpi(), (96, 300));
+    assert_eq!(Resolution::from_dpi(u32::MAX, 1).x, u32::MAX);
+
+    assert_eq!(Resolution::from_pels_per_meter(3780, 3780), Some(Resolution { x: 3780, y: 3780 }));
+    assert_eq!(Resolution::from_pels_per_meter(0, 3780), None);
+    assert_eq!(Resolution::from_pels_per_meter(3780, -1), None);
+    assert_eq!(Resolution { x: 3780, y: u32::MAX }.pels_per_meter(), (3780, i32::MAX));
+
+    let mut dib = info_header(1, 1, 32, BI_RGB, 4, 0);
+    dib[24..32].copy_from_slice(&[0xC4, 0x0E, 0, 0, 0x13, 0x0B, 0, 0]);
+    dib.extend_from_slice(&[0; 4]);
+    assert_eq!(bmp::parse_dib(&dib).expect("Parse DIB").resolution(), Some(Resolution { x: 3780, y: 2835 }));
+    dib[24] = 0;
+    dib[25] = 0;
+    assert_eq!(bmp::parse_dib(&dib).expect("Parse DIB").resolution(), None);
+}
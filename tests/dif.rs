@@ -0,0 +1,64 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::grid::Value;
+use clipboard_win::codec::dif;
+
+const EXCEL: &str = "TABLE\r\n0,1\r\n\"EXCEL\"\r\nVECTORS\r\n0,3\r\n\"\"\r\nTUPLES\r\n0,2\r\n\"\"\r\nDATA\r\n0,0\r\n\"\"\r\n\
+-1,0\r\nBOT\r\n1,0\r\n\"Name\"\r\n1,0\r\n\"say \"\"hi\"\"\"\r\n1,0\r\n\"\"\r\n\
+-1,0\r\nBOT\r\n0,-1.5E+2\r\nV\r\n0,1\r\nTRUE\r\n0,0\r\nNA\r\n\
+-1,0\r\nEOD\r\n\0";
+
+fn text(value: &str) -> Value {
+    Value::Text(value.to_owned())
+}
+
+#[test]
+fn should_parse_excel_output() {
+    let mut rows = vec![vec![Value::Empty]];
+    assert_eq!(dif::parse(EXCEL, &mut rows).expect("Parse"), 2);
+    assert_eq!(rows, vec![
+        vec![Value::Empty],
+        vec![text("Name"), text("say \"hi\""), Value::Empty],
+        vec![Value::Number(-150.0), Value::Bool(true), Value::Error("#N/A".to_owned())],
+    ]);
+}
+
+#[test]
+fn should_round_trip() {
+    let rows = vec![
+        vec![text("a,b"), text("quote \""), Value::Empty, Value::Bool(false)],
+        vec![Value::Number(0.1), Value::Number(-1e300), Value::Error("#N/A".to_owned())],
+        vec![],
+    ];
+
+    let mut out = String::new();
+    assert_eq!(dif::write(&mut out, &rows).expect("Write"), out.len());
+    assert!(out.starts_with("TABLE\r\n0,1\r\n\"\"\r\nVECTORS\r\n0,4\r\n\"\"\r\nTUPLES\r\n0,3\r\n"));
+    assert!(out.ends_with("-1,0\r\nBOT\r\n-1,0\r\nEOD\r\n"));
+
+    let mut output = Vec::new();
+    assert_eq!(dif::parse(&out, &mut output).expect("Parse back"), 3);
+    assert_eq!(output, rows);
+
+    out.clear();
+    dif::write(&mut out, &[vec![Value::Error("#DIV/0!".to_owned())]]).expect("Write");
+    output.clear();
+    dif::parse(&out, &mut output).expect("Parse back");
+    assert_eq!(output, [[Value::Error("#VALUE!".to_owned())]]);
+}
+
+#[test]
+fn should_reject_invalid_content() {
+    let mut rows = Vec::new();
+    assert_eq!(dif::parse("", &mut rows), Err(Error::Truncated));
+    assert_eq!(dif::parse("TABLE\r\n0,1\r\n\"\"\r\n", &mut rows), Err(Error::Truncated));
+    assert_eq!(dif::parse("DATA\r\n0,0\r\n\"\"\r\n-1,0\r\nBOT\r\n0,1\r\nV\r\n", &mut rows), Err(Error::Truncated));
+    assert_eq!(dif::parse("DATA\r\n0,0\r\n\"\"\r\n0,1\r\nV\r\n-1,0\r\nEOD\r\n", &mut rows), Err(Error::Invalid("value outside of tuple")));
+    assert_eq!(dif::parse("DATA\r\n0,0\r\n\"\"\r\n-1,0\r\nBOT\r\n0,x\r\nV\r\n-1,0\r\nEOD\r\n", &mut rows), Err(Error::Invalid("numeric value")));
+    assert_eq!(dif::parse("DATA\r\n0,0\r\n\"\"\r\n-1,0\r\nBOT\r\n2,0\r\n\"\"\r\n-1,0\r\nEOD\r\n", &mut rows), Err(Error::Invalid("data pair type")));
+    assert!(rows.is_empty());
+
+    let mut out = String::from("prefix");
+    assert_eq!(dif::write(&mut out, &[[text("line\nbreak")]]), Err(Error::Invalid("cell contains line break or null character")));
+    assert_eq!(dif::write(&mut out, &[[Value::Number(f64::INFINITY)]]), Err(Error::Invalid("number is not finite")));
+    assert_eq!(out, "prefix");
+}
@@ -0,0 +1,124 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::dropfiles::{self, Name, Point, HEADER_SIZE};
+
+fn header(files_offset: u32, x: i32, y: i32, non_client: bool, wide: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&files_offset.to_le_bytes());
+    out.extend_from_slice(&x.to_le_bytes());
+    out.extend_from_slice(&y.to_le_bytes());
+    out.extend_from_slice(&(non_client as u32).to_le_bytes());
+    out.extend_from_slice(&(wide as u32).to_le_bytes());
+    out
+}
+
+fn wide_list(names: &[&str]) -> Vec<u8> {
+    let mut out = header(HEADER_SIZE as u32, 0, 0, false, true);
+    for name in names {
+        for unit in name.encode_utf16().chain(Some(0)) {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+    out.extend_from_slice(&[0, 0]);
+    out
+}
+
+fn to_strings(data: &[u8]) -> Vec<String> {
+    dropfiles::parse(data).expect("Parse DROPFILES").files().map(|name| match name {
+        Name::Wide(name) => {
+            let mut out = String::new();
+            name.push_lossy(&mut out);
+            out
+        },
+        Name::Ansi(name) => String::from_utf8(name.to_vec()).unwrap(),
+    }).collect()
+}
+
+#[test]
+fn should_parse_wide_list() {
+    let data = wide_list(&["C:\\file.txt", "D:\\каталог\\файл"]);
+    let files = dropfiles::parse(&data).expect("Parse DROPFILES");
+
+    assert!(files.wide);
+    assert!(!files.non_client);
+    assert_eq!(files.point, Point::default());
+    assert_eq!(files.len(), 2);
+    assert_eq!(files.files().len(), 2);
+    assert_eq!(to_strings(&data), ["C:\\file.txt", "D:\\каталог\\файл"]);
+}
+
+#[test]
+fn should_parse_ansi_list() {
+    let mut data = header(HEADER_SIZE as u32 + 4, -5, 10, true, false);
+    data.extend_from_slice(&[0xFF; 4]);
+    data.extend_from_slice(b"C:\\a.txt\0C:\\b.txt\0\0");
+
+    let files = dropfiles::parse(&data).expect("Parse DROPFILES");
+    assert!(!files.wide);
+    assert!(files.non_client);
+    assert_eq!(files.point, Point { x: -5, y: 10 });
+    assert_eq!(to_strings(&data), ["C:\\a.txt", "C:\\b.txt"]);
+
+    let mut files = files.files();
+    assert_eq!(files.next(), Some(Name::Ansi(b"C:\\a.txt")));
+    assert_eq!(files.next(), Some(Name::Ansi(b"C:\\b.txt")));
+    assert_eq!(files.next(), None);
+}
+
+#[test]
+fn should_parse_empty_list() {
+    let data = wide_list(&[]);
+    let files = dropfiles::parse(&data).expect("Parse DROPFILES");
+    assert!(files.is_empty());
+    assert_eq!(files.files().next(), None);
+
+    //Trailing data after terminator is ignored
+    let mut data = header(HEADER_SIZE as u32, 0, 0, false, false);
+    data.extend_from_slice(b"\0garbage");
+    assert!(dropfiles::parse(&data).expect("Parse DROPFILES").is_empty());
+}
+
+#[test]
+fn should_keep_unpaired_surrogates() {
+    let mut data = header(HEADER_SIZE as u32, 0, 0, false, true);
+    for unit in &[0x43u16, 0xD800, 0x78, 0, 0] {
+        data.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    let files = dropfiles::parse(&data).expect("Parse DROPFILES");
+    match files.files().next() {
+        Some(Name::Wide(name)) => {
+            assert_eq!(name.len(), 3);
+            assert_eq!(name.units().collect::<Vec<_>>(), [0x43, 0xD800, 0x78]);
+        },
+        name => panic!("Unexpected name: {:?}", name),
+    }
+    assert_eq!(to_strings(&data), ["C\u{FFFD}x"]);
+}
+
+#[test]
+fn should_reject_malformed() {
+    assert_eq!(dropfiles::parse(&[0; HEADER_SIZE - 1]).unwrap_err(), Error::Truncated);
+
+    let data = header(HEADER_SIZE as u32 - 1, 0, 0, false, false);
+    assert_eq!(dropfiles::parse(&data).unwrap_err(), Error::Invalid("file list overlaps header"));
+
+    let mut data = wide_list(&["C:\\file.txt"]);
+    data[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(dropfiles::parse(&data).unwrap_err(), Error::Truncated);
+
+    //Missing list terminator
+    let data = wide_list(&["C:\\file.txt"]);
+    assert_eq!(dropfiles::parse(&data[..data.len() - 2]).unwrap_err(), Error::Truncated);
+    //Missing name terminator
+    assert_eq!(dropfiles::parse(&data[..data.len() - 4]).unwrap_err(), Error::Truncated);
+    //Odd byte cannot terminate wide name
+    assert_eq!(dropfiles::parse(&data[..data.len() - 1]).unwrap_err(), Error::Truncated);
+
+    //No list at all
+    let data = header(HEADER_SIZE as u32, 0, 0, false, true);
+    assert_eq!(dropfiles::parse(&data).unwrap_err(), Error::Truncated);
+
+    let mut data = header(HEADER_SIZE as u32, 0, 0, false, false);
+    data.extend_from_slice(b"C:\\file.txt\0");
+    assert_eq!(dropfiles::parse(&data).unwrap_err(), Error::Truncated);
+}
@@ -0,0 +1,178 @@
+use clipboard_win::codec::{Error, Point, Rect};
+use clipboard_win::codec::emf::{self, EMR_HEADER, EMR_EOF, EMR_GDICOMMENT};
+
+fn push_u32(out: &mut Vec<u8>, values: &[u32]) {
+    for value in values {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn record(kind: u32, data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, &[kind, 8 + data.len() as u32]);
+    out.extend_from_slice(data);
+    out
+}
+
+fn plus_comment(records: &[(u16, u16, &[u8])]) -> Vec<u8> {
+    let mut comment = b"EMF+".to_vec();
+    for (kind, flags, data) in records {
+        let padding = (4 - data.len() % 4) % 4;
+        comment.extend_from_slice(&kind.to_le_bytes());
+        comment.extend_from_slice(&flags.to_le_bytes());
+        push_u32(&mut comment, &[(12 + data.len() + padding) as u32, data.len() as u32]);
+        comment.extend_from_slice(data);
+        comment.resize(comment.len() + padding, 0);
+    }
+
+    let mut data = Vec::new();
+    push_u32(&mut data, &[comment.len() as u32]);
+    data.extend_from_slice(&comment);
+    record(EMR_GDICOMMENT, &data)
+}
+
+//Writes metafile with header of 108 bytes, followed by description, `records` and EMR_EOF.
+fn metafile(description: &str, records: &[Vec<u8>]) -> Vec<u8> {
+    let mut description: Vec<u8> = description.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+    let description_len = description.len() / 2;
+    //Length is even, so padding is either zero or two bytes
+    description.resize(description.len() + description.len() % 4, 0);
+
+    let mut header = Vec::new();
+    //Bounds and frame
+    push_u32(&mut header, &[0, 0, 99, 49, 0, 0, 2646, 1322]);
+    push_u32(&mut header, &[emf::ENHMETA_SIGNATURE, 0x10000, 0, 0]);
+    header.extend_from_slice(&3u16.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    let description_offset = if description_len > 0 { 108 } else { 0 };
+    push_u32(&mut header, &[description_len as u32, description_offset, 0]);
+    //Device in pixels and millimeters, pixel format, OpenGL and device in micrometers
+    push_u32(&mut header, &[1920, 1080, 508, 286, 0, 0, 0, 508000, 285750]);
+    header.extend_from_slice(&description);
+
+    let mut out = record(EMR_HEADER, &header);
+    for record in records {
+        out.extend_from_slice(record);
+    }
+    let mut eof = Vec::new();
+    push_u32(&mut eof, &[0, 16, 20]);
+    out.extend_from_slice(&record(EMR_EOF, &eof));
+
+    let size = out.len() as u32;
+    out[48..52].copy_from_slice(&size.to_le_bytes());
+    let count = records.len() as u32 + 2;
+    out[52..56].copy_from_slice(&count.to_le_bytes());
+    out
+}
+
+#[test]
+fn should_parse_header() {
+    let data = metafile("App\0Chart\0\0", &[record(27, &[0; 8])]);
+
+    let parsed = emf::parse(&data).expect("Parse");
+    let header = parsed.header;
+    assert_eq!(header.bounds, Rect { left: 0, top: 0, right: 99, bottom: 49 });
+    assert_eq!(header.frame, Rect { left: 0, top: 0, right: 2646, bottom: 1322 });
+    assert_eq!(header.version, 0x10000);
+    assert_eq!(header.size as usize, data.len());
+    assert_eq!(header.records, 3);
+    assert_eq!(header.handles, 3);
+    assert_eq!(header.palette_entries, 0);
+    assert_eq!(header.device, Point { x: 1920, y: 1080 });
+    assert_eq!(header.millimeters, Point { x: 508, y: 286 });
+    assert_eq!(header.micrometers, Some(Point { x: 508000, y: 285750 }));
+
+    let mut description = String::new();
+    parsed.description().push_lossy(&mut description);
+    assert_eq!(description, "App\0Chart\0\0");
+
+    let kinds: Vec<u32> = parsed.records().map(|record| record.kind).collect();
+    assert_eq!(kinds, [EMR_HEADER, 27, EMR_EOF]);
+    assert_eq!(parsed.records().nth(1).expect("Record").data, &[0; 8]);
+    assert!(!parsed.has_plus_records());
+    assert_eq!(parsed.plus_records().count(), 0);
+}
+
+#[test]
+fn should_ignore_trailing_bytes() {
+    let mut data = metafile("", &[]);
+    let size = data.len();
+    data.extend_from_slice(&[0xFF; 13]);
+
+    let parsed = emf::parse(&data).expect("Parse");
+    assert_eq!(parsed.as_bytes().len(), size);
+    assert!(parsed.description().is_empty());
+    assert_eq!(parsed.records().count(), 2);
+}
+
+#[test]
+fn should_parse_plus_records() {
+    let data = metafile("", &[
+        plus_comment(&[(0x4001, 1, &[1, 0, 0xDB, 0xC3]), (0x4004, 0, &[])]),
+        record(EMR_GDICOMMENT, &[4, 0, 0, 0, b'T', b'e', b's', b't']),
+        plus_comment(&[(0x4002, 0, &[7, 8, 9])]),
+    ]);
+
+    let parsed = emf::parse(&data).expect("Parse");
+    assert!(parsed.has_plus_records());
+
+    let records: Vec<_> = parsed.plus_records().map(|record| (record.kind, record.flags, record.data)).collect();
+    assert_eq!(records, [
+        (0x4001, 1, &[1, 0, 0xDB, 0xC3][..]),
+        (0x4004, 0, &[][..]),
+        (0x4002, 0, &[7, 8, 9][..]),
+    ]);
+}
+
+#[test]
+fn should_reject_invalid() {
+    let data = metafile("", &[]);
+
+    assert_eq!(emf::parse(&data[..80]).unwrap_err(), Error::Truncated);
+    assert_eq!(emf::parse(&data[..data.len() - 1]).unwrap_err(), Error::Truncated);
+
+    let mut invalid = data.clone();
+    invalid[0] = 2;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("first record is not EMR_HEADER"));
+
+    let mut invalid = data.clone();
+    invalid[40] = 0;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("EMF signature"));
+
+    let mut invalid = data.clone();
+    invalid[52] = 5;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("record count"));
+
+    let mut invalid = data.clone();
+    invalid[4] = 107;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("record size"));
+
+    let mut invalid = data.clone();
+    invalid[64] = 200;
+    invalid[60] = 1;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("EMF description"));
+
+    //Record after end of file
+    let mut invalid = data.clone();
+    invalid.extend_from_slice(&record(27, &[]));
+    let size = invalid.len() as u32;
+    invalid[48..52].copy_from_slice(&size.to_le_bytes());
+    invalid[52] = 3;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("record after EMR_EOF"));
+
+    //Missing end of file
+    let mut invalid = data[..data.len() - 20].to_vec();
+    let size = invalid.len() as u32;
+    invalid[48..52].copy_from_slice(&size.to_le_bytes());
+    invalid[52] = 1;
+    assert_eq!(emf::parse(&invalid).unwrap_err(), Error::Invalid("missing EMR_EOF"));
+
+    //EMF+ record larger than comment
+    let mut comment = plus_comment(&[(0x4001, 0, &[1, 2, 3, 4])]);
+    comment[20] = 20;
+    assert_eq!(emf::parse(&metafile("", &[comment])).unwrap_err(), Error::Truncated);
+
+    let mut comment = plus_comment(&[(0x4001, 0, &[1, 2, 3, 4])]);
+    comment[24] = 5;
+    assert_eq!(emf::parse(&metafile("", &[comment])).unwrap_err(), Error::Invalid("EMF+ record size"));
+}
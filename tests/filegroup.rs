@@ -0,0 +1,119 @@
+use clipboard_win::codec::{Error, Name, Point};
+use clipboard_win::codec::filegroup::{self, FileInfo, FD_FILESIZE, FD_WRITESTIME, FD_ATTRIBUTES, FD_UNICODE, FD_PROGRESSUI, ANSI_DESCRIPTOR_SIZE, WIDE_DESCRIPTOR_SIZE, MAX_PATH};
+
+fn name_to_string(name: Name) -> String {
+    match name {
+        Name::Wide(name) => {
+            let mut out = String::new();
+            name.push_lossy(&mut out);
+            out
+        },
+        Name::Ansi(name) => String::from_utf8(name.to_vec()).unwrap(),
+    }
+}
+
+#[test]
+fn should_write_and_parse_wide() {
+    let report = FileInfo::default().with_file_size(0x1_0000_0002).with_write_time(0x01D7_0000_1234_5678).with_attributes(0x20);
+    let photo = FileInfo {
+        flags: FD_PROGRESSUI,
+        size: Point { x: 32, y: 32 },
+        ..FileInfo::default()
+    };
+
+    let mut out = vec![0xAA];
+    let written = filegroup::write_wide(&mut out, vec![(&report, "report.pdf"), (&photo, "photos\\фото.jpg")]).expect("Write descriptors");
+    assert_eq!(written, 4 + 2 * WIDE_DESCRIPTOR_SIZE);
+    assert_eq!(out.len(), written + 1);
+
+    let group = filegroup::parse(&out[1..], true).expect("Parse descriptors");
+    assert!(group.wide);
+    assert_eq!(group.len(), 2);
+    assert_eq!(group.iter().len(), 2);
+
+    let first = group.get(0).expect("Get first");
+    assert!(first.info.has(FD_FILESIZE | FD_WRITESTIME | FD_ATTRIBUTES | FD_UNICODE));
+    assert_eq!(first.info.file_size, 0x1_0000_0002);
+    assert_eq!(first.info.write_time, 0x01D7_0000_1234_5678);
+    assert_eq!(first.info.attributes, 0x20);
+    assert_eq!(name_to_string(first.name), "report.pdf");
+
+    let second = group.get(1).expect("Get second");
+    assert!(!second.info.has(FD_FILESIZE));
+    assert_eq!(second.info.flags, FD_PROGRESSUI | FD_UNICODE);
+    assert_eq!(second.info.size, Point { x: 32, y: 32 });
+    assert_eq!(name_to_string(second.name), "photos\\фото.jpg");
+
+    assert!(group.get(2).is_none());
+    let names = group.iter().map(|descriptor| name_to_string(descriptor.name)).collect::<Vec<_>>();
+    assert_eq!(names, ["report.pdf", "photos\\фото.jpg"]);
+}
+
+#[test]
+fn should_parse_ansi() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&FD_FILESIZE.to_le_bytes());
+    data.resize(4 + 64, 0);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&5u32.to_le_bytes());
+    data.extend_from_slice(b"mail.eml\0");
+    data.resize(4 + ANSI_DESCRIPTOR_SIZE, 0);
+
+    let group = filegroup::parse(&data, false).expect("Parse descriptors");
+    assert!(!group.wide);
+    assert_eq!(group.len(), 1);
+
+    let descriptor = group.get(0).expect("Get descriptor");
+    assert_eq!(descriptor.info.file_size, 5);
+    assert_eq!(descriptor.name, Name::Ansi(b"mail.eml"));
+
+    //Same data is too short for wide descriptor
+    assert_eq!(filegroup::parse(&data, true).unwrap_err(), Error::Truncated);
+}
+
+#[test]
+fn should_parse_empty() {
+    let mut out = Vec::new();
+    assert_eq!(filegroup::write_wide(&mut out, Vec::new()), Ok(4));
+
+    let group = filegroup::parse(&out, true).expect("Parse descriptors");
+    assert!(group.is_empty());
+    assert!(group.iter().next().is_none());
+}
+
+#[test]
+fn should_reject_malformed() {
+    assert_eq!(filegroup::parse(&[1, 0], true).unwrap_err(), Error::Truncated);
+    assert_eq!(filegroup::parse(&u32::MAX.to_le_bytes(), true).unwrap_err(), Error::Truncated);
+
+    let info = FileInfo::default();
+    let mut out = Vec::new();
+    filegroup::write_wide(&mut out, vec![(&info, "file.txt")]).expect("Write descriptors");
+    assert_eq!(filegroup::parse(&out[..out.len() - 1], true).unwrap_err(), Error::Truncated);
+
+    //Name occupies whole buffer
+    let name_start = 4 + 72;
+    for byte in out[name_start..].iter_mut() {
+        *byte = b'a';
+    }
+    assert_eq!(filegroup::parse(&out, true).unwrap_err(), Error::Invalid("file name is not terminated"));
+}
+
+#[test]
+fn should_reject_invalid_names() {
+    let info = FileInfo::default();
+    let mut out = vec![1, 2, 3];
+
+    let long_name = "a".repeat(MAX_PATH);
+    assert_eq!(filegroup::write_wide(&mut out, vec![(&info, "ok"), (&info, long_name.as_str())]).unwrap_err(), Error::Invalid("file name is too long"));
+    assert_eq!(out, [1, 2, 3]);
+
+    assert_eq!(filegroup::write_wide(&mut out, vec![(&info, "a\0b")]).unwrap_err(), Error::Invalid("file name contains null character"));
+    assert_eq!(out, [1, 2, 3]);
+
+    let max_name = "a".repeat(MAX_PATH - 1);
+    filegroup::write_wide(&mut out, vec![(&info, max_name.as_str())]).expect("Write descriptors");
+    let group = filegroup::parse(&out[3..], true).expect("Parse descriptors");
+    assert_eq!(name_to_string(group.get(0).unwrap().name), max_name);
+}
@@ -1,3 +1,5 @@
+#![cfg(windows)]
+
 use clipboard_win::raw::{register_format, format_name, format_name_big};
 
 #[test]
@@ -0,0 +1,89 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::gif::{self, Gif};
+
+fn header(version: &[u8; 6], width: u16, height: u16, flags: u8) -> Vec<u8> {
+    let mut data = version.to_vec();
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&[flags, 0, 0]);
+    if flags & 0x80 != 0 {
+        data.extend_from_slice(&vec![0; 3 << ((flags & 7) + 1)]);
+    }
+    data
+}
+
+fn image(width: u16, height: u16, code_size: u8) -> Vec<u8> {
+    let mut data = vec![gif::IMAGE, 0, 0, 0, 0];
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.push(0);
+    data.extend_from_slice(&[code_size, 2, 0x4C, 0x01, 0]);
+    data
+}
+
+//Graphic control extension, delaying frame of animation.
+fn graphic_control() -> Vec<u8> {
+    vec![gif::EXTENSION, 0xF9, 4, 0, 10, 0, 0, 0]
+}
+
+fn stream(header: Vec<u8>, blocks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = header;
+    for block in blocks {
+        data.extend_from_slice(block);
+    }
+    data.push(gif::TRAILER);
+    data
+}
+
+#[test]
+fn should_parse_single_image() {
+    let data = stream(header(b"GIF87a", 2, 1, 0x80), &[image(2, 1, 2)]);
+
+    let gif = gif::parse(&data).expect("Parse");
+    assert_eq!(gif, Gif {
+        width: 2,
+        height: 1,
+        frames: 1,
+        size: data.len(),
+    });
+    assert!(!gif.is_animated());
+}
+
+#[test]
+fn should_parse_animation() {
+    let mut looping = vec![gif::EXTENSION, 0xFF, 11];
+    looping.extend_from_slice(b"NETSCAPE2.0");
+    looping.extend_from_slice(&[3, 1, 0, 0, 0]);
+
+    let data = stream(header(b"GIF89a", 4, 4, 0x81), &[looping, graphic_control(), image(4, 4, 2), graphic_control(), image(2, 2, 8)]);
+
+    let gif = gif::parse(&data).expect("Parse");
+    assert_eq!(gif.frames, 2);
+    assert!(gif.is_animated());
+    assert_eq!(gif.size, data.len());
+}
+
+#[test]
+fn should_exclude_trailing_data() {
+    let mut data = stream(header(b"GIF89a", 1, 1, 0), &[image(1, 1, 2)]);
+    let size = data.len();
+    data.extend_from_slice(b"trailing");
+
+    assert_eq!(gif::parse(&data).expect("Parse").size, size);
+}
+
+#[test]
+fn should_reject_invalid_stream() {
+    assert_eq!(gif::parse(b"GIF90a"), Err(Error::Invalid("GIF signature")));
+    assert_eq!(gif::parse(b"GIF89a"), Err(Error::Truncated));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 0, 1, 0), &[])), Err(Error::Invalid("GIF dimensions")));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 1, 1, 0), &[])), Err(Error::Invalid("GIF without image")));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 1, 1, 0), &[graphic_control()])), Err(Error::Invalid("GIF without image")));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 1, 1, 0), &[image(0, 1, 2)])), Err(Error::Invalid("GIF image dimensions")));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 1, 1, 0), &[image(1, 1, 9)])), Err(Error::Invalid("GIF code size")));
+    assert_eq!(gif::parse(&stream(header(b"GIF89a", 1, 1, 0), &[vec![0]])), Err(Error::Invalid("GIF block")));
+
+    let mut data = stream(header(b"GIF89a", 1, 1, 0), &[image(1, 1, 2)]);
+    data.pop();
+    assert_eq!(gif::parse(&data), Err(Error::Truncated));
+}
@@ -0,0 +1,44 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::grid::{date_serial, Grid, Value};
+use clipboard_win::codec::{dif, sylk};
+
+#[test]
+fn should_convert_date_to_serial() {
+    assert_eq!(date_serial("1899-12-30"), Some(0.0));
+    assert_eq!(date_serial("1900-03-01T00:00:00.000"), Some(61.0));
+    assert_eq!(date_serial("2021-03-04T12:00"), Some(44259.5));
+    assert_eq!(date_serial("2000-02-29T06:00:00"), Some(36585.25));
+    assert_eq!(date_serial("1899-12-31T00:00:01.5"), Some(1.0 + 1.5 / 86400.0));
+
+    assert_eq!(date_serial("2021-02-29"), None);
+    assert_eq!(date_serial("2021-13-01"), None);
+    assert_eq!(date_serial("2021-1-01"), None);
+    assert_eq!(date_serial("2021-01-01T24:00"), None);
+    assert_eq!(date_serial("2021-01-01T00:00:00."), None);
+    assert_eq!(date_serial("today"), None);
+}
+
+#[test]
+fn should_write_date_as_serial() {
+    let mut grid = Grid::new();
+    grid.set(0, 0, Value::DateTime("2021-03-04T12:00:00.000".to_owned()));
+    let mut out = String::new();
+    sylk::write(&mut out, &grid).expect("Write sylk");
+    assert!(out.contains("C;Y1;X1;K44259.5\r\n"));
+
+    out.clear();
+    dif::write(&mut out, &[[Value::DateTime("2021-03-04".to_owned())]]).expect("Write dif");
+    assert!(out.contains("0,44259\r\nV\r\n"));
+
+    assert_eq!(dif::write(&mut out, &[[Value::DateTime("invalid".to_owned())]]), Err(Error::Invalid("date time")));
+}
+
+#[test]
+fn should_track_grid_bounds() {
+    let mut grid = Grid::new();
+    assert_eq!((grid.rows(), grid.columns()), (0, 0));
+    grid.set(2, 0, Value::Number(1.0));
+    grid.set(0, 5, Value::Empty);
+    assert_eq!((grid.rows(), grid.columns()), (3, 6));
+    assert_eq!(grid.iter().map(|(position, _)| *position).collect::<Vec<_>>(), [(0, 5), (2, 0)]);
+}
@@ -0,0 +1,205 @@
+use clipboard_win::codec::{Error, Name};
+use clipboard_win::codec::idlist::{self, IdList, Item, CLSID_MY_COMPUTER, CLSID_CONTROL_PANEL};
+
+fn item_id(data: &[u8]) -> Vec<u8> {
+    let mut out = ((data.len() + 2) as u16).to_le_bytes().to_vec();
+    out.extend_from_slice(data);
+    out
+}
+
+fn root(clsid: &[u8; 16]) -> Vec<u8> {
+    let mut data = vec![0x1F, 0x50];
+    data.extend_from_slice(clsid);
+    item_id(&data)
+}
+
+fn drive(path: &str) -> Vec<u8> {
+    let mut data = vec![0x2F];
+    data.extend_from_slice(path.as_bytes());
+    data.resize(23, 0);
+    item_id(&data)
+}
+
+fn file(short_name: &str, long_name: &str, version: u16) -> Vec<u8> {
+    let mut data = vec![0x32, 0];
+    data.extend_from_slice(&1234u32.to_le_bytes());
+    data.extend_from_slice(&0x5021u16.to_le_bytes());
+    data.extend_from_slice(&0x6000u16.to_le_bytes());
+    data.extend_from_slice(&0x20u16.to_le_bytes());
+    data.extend_from_slice(short_name.as_bytes());
+    data.push(0);
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+
+    let name_offset = match version {
+        3 => 20,
+        7 => 38,
+        8 => 42,
+        _ => 46,
+    };
+    let mut extension = vec![0; name_offset];
+    extension[2..4].copy_from_slice(&version.to_le_bytes());
+    extension[4..8].copy_from_slice(&0xBEEF0004u32.to_le_bytes());
+    for unit in long_name.encode_utf16().chain(Some(0)) {
+        extension.extend_from_slice(&unit.to_le_bytes());
+    }
+    extension.extend_from_slice(&14u16.to_le_bytes());
+    let size = extension.len() as u16;
+    extension[..2].copy_from_slice(&size.to_le_bytes());
+
+    data.extend_from_slice(&extension);
+    item_id(&data)
+}
+
+fn id_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = items.concat();
+    out.extend_from_slice(&[0, 0]);
+    out
+}
+
+fn cida(parent: &[u8], children: &[Vec<u8>]) -> Vec<u8> {
+    let mut offsets = Vec::new();
+    let mut lists = Vec::new();
+    let header_size = 4 + 4 * (children.len() + 1);
+    for list in Some(parent).into_iter().chain(children.iter().map(|child| child.as_slice())) {
+        offsets.push((header_size + lists.len()) as u32);
+        lists.extend_from_slice(list);
+    }
+
+    let mut out = (children.len() as u32).to_le_bytes().to_vec();
+    for offset in offsets {
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    out.extend_from_slice(&lists);
+    out
+}
+
+fn wide_to_string(name: Name) -> String {
+    match name {
+        Name::Wide(name) => {
+            let mut out = String::new();
+            name.push_lossy(&mut out);
+            out
+        },
+        Name::Ansi(name) => String::from_utf8(name.to_vec()).unwrap(),
+    }
+}
+
+#[test]
+fn should_parse_cida() {
+    let parent = id_list(&[root(&CLSID_MY_COMPUTER), drive("C:\\")]);
+    let children = [
+        id_list(&[file("REPORT~1.PDF", "Quarterly report.pdf", 9)]),
+        id_list(&[file("DATA.CSV", "data.csv", 3), file("A.TXT", "a.txt", 8)]),
+        id_list(&[]),
+    ];
+    let data = cida(&parent, &children);
+
+    let cida = idlist::parse(&data).expect("Parse CIDA");
+    assert_eq!(cida.len(), 3);
+    assert_eq!(cida.parent().as_bytes(), &parent[..]);
+
+    let parent = cida.parent().items().map(|item| item.decode()).collect::<Vec<_>>();
+    assert_eq!(parent[0], Item::Root(CLSID_MY_COMPUTER));
+    assert_eq!(idlist::known_folder(&CLSID_MY_COMPUTER), Some("This PC"));
+    assert_eq!(parent[1], Item::Drive(b"C:\\"));
+
+    let first = cida.child(0).expect("Get child").expect("Parse child");
+    assert_eq!(first.as_bytes(), &children[0][..]);
+    let items = first.items().collect::<Vec<_>>();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].as_bytes(), &children[0][..children[0].len() - 2]);
+    match items[0].decode() {
+        Item::FileSystem(item) => {
+            assert!(!item.is_directory);
+            assert_eq!(item.file_size, 1234);
+            assert_eq!(item.modified, 0x5021_6000);
+            assert_eq!(item.attributes, 0x20);
+            assert_eq!(item.short_name, Name::Ansi(b"REPORT~1.PDF"));
+            assert_eq!(wide_to_string(Name::Wide(item.long_name.expect("Long name"))), "Quarterly report.pdf");
+        },
+        item => panic!("Unexpected item: {:?}", item),
+    }
+
+    let second = cida.child(1).expect("Get child").expect("Parse child");
+    let names = second.items().map(|item| match item.decode() {
+        Item::FileSystem(item) => wide_to_string(Name::Wide(item.long_name.expect("Long name"))),
+        item => panic!("Unexpected item: {:?}", item),
+    }).collect::<Vec<_>>();
+    assert_eq!(names, ["data.csv", "a.txt"]);
+
+    let desktop = cida.child(2).expect("Get child").expect("Parse child");
+    assert!(desktop.is_empty());
+    assert!(cida.child(3).is_none());
+    assert_eq!(cida.children().len(), 3);
+    assert!(cida.children().all(|child| child.is_ok()));
+}
+
+#[test]
+fn should_decode_unknown_items() {
+    let control_panel = id_list(&[root(&CLSID_CONTROL_PANEL), item_id(&[0x71, 1, 2, 3])]);
+    let list = IdList::parse(&control_panel).expect("Parse list");
+    let items = list.items().map(|item| item.decode()).collect::<Vec<_>>();
+    assert_eq!(items, [Item::Root(CLSID_CONTROL_PANEL), Item::Unknown(0x71)]);
+    assert_eq!(idlist::known_folder(&CLSID_CONTROL_PANEL), Some("Control Panel"));
+    assert_eq!(idlist::known_folder(&[0; 16]), None);
+
+    //Truncated items cannot be decoded
+    let truncated = id_list(&[item_id(&[0x1F, 0x50, 1, 2]), item_id(&[0x32, 0, 1]), item_id(&[0x2F, b'C'])]);
+    let list = IdList::parse(&truncated).expect("Parse list");
+    let items = list.items().map(|item| item.decode()).collect::<Vec<_>>();
+    assert_eq!(items, [Item::Unknown(0x1F), Item::Unknown(0x32), Item::Unknown(0x2F)]);
+}
+
+#[test]
+fn should_decode_file_without_extension() {
+    let mut data = file("DIR", "", 9);
+    data.truncate(2 + 12 + 4);
+    let size = data.len() as u16;
+    data[..2].copy_from_slice(&size.to_le_bytes());
+    data[2] = 0x31;
+    let list = id_list(&[data]);
+
+    let list = IdList::parse(&list).expect("Parse list");
+    match list.items().next().expect("Get item").decode() {
+        Item::FileSystem(item) => {
+            assert!(item.is_directory);
+            assert_eq!(item.short_name, Name::Ansi(b"DIR"));
+            assert_eq!(item.long_name, None);
+        },
+        item => panic!("Unexpected item: {:?}", item),
+    }
+}
+
+#[test]
+fn should_reject_malformed() {
+    assert_eq!(idlist::parse(&[]).unwrap_err(), Error::Truncated);
+    assert_eq!(idlist::parse(&u32::MAX.to_le_bytes()).unwrap_err(), Error::Truncated);
+
+    let parent = id_list(&[drive("C:\\")]);
+    let data = cida(&parent, &[id_list(&[file("A.TXT", "a.txt", 9)])]);
+
+    //Offset outside of data
+    let mut invalid = data.clone();
+    invalid[8..12].copy_from_slice(&(data.len() as u32).to_le_bytes());
+    assert_eq!(idlist::parse(&invalid).unwrap_err(), Error::Truncated);
+
+    //Missing terminator of parent
+    let mut invalid = data.clone();
+    invalid[4..8].copy_from_slice(&((data.len() - 4) as u32).to_le_bytes());
+    assert_eq!(idlist::parse(&invalid).unwrap_err(), Error::Truncated);
+
+    //Child with invalid size is reported on access
+    let mut invalid = data.clone();
+    let child_offset = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    invalid[child_offset..child_offset + 2].copy_from_slice(&1u16.to_le_bytes());
+    let cida = idlist::parse(&invalid).expect("Parse CIDA");
+    assert_eq!(cida.child(0).expect("Get child").unwrap_err(), Error::Invalid("item id size"));
+
+    //Child size beyond data
+    let mut invalid = data;
+    invalid[child_offset..child_offset + 2].copy_from_slice(&u16::MAX.to_le_bytes());
+    let cida = idlist::parse(&invalid).expect("Parse CIDA");
+    assert_eq!(cida.child(0).expect("Get child").unwrap_err(), Error::Truncated);
+}
@@ -0,0 +1,174 @@
+use clipboard_win::codec::{Error, Resolution};
+use clipboard_win::codec::bmp::{self, Profile, BI_RGB, BI_BITFIELDS, V5_HEADER_SIZE};
+use clipboard_win::codec::image::{self, Image, PixelFormat};
+
+//2x2 image with distinct pixels and padded rows.
+fn test_image() -> Image {
+    Image {
+        width: 2,
+        height: 2,
+        format: PixelFormat::Rgba8,
+        stride: 12,
+        pixels: vec![
+            1, 2, 3, 255, 4, 5, 6, 128, 0xEE, 0xEE, 0xEE, 0xEE,
+            7, 8, 9, 0, 10, 11, 12, 255, 0xEE, 0xEE, 0xEE, 0xEE,
+        ],
+        resolution: Some(Resolution::from_dpi(96, 96)),
+        icc_profile: None,
+    }
+}
+
+#[test]
+fn should_swap_red_blue() {
+    let mut pixels = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    image::swap_red_blue(&mut pixels);
+    assert_eq!(pixels, [3, 2, 1, 4, 7, 6, 5, 8, 9]);
+    image::swap_red_blue(&mut pixels);
+    assert_eq!(pixels, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn should_premultiply() {
+    let mut pixels = [200, 100, 50, 255, 200, 100, 50, 128, 200, 100, 50, 0];
+    image::premultiply(&mut pixels);
+    assert_eq!(pixels, [200, 100, 50, 255, 100, 50, 25, 128, 0, 0, 0, 0]);
+
+    image::unpremultiply(&mut pixels);
+    assert_eq!(pixels, [200, 100, 50, 255, 199, 100, 50, 128, 0, 0, 0, 0]);
+
+    //Channels exceeding alpha are saturated
+    let mut pixels = [255, 20, 10, 10];
+    image::unpremultiply(&mut pixels);
+    assert_eq!(pixels, [255, 255, 255, 10]);
+}
+
+#[test]
+fn should_flip_rows() {
+    let mut pixels = [1, 1, 2, 2, 3, 3, 4];
+    image::flip_rows(&mut pixels, 2);
+    assert_eq!(pixels, [3, 3, 2, 2, 1, 1, 4]);
+
+    let mut pixels = [1, 1, 2, 2];
+    image::flip_rows(&mut pixels, 2);
+    assert_eq!(pixels, [2, 2, 1, 1]);
+    image::flip_rows(&mut pixels, 0);
+    assert_eq!(pixels, [2, 2, 1, 1]);
+}
+
+#[test]
+fn should_validate() {
+    let image = test_image();
+    assert_eq!(image.validate(), Ok(()));
+    assert_eq!(image.row(1), &[7, 8, 9, 0, 10, 11, 12, 255]);
+    assert!(!image.is_opaque());
+
+    //Padding of last row is optional
+    let mut truncated = image.clone();
+    truncated.pixels.truncate(20);
+    assert_eq!(truncated.validate(), Ok(()));
+    truncated.pixels.pop();
+    assert_eq!(truncated.validate(), Err(Error::Truncated));
+
+    let mut invalid = image.clone();
+    invalid.stride = 7;
+    assert_eq!(invalid.validate(), Err(Error::Invalid("image stride")));
+    assert_eq!(Image::default().validate(), Err(Error::Invalid("image dimensions")));
+
+    let new = Image::new(3, 2, PixelFormat::Bgra8).expect("Create image");
+    assert_eq!(new.stride, 12);
+    assert_eq!(new.pixels, [0; 24]);
+    assert_eq!(new.validate(), Ok(()));
+}
+
+#[test]
+fn should_convert() {
+    let mut image = test_image();
+    image.convert(PixelFormat::Bgra8);
+    assert_eq!(image.format, PixelFormat::Bgra8);
+    assert_eq!(image.row(0), &[3, 2, 1, 255, 6, 5, 4, 128]);
+    //Padding is untouched
+    assert_eq!(&image.pixels[8..12], &[0xEE; 4]);
+
+    image.convert(PixelFormat::Rgba8);
+    assert_eq!(image, test_image());
+}
+
+#[test]
+fn should_write_dib() {
+    let image = test_image();
+    let mut out = vec![1];
+    let size = image.write_dib(&mut out).expect("Write DIB");
+    assert_eq!(size, out.len() - 1);
+
+    let dib = bmp::parse_dib(&out[1..]).expect("Parse DIB");
+    assert_eq!(dib.header.compression, BI_RGB);
+    assert_eq!(dib.header.bit_count, 32);
+    assert!(!dib.header.is_top_down());
+    assert_eq!(dib.resolution(), image.resolution);
+    //Bottom-up BGRA
+    assert_eq!(dib.pixels, &[9, 8, 7, 0, 12, 11, 10, 255, 3, 2, 1, 255, 6, 5, 4, 128]);
+
+    let mut file = Vec::new();
+    image.write_bitmap(&mut file).expect("Write bitmap");
+    assert_eq!(bmp::parse_file(&file).expect("Parse file").pixels, dib.pixels);
+}
+
+#[test]
+fn should_write_dibv5() {
+    let mut image = test_image();
+    image.icc_profile = Some(b"ICC profile".to_vec());
+    image.convert(PixelFormat::Bgra8);
+
+    let mut out = Vec::new();
+    image.write_dibv5(&mut out).expect("Write DIBV5");
+    let dib = bmp::parse_dib(&out).expect("Parse DIBV5");
+    assert_eq!(dib.header.size as usize, V5_HEADER_SIZE);
+    assert_eq!(dib.header.compression, BI_BITFIELDS);
+    assert_eq!(dib.header.masks, [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000]);
+    assert_eq!(dib.profile, Some(Profile::Embedded(b"ICC profile")));
+
+    //Alpha is preserved, including fully transparent pixel
+    let decoded = Image::from_dib(&dib).expect("Decode DIBV5");
+    assert_eq!(decoded.format, PixelFormat::Rgba8);
+    assert_eq!(decoded.stride, 8);
+    assert_eq!(decoded.pixels, [1, 2, 3, 255, 4, 5, 6, 128, 7, 8, 9, 0, 10, 11, 12, 255]);
+    assert_eq!(decoded.resolution, image.resolution);
+    assert_eq!(decoded.icc_profile, image.icc_profile);
+}
+
+#[test]
+fn should_decode_bitmap() {
+    let data = std::fs::read("tests/test-image.bmp").expect("Read test image");
+    let dib = bmp::parse_file(&data).expect("Parse bitmap");
+    let image = Image::from_dib(&dib).expect("Decode bitmap");
+
+    assert_eq!((image.width, image.height), (750, 300));
+    assert_eq!(image.validate(), Ok(()));
+    assert!(image.is_opaque());
+    assert_eq!(image.icc_profile, None);
+
+    //Round trip through DIB preserves pixels
+    let mut out = Vec::new();
+    image.write_dib(&mut out).expect("Write DIB");
+    let written = Image::from_dib(&bmp::parse_dib(&out).expect("Parse DIB")).expect("Decode DIB");
+    assert_eq!(written.pixels, image.pixels);
+}
+
+#[test]
+fn should_reject_invalid_image() {
+    let mut out = Vec::new();
+    let mut image = test_image();
+    image.pixels.truncate(10);
+    assert_eq!(image.write_dib(&mut out), Err(Error::Truncated));
+    assert_eq!(image.write_dibv5(&mut out), Err(Error::Truncated));
+    assert_eq!(image.write_bitmap(&mut out), Err(Error::Truncated));
+    assert!(out.is_empty());
+
+    let image = Image {
+        width: 0x8000_0000,
+        height: 1,
+        stride: 0x2_0000_0000,
+        ..Image::default()
+    };
+    assert!(image.write_dib(&mut out).is_err());
+}
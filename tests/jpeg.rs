@@ -0,0 +1,98 @@
+use clipboard_win::codec::{Error, Resolution};
+use clipboard_win::codec::jpeg::{self, Jpeg};
+
+fn segment(marker: u8, content: &[u8]) -> Vec<u8> {
+    let mut data = vec![0xFF, marker];
+    data.extend_from_slice(&(content.len() as u16 + 2).to_be_bytes());
+    data.extend_from_slice(content);
+    data
+}
+
+fn jfif(unit: u8, x: u16, y: u16) -> Vec<u8> {
+    let mut content = b"JFIF\x00\x01\x02".to_vec();
+    content.push(unit);
+    content.extend_from_slice(&x.to_be_bytes());
+    content.extend_from_slice(&y.to_be_bytes());
+    content.extend_from_slice(&[0, 0]);
+    segment(jpeg::APP0, &content)
+}
+
+fn frame(marker: u8, width: u16, height: u16) -> Vec<u8> {
+    let mut content = vec![8];
+    content.extend_from_slice(&height.to_be_bytes());
+    content.extend_from_slice(&width.to_be_bytes());
+    content.extend_from_slice(&[3, 1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1]);
+    segment(marker, &content)
+}
+
+fn scan(entropy: &[u8]) -> Vec<u8> {
+    let mut data = segment(jpeg::SOS, &[1, 1, 0, 0, 63, 0]);
+    data.extend_from_slice(entropy);
+    data
+}
+
+fn stream(parts: &[&[u8]]) -> Vec<u8> {
+    let mut data = vec![0xFF, jpeg::SOI];
+    for part in parts {
+        data.extend_from_slice(part);
+    }
+    data.extend_from_slice(&[0xFF, jpeg::EOI]);
+    data
+}
+
+#[test]
+fn should_parse_baseline_with_jfif_density() {
+    let data = stream(&[&jfif(1, 72, 96), &segment(0xDB, &[0; 65]), &frame(0xC0, 640, 480), &scan(&[1, 0xFF, 0x00, 2, 0xFF, 0xD0, 3])]);
+
+    let jpeg = jpeg::parse(&data).expect("Parse");
+    assert_eq!(jpeg, Jpeg {
+        width: 640,
+        height: 480,
+        components: 3,
+        progressive: false,
+        resolution: Some(Resolution::from_dpi(72, 96)),
+        size: data.len(),
+    });
+}
+
+#[test]
+fn should_parse_progressive_with_multiple_scans() {
+    let data = stream(&[&jfif(2, 40, 40), &frame(0xC2, 16, 8), &scan(&[1, 2]), &segment(0xC4, &[0; 17]), &scan(&[3, 0xFF, 0xFF])]);
+
+    let jpeg = jpeg::parse(&data).expect("Parse");
+    assert!(jpeg.progressive);
+    assert_eq!((jpeg.width, jpeg.height), (16, 8));
+    assert_eq!(jpeg.resolution, Some(Resolution { x: 4000, y: 4000 }));
+}
+
+#[test]
+fn should_ignore_density_without_unit() {
+    let data = stream(&[&jfif(0, 1, 1), &frame(0xC0, 1, 1), &scan(&[0])]);
+    assert_eq!(jpeg::parse(&data).expect("Parse").resolution, None);
+}
+
+#[test]
+fn should_exclude_trailing_data() {
+    let mut data = stream(&[&frame(0xC1, 2, 2), &scan(&[0])]);
+    let size = data.len();
+    data.extend_from_slice(&[0, 0, 0xFF, 0xD9]);
+
+    assert_eq!(jpeg::parse(&data).expect("Parse").size, size);
+}
+
+#[test]
+fn should_reject_invalid_stream() {
+    assert_eq!(jpeg::parse(b"\x89PNG"), Err(Error::Invalid("JPEG signature")));
+    assert_eq!(jpeg::parse(&[0xFF, jpeg::SOI]), Err(Error::Truncated));
+    assert_eq!(jpeg::parse(&stream(&[])), Err(Error::Invalid("JPEG without frame")));
+    assert_eq!(jpeg::parse(&stream(&[&scan(&[0]), &frame(0xC0, 1, 1)])), Err(Error::Invalid("JPEG scan before frame")));
+    assert_eq!(jpeg::parse(&stream(&[&frame(0xC0, 0, 1)])), Err(Error::Invalid("JPEG dimensions")));
+    assert_eq!(jpeg::parse(&stream(&[&frame(0xC0, 1, 0)])), Err(Error::Unsupported("JPEG height defined by DNL")));
+    assert_eq!(jpeg::parse(&stream(&[&segment(0xC0, &[8, 0, 1, 0, 1, 2])])), Err(Error::Invalid("JPEG frame header")));
+    assert_eq!(jpeg::parse(&stream(&[&[0xFF, 0xE1, 0, 1]])), Err(Error::Invalid("JPEG segment length")));
+    assert_eq!(jpeg::parse(&stream(&[&[0, 0]])), Err(Error::Invalid("JPEG marker")));
+
+    let mut data = stream(&[&frame(0xC0, 1, 1), &scan(&[0])]);
+    data.truncate(data.len() - 2);
+    assert_eq!(jpeg::parse(&data), Err(Error::Truncated));
+}
@@ -0,0 +1,86 @@
+use clipboard_win::limits::{self, Limits, TooLarge, MAX_FORMAT_LIMITS};
+
+const TEXT: u32 = 13;
+const DIB: u32 = 8;
+
+#[test]
+fn should_be_unlimited_by_default() {
+    let limits = Limits::default();
+
+    assert_eq!(limits, Limits::UNLIMITED);
+    assert_eq!(limits.max_size(TEXT), usize::MAX);
+    assert_eq!(limits.max_pixels(), u64::MAX);
+    assert!(limits.check_size(TEXT, usize::MAX).is_ok());
+    assert!(limits.check_pixels(u32::MAX, u32::MAX).is_ok());
+}
+
+#[test]
+fn should_check_global_size() {
+    let limits = Limits::new(1024);
+
+    assert!(limits.check_size(TEXT, 0).is_ok());
+    assert!(limits.check_size(TEXT, 1024).is_ok());
+    assert_eq!(limits.check_size(TEXT, 1025), Err(TooLarge { size: 1025, limit: 1024 }));
+    assert_eq!(limits.check_size(DIB, 4096), Err(TooLarge { size: 4096, limit: 1024 }));
+}
+
+#[test]
+fn should_restrict_format_within_global() {
+    let limits = Limits::new(1024).with_format(TEXT, 16).with_format(DIB, 4096);
+
+    assert_eq!(limits.max_size(TEXT), 16);
+    //Format limit cannot exceed global one.
+    assert_eq!(limits.max_size(DIB), 1024);
+    assert_eq!(limits.max_size(0xC000), 1024);
+
+    assert!(limits.check_size(TEXT, 16).is_ok());
+    assert_eq!(limits.check_size(TEXT, 17), Err(TooLarge { size: 17, limit: 16 }));
+    assert_eq!(limits.check_size(DIB, 2048), Err(TooLarge { size: 2048, limit: 1024 }));
+
+    let limits = limits.with_format(TEXT, 32);
+    assert_eq!(limits.max_size(TEXT), 32);
+}
+
+#[test]
+#[should_panic]
+fn should_panic_on_too_many_formats() {
+    let mut limits = Limits::UNLIMITED;
+    for format in 0..=MAX_FORMAT_LIMITS as u32 {
+        limits = limits.with_format(format, 1);
+    }
+}
+
+#[test]
+fn should_check_pixels() {
+    let limits = Limits::UNLIMITED.with_max_pixels(1920 * 1080);
+
+    assert!(limits.check_pixels(1920, 1080).is_ok());
+    assert!(limits.check_pixels(0, u32::MAX).is_ok());
+    assert_eq!(limits.check_pixels(1921, 1080), Err(TooLarge { size: 1921 * 1080, limit: 1920 * 1080 }));
+    //No overflow on huge dimensions
+    assert_eq!(limits.check_pixels(u32::MAX, u32::MAX), Err(TooLarge { size: u32::MAX as u64 * u32::MAX as u64, limit: 1920 * 1080 }));
+}
+
+#[test]
+fn should_set_process_limits() {
+    const LIMITS: Limits = Limits::new(64).with_format(TEXT, 8).with_max_pixels(100);
+
+    assert_eq!(limits::get(), Limits::UNLIMITED);
+    limits::set(LIMITS);
+    assert_eq!(limits::get(), LIMITS);
+
+    assert!(limits::check_size(TEXT, 8).is_ok());
+    assert!(limits::check_size(TEXT, 9).is_err());
+    assert!(limits::check_size(DIB, 64).is_ok());
+    assert!(limits::check_pixels(10, 10).is_ok());
+    assert!(limits::check_pixels(10, 11).is_err());
+
+    limits::set(Limits::UNLIMITED);
+    assert!(limits::check_size(TEXT, 9).is_ok());
+}
+
+#[test]
+fn should_display_error() {
+    let error = TooLarge { size: 10, limit: 5 };
+    assert_eq!(error.to_string(), "Clipboard content is too large: 10 exceeds limit of 5");
+}
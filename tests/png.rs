@@ -0,0 +1,299 @@
+use clipboard_win::codec::{Error, Resolution};
+use clipboard_win::codec::image::{Image, PixelFormat};
+use clipboard_win::codec::png::{self, Header, IHDR, ICCP, SRGB, PHYS, IDAT, IEND, SIGNATURE, COLOR_RGBA, COLOR_RGB};
+
+//Writes PNG stream of 1x1 gray image, with `chunks` between header and data.
+fn png_file(chunks: &[([u8; 4], &[u8])]) -> Vec<u8> {
+    let mut out = SIGNATURE.to_vec();
+    png::write_chunk(&mut out, IHDR, &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]).expect("Write IHDR");
+    for (kind, data) in chunks {
+        png::write_chunk(&mut out, *kind, data).expect("Write chunk");
+    }
+    let mut data = Vec::new();
+    png::write_zlib_stored(&mut data, &[0, 0x80]);
+    png::write_chunk(&mut out, *b"IDAT", &data).expect("Write IDAT");
+    png::write_chunk(&mut out, IEND, &[]).expect("Write IEND");
+    out
+}
+
+#[test]
+fn should_write_chunk() {
+    let mut out = Vec::new();
+    png::write_chunk(&mut out, IEND, &[]).expect("Write");
+    assert_eq!(out, [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+
+    out.clear();
+    png::write_chunk(&mut out, SRGB, &[0]).expect("Write");
+    assert_eq!(out, [0, 0, 0, 1, b's', b'R', b'G', b'B', 0, 0xAE, 0xCE, 0x1C, 0xE9]);
+}
+
+#[test]
+fn should_write_zlib_stored() {
+    let mut out = Vec::new();
+    png::write_zlib_stored(&mut out, b"Wikipedia");
+    assert_eq!(&out[..7], &[0x78, 0x01, 1, 9, 0, 0xF6, 0xFF]);
+    assert_eq!(&out[7..16], b"Wikipedia");
+    assert_eq!(&out[16..], &[0x11, 0xE6, 0x03, 0x98]);
+
+    out.clear();
+    png::write_zlib_stored(&mut out, &[]);
+    assert_eq!(out, [0x78, 0x01, 1, 0, 0, 0xFF, 0xFF, 0, 0, 0, 1]);
+
+    //Multiple blocks, with last one marked as final
+    let data = vec![7u8; 0x10000];
+    out.clear();
+    png::write_zlib_stored(&mut out, &data);
+    assert_eq!(out.len(), 2 + 5 + 0xFFFF + 5 + 1 + 4);
+    assert_eq!(&out[2..7], &[0, 0xFF, 0xFF, 0, 0]);
+    assert_eq!(&out[7 + 0xFFFF..7 + 0xFFFF + 5], &[1, 1, 0, 0xFE, 0xFF]);
+}
+
+#[test]
+fn should_write_iccp() {
+    let profile = b"ICC profile data";
+    let mut out = Vec::new();
+    png::write_iccp(&mut out, "Display P3", profile).expect("Write");
+
+    let file = png_file(&[]);
+    let mut with_profile = Vec::new();
+    assert_eq!(png::insert_chunks(&file, &[ICCP], &out, &mut with_profile), Ok(file.len() + out.len()));
+
+    let chunks: Vec<_> = png::chunks(&with_profile).expect("Parse").collect();
+    let kinds: Vec<[u8; 4]> = chunks.iter().map(|chunk| chunk.kind).collect();
+    assert_eq!(kinds, [IHDR, ICCP, *b"IDAT", IEND]);
+    assert_eq!(chunks[1].bytes, &out[..]);
+
+    let data = chunks[1].data;
+    assert_eq!(&data[..12], b"Display P3\0\0");
+    assert_eq!(&data[12..14], &[0x78, 0x01]);
+    assert_eq!(&data[19..19 + profile.len()], profile);
+
+    assert_eq!(png::write_iccp(&mut out, "", profile).unwrap_err(), Error::Invalid("PNG profile name"));
+    assert_eq!(png::write_iccp(&mut out, "Profile\n", profile).unwrap_err(), Error::Invalid("PNG profile name"));
+}
+
+#[test]
+fn should_replace_chunks() {
+    let file = png_file(&[(SRGB, &[0]), (*b"tEXt", b"Key\0Value")]);
+    let mut iccp = Vec::new();
+    png::write_iccp(&mut iccp, "ICC profile", b"data").expect("Write");
+
+    let mut out = vec![1, 2, 3];
+    png::insert_chunks(&file, &[ICCP, SRGB], &iccp, &mut out).expect("Insert");
+    assert_eq!(&out[..3], &[1, 2, 3]);
+    let kinds: Vec<[u8; 4]> = png::chunks(&out[3..]).expect("Parse").map(|chunk| chunk.kind).collect();
+    assert_eq!(kinds, [IHDR, ICCP, *b"tEXt", *b"IDAT", IEND]);
+
+    //Content after IEND is dropped
+    let mut trailing = file.clone();
+    trailing.extend_from_slice(&[0; 5]);
+    let mut out = Vec::new();
+    png::insert_chunks(&trailing, &[], &[], &mut out).expect("Insert");
+    assert_eq!(out, file);
+}
+
+#[test]
+fn should_reject_invalid() {
+    let file = png_file(&[]);
+
+    let mut invalid = file.clone();
+    invalid[1] = b'p';
+    assert_eq!(png::chunks(&invalid).unwrap_err(), Error::Invalid("PNG signature"));
+
+    let mut invalid = file.clone();
+    invalid[12] = b'i';
+    assert_eq!(png::chunks(&invalid).unwrap_err(), Error::Invalid("PNG header"));
+
+    assert_eq!(png::chunks(&file[..20]).unwrap_err(), Error::Truncated);
+
+    let mut out = vec![1];
+    assert_eq!(png::insert_chunks(&file[..file.len() - 1], &[], &[], &mut out).unwrap_err(), Error::Truncated);
+    assert_eq!(out, [1]);
+}
+
+#[test]
+fn should_map_phys() {
+    let mut chunk = Vec::new();
+    png::write_phys(&mut chunk, Resolution::from_dpi(96, 72)).expect("Write");
+    assert_eq!(&chunk[4..8], &PHYS);
+    assert_eq!(&chunk[8..17], &[0, 0, 0x0E, 0xC4, 0, 0, 0x0B, 0x13, 1]);
+
+    let file = png_file(&[]);
+    assert_eq!(png::resolution(&file), Ok(None));
+    let mut out = Vec::new();
+    png::insert_chunks(&file, &[PHYS], &chunk, &mut out).expect("Insert");
+    assert_eq!(png::resolution(&out), Ok(Some(Resolution { x: 3780, y: 2835 })));
+
+    assert_eq!(png::parse_phys(&[0, 0, 0x0B, 0x13, 0, 0, 0x0B, 0x13, 1]).expect("Resolution").dpi(), (72, 72));
+    //Aspect ratio only
+    assert_eq!(png::parse_phys(&[0, 0, 0, 1, 0, 0, 0, 2, 0]), None);
+    assert_eq!(png::parse_phys(&[0, 0, 0, 0, 0, 0, 0, 2, 1]), None);
+    assert_eq!(png::parse_phys(&[0, 0, 0, 1, 0, 0, 0, 2]), None);
+}
+
+fn read_file(name: &str) -> Vec<u8> {
+    std::fs::read(format!("tests/png/{}.png", name)).expect("Read test image")
+}
+
+//Asserts that every pixel of image matches `expected`.
+fn assert_pixels(image: &Image, expected: impl Fn(u32, u32) -> [u8; 4]) {
+    assert_eq!(image.validate(), Ok(()));
+    assert_eq!(image.format, PixelFormat::Rgba8);
+    for y in 0..image.height {
+        for x in 0..image.width {
+            assert_eq!(image.row(y)[x as usize * 4..][..4], expected(x, y), "Pixel at {}x{}", x, y);
+        }
+    }
+}
+
+#[test]
+fn should_parse_header() {
+    let header = png::header(&read_file("rgb16_interlaced")).expect("Parse header");
+    assert_eq!(header, Header {
+        width: 9,
+        height: 10,
+        bit_depth: 16,
+        color_type: COLOR_RGB,
+        interlaced: true,
+    });
+    assert_eq!(header.channels(), 3);
+    //Every Adam7 pass with filter type byte per row
+    assert_eq!(header.data_size(), Some(2 * 13 + 2 * 7 + 19 + 3 * 13 + 2 * 31 + 5 * 25 + 5 * 55));
+
+    let header = png::header(&png_file(&[])).expect("Parse header");
+    assert_eq!(header.data_size(), Some(2));
+
+    let mut data = [0, 0, 0, 1, 0, 0, 0, 1, 8, COLOR_RGBA, 0, 0, 0];
+    assert!(Header::parse(&data).is_ok());
+    data[8] = 4;
+    assert_eq!(Header::parse(&data), Err(Error::Invalid("PNG bit depth")));
+    data[8] = 8;
+    data[12] = 2;
+    assert_eq!(Header::parse(&data), Err(Error::Unsupported("PNG interlace method")));
+    data[12] = 0;
+    data[3] = 0;
+    assert_eq!(Header::parse(&data), Err(Error::Invalid("PNG dimensions")));
+    assert_eq!(Header::parse(&data[..12]), Err(Error::Invalid("PNG header")));
+}
+
+#[test]
+fn should_read_image() {
+    let image = png::read_image(&read_file("rgba8")).expect("Decode RGBA");
+    assert_eq!((image.width, image.height), (7, 5));
+    assert_pixels(&image, |x, y| [x as u8 * 30, y as u8 * 50, 255 - x as u8 * 20, 100 + (x * y) as u8 * 5]);
+    assert_eq!(image.resolution, None);
+    assert_eq!(image.icc_profile, None);
+
+    //16 bits are truncated, while transparent color is compared in full
+    let image = png::read_image(&read_file("rgb16_interlaced")).expect("Decode RGB");
+    assert_pixels(&image, |x, y| {
+        let alpha = match (x, y) {
+            (2, 1) => 0,
+            _ => 255,
+        };
+        [x as u8 * 25, y as u8 * 20, (x * y) as u8, alpha]
+    });
+
+    let image = png::read_image(&read_file("palette2")).expect("Decode palette");
+    let palette = [[255, 0, 0, 0], [0, 255, 0, 128], [0, 0, 255, 255], [10, 20, 30, 255]];
+    assert_pixels(&image, |x, y| palette[(x + y) as usize % 4]);
+
+    let image = png::read_image(&read_file("gray4")).expect("Decode gray");
+    assert_pixels(&image, |x, y| {
+        let gray = (x * 4 + y) as u8 % 16;
+        let alpha = match gray {
+            5 => 0,
+            _ => 255,
+        };
+        [gray * 17, gray * 17, gray * 17, alpha]
+    });
+
+    let image = png::read_image(&read_file("gray_alpha8")).expect("Decode gray with alpha");
+    assert_pixels(&image, |x, y| [x as u8 * 100, x as u8 * 100, x as u8 * 100, 255 - y as u8 * 100]);
+    assert_eq!(image.resolution, Some(Resolution { x: 3780, y: 2835 }));
+    let profile: Vec<u8> = (0..1024).map(|index| index as u8).collect();
+    assert_eq!(image.icc_profile, Some(profile));
+}
+
+#[test]
+fn should_write_image() {
+    let mut image = Image::new(3, 2, PixelFormat::Bgra8).expect("Create image");
+    for (index, pixel) in image.pixels.iter_mut().enumerate() {
+        *pixel = index as u8 * 10;
+    }
+    image.resolution = Some(Resolution::from_dpi(300, 300));
+    image.icc_profile = Some(b"ICC profile".to_vec());
+
+    let mut out = vec![1];
+    let size = png::write_image(&mut out, &image).expect("Encode");
+    assert_eq!(size, out.len() - 1);
+    let kinds: Vec<[u8; 4]> = png::chunks(&out[1..]).expect("Parse").map(|chunk| chunk.kind).collect();
+    assert_eq!(kinds, [IHDR, ICCP, PHYS, IDAT, IEND]);
+
+    let decoded = png::read_image(&out[1..]).expect("Decode");
+    image.convert(PixelFormat::Rgba8);
+    assert_eq!(decoded, image);
+
+    //Padding of rows is dropped
+    let mut padded = image.clone();
+    padded.stride = 16;
+    padded.pixels = image.pixels[..12].iter().chain(&[0xEE; 4]).chain(&image.pixels[12..]).copied().collect();
+    out.clear();
+    png::write_image(&mut out, &padded).expect("Encode");
+    assert_eq!(png::read_image(&out).expect("Decode"), image);
+
+    out.clear();
+    assert_eq!(png::write_image(&mut out, &Image::default()), Err(Error::Invalid("image dimensions")));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn should_reject_invalid_image() {
+    let data = read_file("rgba8");
+    let mut invalid = data.clone();
+
+    //Corrupted image data, with CRC of chunk left as is
+    let idat = png::chunks(&data).expect("Parse").find(|chunk| chunk.kind == IDAT).expect("IDAT");
+    let offset = idat.bytes.as_ptr() as usize - data.as_ptr() as usize + 8;
+    invalid[offset] ^= 0x0F;
+    assert_eq!(png::read_image(&invalid), Err(Error::Invalid("zlib header")));
+    invalid[offset] = data[offset];
+    invalid[offset + 5] ^= 0xFF;
+    assert!(png::read_image(&invalid).is_err());
+
+    //Missing end
+    let end = data.len() - 12;
+    assert_eq!(png::read_image(&data[..end]), Err(Error::Truncated));
+
+    //Image data exceeding size of image
+    let mut compressed = Vec::new();
+    png::write_zlib_stored(&mut compressed, &[0, 0x80, 0]);
+    let mut oversized = SIGNATURE.to_vec();
+    png::write_chunk(&mut oversized, IHDR, &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]).expect("Write IHDR");
+    png::write_chunk(&mut oversized, IDAT, &compressed).expect("Write IDAT");
+    png::write_chunk(&mut oversized, IEND, &[]).expect("Write IEND");
+    assert_eq!(png::read_image(&oversized), Err(Error::Invalid("deflate data exceeds expected size")));
+
+    //Palette image without palette
+    let mut out = Vec::new();
+    png::insert_chunks(&read_file("palette2"), &[*b"PLTE"], &[], &mut out).expect("Remove palette");
+    assert_eq!(png::read_image(&out), Err(Error::Invalid("PNG palette")));
+}
+
+#[test]
+fn should_read_iccp() {
+    let mut chunk = Vec::new();
+    png::write_iccp(&mut chunk, "Profile", b"ICC profile data").expect("Write");
+    let data = &chunk[8..chunk.len() - 4];
+
+    let mut out = vec![1];
+    assert_eq!(png::read_iccp(data, &mut out), Ok(16));
+    assert_eq!(&out[1..], b"ICC profile data");
+
+    let mut invalid = data.to_vec();
+    invalid[8] = 1;
+    assert_eq!(png::read_iccp(&invalid, &mut out), Err(Error::Unsupported("PNG compression method")));
+    assert_eq!(png::read_iccp(&data[7..], &mut out), Err(Error::Invalid("PNG profile name")));
+    assert_eq!(png::read_iccp(&data[..data.len() - 1], &mut out), Err(Error::Truncated));
+    assert_eq!(out.len(), 17);
+}
@@ -0,0 +1,17 @@
+//Builds RIFF container of `form`, padding each chunk to even size.
+pub fn form(form: &[u8; 4], chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut body = form.to_vec();
+    for (id, content) in chunks {
+        body.extend_from_slice(*id);
+        body.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        body.extend_from_slice(content);
+        if content.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    data.extend_from_slice(&body);
+    data
+}
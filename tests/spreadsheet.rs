@@ -0,0 +1,89 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::grid::{Cell, Value, Format, NumberFormat, Align};
+use clipboard_win::codec::spreadsheet::{self, Workbook, Worksheet, Merge};
+
+fn text(value: &str) -> Value {
+    Value::Text(value.to_owned())
+}
+
+fn parse(text: &str) -> Result<Workbook, Error> {
+    let mut workbook = Workbook::default();
+    spreadsheet::parse(text, &mut workbook).map(|_| workbook)
+}
+
+#[test]
+fn should_parse_excel_output() {
+    let content = std::fs::read_to_string("tests/spreadsheet/excel.xml").expect("Read test file");
+    let mut workbook = Workbook::default();
+    assert_eq!(spreadsheet::parse(&content, &mut workbook).expect("Parse"), 1);
+
+    let worksheet = &workbook.worksheets[0];
+    assert_eq!(worksheet.name, "Data & Notes");
+    assert_eq!(worksheet.merges, [
+        Merge { row: 0, column: 0, rows: 1, columns: 2 },
+        Merge { row: 2, column: 4, rows: 2, columns: 1 },
+    ]);
+
+    let grid = &worksheet.grid;
+    assert_eq!(grid.len(), 8);
+    assert_eq!(grid.get(0, 0), Some(&Cell {
+        value: text("Header"),
+        formula: None,
+        format: Some(Format { align: Align::Center, bold: true, ..Format::default() }),
+    }));
+    //Merged cell is skipped
+    assert_eq!(grid.get(0, 2).unwrap().value, text("A <b> \nline"));
+    assert_eq!(grid.get(2, 0).unwrap().value, Value::DateTime("2021-03-04T00:00:00.000".to_owned()));
+    assert_eq!(grid.get(2, 1).unwrap().format, Some(Format { number: NumberFormat::Percent, digits: 3, ..Format::default() }));
+    assert_eq!(grid.get(2, 2), Some(&Cell { value: Value::Number(0.25), formula: Some("=RC[-1]*2".to_owned()), format: None }));
+    assert_eq!(grid.get(2, 4).unwrap().value, Value::Bool(true));
+    assert_eq!(grid.get(3, 0).unwrap().value, Value::Error("#DIV/0!".to_owned()));
+    assert_eq!(grid.get(3, 1).unwrap().value, text("rich text"));
+}
+
+#[test]
+fn should_round_trip() {
+    let mut worksheet = Worksheet { name: "Sheet \"1\"".to_owned(), ..Worksheet::default() };
+    worksheet.grid.set(0, 0, Cell {
+        value: text("Total <all>"),
+        formula: None,
+        format: Some(Format { number: NumberFormat::Fixed, digits: 3, align: Align::Right, bold: true, italic: true }),
+    });
+    worksheet.grid.set(0, 3, Cell { value: Value::Number(-1.5e-7), formula: Some("=SUM(R[1]C:R[5]C)".to_owned()), format: None });
+    worksheet.grid.set(4, 1, Cell {
+        value: Value::DateTime("1999-12-31T23:59:59.000".to_owned()),
+        formula: None,
+        format: Some(Format { number: NumberFormat::Exponent, digits: 2, ..Format::default() }),
+    });
+    worksheet.grid.set(5, 0, Value::Bool(false));
+    worksheet.grid.set(5, 1, text("line\r\nbreak\ttab"));
+    worksheet.merges.push(Merge { row: 2, column: 2, rows: 2, columns: 3 });
+    let workbook = Workbook { worksheets: vec![worksheet, Worksheet::default()] };
+
+    let mut out = String::new();
+    assert_eq!(spreadsheet::write(&mut out, &workbook).expect("Write"), out.len());
+    assert!(out.contains("<Style ss:ID=\"s1\"><Alignment ss:Horizontal=\"Right\"/><Font ss:Bold=\"1\" ss:Italic=\"1\"/><NumberFormat ss:Format=\"0.000\"/></Style>"));
+    assert!(out.contains("<Cell ss:Index=\"3\" ss:MergeAcross=\"2\" ss:MergeDown=\"1\"/>"));
+    assert_eq!(parse(&out).expect("Parse back"), workbook);
+}
+
+#[test]
+fn should_reject_invalid_content() {
+    assert_eq!(parse(""), Err(Error::Truncated));
+    assert_eq!(parse("<Other/>"), Err(Error::Invalid("root element is not Workbook")));
+    assert_eq!(parse("<Workbook><Worksheet></Workbook>"), Err(Error::Invalid("mismatched end tag")));
+    assert_eq!(parse("<Workbook>"), Err(Error::Truncated));
+    assert_eq!(parse("<Workbook/><Workbook/>"), Err(Error::Invalid("content after root element")));
+    assert_eq!(parse("<Workbook a=b/>"), Err(Error::Invalid("attribute value is not quoted")));
+    assert_eq!(parse("<Workbook>&custom;</Workbook>"), Err(Error::Unsupported("entity")));
+    assert_eq!(parse("<!DOCTYPE x [<!ENTITY e \"boom\">]><Workbook>&e;</Workbook>"), Err(Error::Unsupported("entity")));
+    assert_eq!(parse("<Workbook><Worksheet><Table><Row ss:Index=\"0\"/></Table></Worksheet></Workbook>"), Err(Error::Invalid("cell coordinate")));
+    assert_eq!(parse("<Workbook><Worksheet><Table><Row><Cell><Data ss:Type=\"Number\">x</Data></Cell></Row></Table></Worksheet></Workbook>"), Err(Error::Invalid("cell value")));
+    assert_eq!(parse("<Workbook><Worksheet><Table><Row ss:Index=\"4294967295\"><Cell ss:MergeDown=\"2\"/></Row></Table></Worksheet></Workbook>"), Err(Error::Invalid("merged range")));
+
+    let mut worksheet = Worksheet::default();
+    worksheet.grid.set(0, 0, text("null\0"));
+    let mut out = String::from("prefix");
+    assert_eq!(spreadsheet::write(&mut out, &Workbook { worksheets: vec![worksheet] }), Err(Error::Invalid("character is not allowed in XML")));
+    assert_eq!(out, "prefix");
+}
@@ -0,0 +1,99 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::svg::{self, Encoding, Length, Unit, ViewBox};
+
+const DOCUMENT: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2in\" height=\"48\" viewBox=\"0, 0 192 48\"><rect width=\"10\" height=\"10\" fill=\"#ff0000\"/><text x=\"0\" y=\"40\">A &amp; B</text></svg>\n";
+
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    text.encode_utf16().flat_map(|unit| match big_endian {
+        true => unit.to_be_bytes(),
+        false => unit.to_le_bytes(),
+    }).collect()
+}
+
+#[test]
+fn should_parse_dimensions() {
+    let svg = svg::parse(DOCUMENT).expect("Parse");
+    assert_eq!(svg.width, Some(Length { value: 2.0, unit: Unit::In }));
+    assert_eq!(svg.height, Some(Length { value: 48.0, unit: Unit::None }));
+    assert_eq!(svg.view_box, Some(ViewBox { x: 0.0, y: 0.0, width: 192.0, height: 48.0 }));
+    assert_eq!(svg.size(), Some((192.0, 48.0)));
+}
+
+#[test]
+fn should_take_relative_size_from_view_box() {
+    let svg = svg::parse("<svg:svg xmlns:svg=\"http://www.w3.org/2000/svg\" width=\"100%\" height=\"auto\" viewBox=\"-5 -5 20.5 1e1\"/>").expect("Parse");
+    assert_eq!(svg.width, Some(Length { value: 100.0, unit: Unit::Percent }));
+    assert_eq!(svg.height, None);
+    assert_eq!(svg.size(), Some((20.5, 10.0)));
+
+    let svg = svg::parse("<svg width=\"3em\"></svg>").expect("Parse");
+    assert_eq!(svg.size(), None);
+}
+
+#[test]
+fn should_convert_length_units() {
+    for (text, pixels) in &[("12", Some(12.0)), ("12px", Some(12.0)), (" 1in ", Some(96.0)), ("2.54cm", Some(96.0)), ("25.4mm", Some(96.0)), ("72pt", Some(96.0)), ("6pc", Some(96.0)), ("1ex", None)] {
+        let length = Length::parse(text).expect("Parse length");
+        assert!(match (length.pixels(), pixels) {
+            (Some(actual), Some(expected)) => (actual - expected).abs() < 1e-9,
+            (actual, expected) => actual == *expected,
+        }, "{}", text);
+    }
+
+    assert_eq!(Length::parse("-1px"), Err(Error::Invalid("SVG length")));
+    assert_eq!(Length::parse("1 px"), Err(Error::Invalid("SVG length")));
+    assert_eq!(Length::parse("inf"), Err(Error::Invalid("SVG length")));
+    assert_eq!(Length::parse("px"), Err(Error::Invalid("SVG length")));
+}
+
+#[test]
+fn should_reject_invalid_document() {
+    assert_eq!(svg::parse("<html><svg/></html>"), Err(Error::Invalid("root element is not svg")));
+    assert_eq!(svg::parse("<!-- empty -->"), Err(Error::Truncated));
+    assert_eq!(svg::parse("<svg><g></svg>"), Err(Error::Invalid("mismatched end tag")));
+    assert_eq!(svg::parse("<svg viewBox=\"0 0 10\"/>"), Err(Error::Invalid("SVG viewBox")));
+    assert_eq!(svg::parse("<svg viewBox=\"0 0 10 10 10\"/>"), Err(Error::Invalid("SVG viewBox")));
+    assert_eq!(svg::parse("<svg viewBox=\"0 0 -10 10\"/>"), Err(Error::Invalid("SVG viewBox")));
+    assert_eq!(svg::parse("<svg width=\"wide\"/>"), Err(Error::Invalid("SVG length")));
+    assert_eq!(svg::parse("<svg xmlns=\"&ns_svg;\"/>"), Err(Error::Unsupported("entity")));
+}
+
+#[test]
+fn should_detect_encoding() {
+    assert_eq!(Encoding::detect(b"<svg/>"), (Encoding::Utf8, 0));
+    assert_eq!(Encoding::detect(b"\xEF\xBB\xBF<svg/>"), (Encoding::Utf8, 3));
+    assert_eq!(Encoding::detect(b"\xFF\xFE<\x00"), (Encoding::Utf16Le, 2));
+    assert_eq!(Encoding::detect(b"\xFE\xFF\x00<"), (Encoding::Utf16Be, 2));
+    assert_eq!(Encoding::detect(b"<\x00s\x00"), (Encoding::Utf16Le, 0));
+    assert_eq!(Encoding::detect(b"\x00<\x00s"), (Encoding::Utf16Be, 0));
+    assert_eq!(Encoding::detect(b""), (Encoding::Utf8, 0));
+}
+
+#[test]
+fn should_decode_every_encoding() {
+    let mut with_bom = b"\xEF\xBB\xBF".to_vec();
+    with_bom.extend_from_slice(DOCUMENT.as_bytes());
+    with_bom.push(0);
+
+    let mut utf16le = vec![0xFF, 0xFE];
+    utf16le.extend_from_slice(&utf16(DOCUMENT, false));
+    utf16le.extend_from_slice(&[0, 0]);
+
+    let mut utf16be = vec![0xFE, 0xFF];
+    utf16be.extend_from_slice(&utf16(DOCUMENT, true));
+
+    for data in &[DOCUMENT.as_bytes().to_vec(), with_bom, utf16le, utf16(DOCUMENT, false), utf16be, utf16(DOCUMENT, true)] {
+        let mut out = "prefix".to_owned();
+        assert_eq!(svg::decode(data, &mut out).expect("Decode"), DOCUMENT.len());
+        assert_eq!(&out["prefix".len()..], DOCUMENT);
+    }
+}
+
+#[test]
+fn should_reject_invalid_encoding() {
+    let mut out = "prefix".to_owned();
+    assert_eq!(svg::decode(b"<svg>\xFF</svg>", &mut out), Err(Error::Invalid("SVG is not valid UTF-8")));
+    assert_eq!(svg::decode(b"\xFF\xFE<\x00s", &mut out), Err(Error::Invalid("SVG is not valid UTF-16")));
+    assert_eq!(svg::decode(b"\xFF\xFE<\x00\x00\xD8<\x00", &mut out), Err(Error::Invalid("SVG is not valid UTF-16")));
+    assert_eq!(out, "prefix");
+}
@@ -0,0 +1,69 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::grid::{Grid, Cell, Value, Format, NumberFormat, Align};
+use clipboard_win::codec::sylk;
+
+fn excel_grid() -> Grid {
+    let mut grid = Grid::new();
+    grid.set(0, 0, Cell {
+        value: Value::Text("Name".to_owned()),
+        formula: None,
+        format: Some(Format { number: NumberFormat::General, digits: 0, align: Align::Left, bold: true, italic: false }),
+    });
+    grid.set(0, 1, Cell {
+        value: Value::Text("a;b".to_owned()),
+        formula: None,
+        format: Some(Format { number: NumberFormat::Fixed, digits: 2, align: Align::Right, bold: false, italic: false }),
+    });
+    grid.set(1, 0, Value::Text("Alice".to_owned()));
+    grid.set(1, 1, Value::Number(1.5));
+    grid.set(1, 2, Cell { value: Value::Number(3.0), formula: Some("R2C2*2".to_owned()), format: None });
+    grid.set(2, 0, Value::Bool(true));
+    grid.set(2, 1, Cell { value: Value::Error("#DIV/0!".to_owned()), formula: Some("1/0".to_owned()), format: None });
+    grid.set(2, 2, Value::Number(-2500.0));
+    grid
+}
+
+#[test]
+fn should_parse_excel_output() {
+    let text = std::fs::read_to_string("tests/sylk/excel.slk").expect("Read golden file");
+    let grid = sylk::parse(&text).expect("Parse");
+    assert_eq!(grid, excel_grid());
+    assert_eq!((grid.rows(), grid.columns()), (3, 3));
+}
+
+#[test]
+fn should_write_golden_file() {
+    let golden = std::fs::read_to_string("tests/sylk/grid.slk").expect("Read golden file");
+    let mut out = String::new();
+    assert_eq!(sylk::write(&mut out, &excel_grid()).expect("Write"), out.len());
+    assert_eq!(out, golden);
+    assert_eq!(sylk::parse(&out).expect("Parse back"), excel_grid());
+}
+
+#[test]
+fn should_write_empty_grid() {
+    let mut out = String::new();
+    sylk::write(&mut out, &Grid::new()).expect("Write");
+    assert_eq!(out, "ID;PWXL;N;E\r\nE\r\n");
+    assert!(sylk::parse(&out).expect("Parse back").is_empty());
+}
+
+#[test]
+fn should_reject_invalid_content() {
+    assert_eq!(sylk::parse(""), Err(Error::Invalid("missing ID record")));
+    assert_eq!(sylk::parse("C;Y1;X1;K1\r\nE"), Err(Error::Invalid("missing ID record")));
+    assert_eq!(sylk::parse("ID;P\r\nC;Y1;X1;K1\r\n"), Err(Error::Truncated));
+    assert_eq!(sylk::parse("ID;P\r\nC;Y0;X1;K1\r\nE"), Err(Error::Invalid("cell coordinate")));
+    assert_eq!(sylk::parse("ID;P\r\nC;Y1;X1;Kabc\r\nE"), Err(Error::Invalid("cell value")));
+    assert_eq!(sylk::parse("ID;P\r\nC;Y1;X1;Kinf\r\nE"), Err(Error::Invalid("cell value")));
+    assert_eq!(sylk::parse("ID;P\r\nF;FZ0L;Y1;X1\r\nE"), Err(Error::Invalid("cell format")));
+
+    let mut grid = Grid::new();
+    grid.set(0, 0, Value::Text("line\nbreak".to_owned()));
+    let mut out = String::from("prefix");
+    assert_eq!(sylk::write(&mut out, &grid), Err(Error::Invalid("cell contains line break or null character")));
+    assert_eq!(out, "prefix");
+
+    grid.set(0, 0, Value::Number(f64::NAN));
+    assert_eq!(sylk::write(&mut out, &grid), Err(Error::Invalid("number is not finite")));
+}
@@ -0,0 +1,64 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::table::{self, CSV, TSV};
+
+fn parse(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut out = Vec::new();
+    let len = table::parse(text, delimiter, &mut out);
+    assert_eq!(len, out.len());
+    out
+}
+
+#[test]
+fn should_parse_excel_tsv() {
+    let text = "Name\tNote\r\nAlice\t\"multi\r\nline \"\"quoted\"\"\"\r\nBob\t\r\n\t\r\n\t\r\n\0";
+    assert_eq!(parse(text, TSV), vec![
+        vec!["Name", "Note"],
+        vec!["Alice", "multi\r\nline \"quoted\""],
+        vec!["Bob", ""],
+    ]);
+}
+
+#[test]
+fn should_parse_csv() {
+    assert_eq!(parse("a,\"b,c\",d\n1,2", CSV), vec![vec!["a", "b,c", "d"], vec!["1", "2"]]);
+    assert_eq!(parse("a\r\rb\r", CSV), vec![vec!["a"], vec![""], vec!["b"]]);
+    assert_eq!(parse("\"\",x", CSV), vec![vec!["", "x"]]);
+    assert!(parse("", CSV).is_empty());
+    assert!(parse("\r\n,\r\n", CSV).is_empty());
+}
+
+#[test]
+fn should_parse_malformed_quotes() {
+    assert_eq!(parse("a\"b\",\"c\"d", CSV), vec![vec!["a\"b\"", "cd"]]);
+    assert_eq!(parse("\"unterminated,\r\nrest", CSV), vec![vec!["unterminated,\r\nrest"]]);
+}
+
+#[test]
+fn should_sniff_delimiter() {
+    assert_eq!(table::sniff("a,b,c\r\nd;e;f;g"), ',');
+    assert_eq!(table::sniff("a;b;\"c,d,e\"\r\n"), ';');
+    assert_eq!(table::sniff("single"), ',');
+}
+
+#[test]
+fn should_write_and_parse_back() {
+    let rows = [
+        vec!["plain", "with,comma", "with\ttab"],
+        vec!["\"quoted\"", "multi\nline", ""],
+    ];
+
+    let mut out = String::new();
+    assert_eq!(table::write(&mut out, &rows, CSV).unwrap(), out.len());
+    assert_eq!(out, "plain,\"with,comma\",with\ttab\r\n\"\"\"quoted\"\"\",\"multi\nline\",\r\n");
+    assert_eq!(parse(&out, CSV), rows);
+
+    out.clear();
+    table::write(&mut out, &rows, TSV).unwrap();
+    assert_eq!(out, "plain\twith,comma\t\"with\ttab\"\r\n\"\"\"quoted\"\"\"\t\"multi\nline\"\t\r\n");
+    assert_eq!(parse(&out, TSV), rows);
+
+    out.clear();
+    out.push_str("prefix");
+    assert_eq!(table::write(&mut out, &[["a\0"]], CSV), Err(Error::Invalid("cell contains null character")));
+    assert_eq!(out, "prefix");
+}
@@ -1,5 +1,7 @@
-use clipboard_win::{Getter, Setter, Clipboard, is_format_avail};
-use clipboard_win::formats::{RawData, Unicode, Bitmap, CF_TEXT, CF_UNICODETEXT, CF_BITMAP};
+#![cfg(windows)]
+
+use clipboard_win::{Getter, Setter, Clipboard, is_format_avail, is_sensitive};
+use clipboard_win::formats::{RawData, Unicode, Bitmap, FileList, Sensitive, VirtualFiles, VirtualFile, Url, Table, Sylk, Dif, XmlSpreadsheet, Wave, Tiff, EnhMetafile, MetafilePict, Dib, DibV5, Png, Jpeg, Gif, WebP, Svg, CF_TEXT, CF_UNICODETEXT, CF_BITMAP, CF_HDROP};
 
 fn should_work_with_bitmap() {
     let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
@@ -12,7 +14,39 @@ fn should_work_with_bitmap() {
     assert_eq!(Bitmap.read_clipboard(&mut out).expect("To get image"), out.len());
 
     assert_eq!(test_image_bytes.len(), out.len());
-    assert!(test_image_bytes == out);
+    //Resolution of bitmap is resolution of screen
+    let resolution = clipboard_win::codec::bmp::parse_file(&out).expect("Parse image").resolution().expect("Resolution");
+    assert!(resolution.dpi().0 >= 96);
+    assert!(test_image_bytes[..38] == out[..38]);
+    assert!(test_image_bytes[46..] == out[46..]);
+}
+
+fn should_work_with_dibv5_profile() {
+    use clipboard_win::codec::{bmp::ProfileBuf, png};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let test_image_bytes = std::fs::read("tests/test-image.bmp").expect("Read test image");
+    let profile = b"Wide gamut profile".to_vec();
+    clipboard_win::raw::set_dibv5(&test_image_bytes, Some(&profile)).expect("To set image");
+    assert_eq!(clipboard_win::raw::get_dibv5_profile().expect("To get profile"), Some(ProfileBuf::Embedded(profile)));
+
+    let mut out = Vec::new();
+    clipboard_win::raw::get_dibv5(&mut out).expect("To get image");
+    let kinds: Vec<[u8; 4]> = png::chunks(&out).expect("Parse PNG").map(|chunk| chunk.kind).collect();
+    assert_eq!(kinds[1], png::ICCP);
+
+    clipboard_win::raw::set_dibv5(&test_image_bytes, None).expect("To set image");
+    assert_eq!(clipboard_win::raw::get_dibv5_profile().expect("To get profile"), None);
+
+    //Resolution is carried over as pHYs chunk
+    let mut image = test_image_bytes.clone();
+    image[38..46].copy_from_slice(&[0xC4, 0x0E, 0, 0, 0xC4, 0x0E, 0, 0]);
+    clipboard_win::raw::set_dibv5(&image, None).expect("To set image");
+    out.clear();
+    clipboard_win::raw::get_dibv5(&mut out).expect("To get image");
+    let resolution = png::resolution(&out).expect("Parse PNG").expect("Resolution");
+    assert_eq!(resolution.dpi(), (96, 96));
 }
 
 fn should_work_with_string() {
@@ -72,8 +106,378 @@ fn should_work_with_bytes() {
     assert_eq!(format!("{0}{0}", text), output);
 }
 
+fn should_work_with_empty_string() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    Unicode.write_clipboard(&"").expect("Write empty text");
+    assert!(is_format_avail(CF_UNICODETEXT));
+
+    let mut output = String::new();
+    assert_eq!(Unicode.read_clipboard(&mut output).expect("Read empty text"), 0);
+    assert!(output.is_empty());
+}
+
+fn should_work_with_empty_bytes() {
+    let ascii = RawData(CF_TEXT);
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    ascii.write_clipboard(&b"").expect("Write empty bytes");
+    assert!(is_format_avail(CF_TEXT));
+
+    let mut output = Vec::new();
+    assert_eq!(ascii.read_clipboard(&mut output).expect("Read empty bytes"), 0);
+    assert!(output.is_empty());
+
+    let mut output = [1u8; 4];
+    assert_eq!(clipboard_win::raw::get(CF_TEXT, &mut output).expect("Read empty bytes"), 0);
+    assert_eq!(clipboard_win::raw::get(CF_TEXT, &mut []).expect("Read into empty buffer"), 0);
+}
+
+fn should_work_with_file_list() {
+    let files = ["C:\\Windows\\notepad.exe", "C:\\ファイル.txt"];
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    clipboard_win::raw::set_file_list(&files).expect("Write file list");
+
+    let mut output = Vec::<String>::new();
+    assert_eq!(FileList.read_clipboard(&mut output).expect("Read file list"), files.len());
+    assert_eq!(output, files);
+}
+
+fn should_work_with_empty_file_list() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    clipboard_win::raw::set_file_list(&[] as &[&str]).expect("Write empty file list");
+    assert!(is_format_avail(CF_HDROP));
+
+    let mut output = Vec::<String>::new();
+    assert_eq!(FileList.read_clipboard(&mut output).expect("Read empty file list"), 0);
+    assert!(output.is_empty());
+}
+
+#[cfg(feature = "std")]
+fn should_work_with_file_list_paths() {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use std::path::PathBuf;
+
+    //Name with unpaired surrogate, that cannot be represented as String
+    let name = [b'C' as u16, b':' as u16, b'\\' as u16, 0xD800, b'x' as u16];
+    let mut data = Vec::new();
+    for word in &[20u32, 0, 0, 0, 1] {
+        data.extend_from_slice(&word.to_le_bytes());
+    }
+    for unit in name.iter().chain(&[0, 0]) {
+        data.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    let clip = Clipboard::new_attempts(10).expect("Open clipboard");
+    RawData(CF_HDROP).write_clipboard(&data).expect("Write file list");
+
+    let expected = OsString::from_wide(&name);
+
+    let mut output = Vec::<OsString>::new();
+    assert_eq!(FileList.read_clipboard(&mut output).expect("Read file list"), 1);
+    assert_eq!(output, core::slice::from_ref(&expected));
+
+    let mut output = Vec::<PathBuf>::new();
+    assert_eq!(FileList.read_clipboard(&mut output).expect("Read file list"), 1);
+    assert_eq!(output, [PathBuf::from(expected.clone())]);
+
+    let mut files = FileList.iter(&clip).expect("Iterate file list");
+    //List is copied, so it outlives content of clipboard
+    clipboard_win::raw::empty().expect("Empty clipboard");
+    assert_eq!(files.len(), 1);
+    assert_eq!(files.next().expect("Get path").expect("Decode path"), PathBuf::from(expected));
+    assert!(files.next().is_none());
+}
+
+fn should_mark_sensitive() {
+    let text = "hunter2";
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    Sensitive(Unicode).write_clipboard(&text).expect("Write sensitive text");
+    assert!(is_sensitive());
+
+    let mut output = String::new();
+    assert_eq!(Unicode.read_clipboard(&mut output).expect("Read text"), text.len());
+    assert_eq!(text, output);
+
+    Unicode.write_clipboard(&text).expect("Write text");
+    assert!(!is_sensitive());
+}
+
+#[cfg(feature = "zeroize")]
+fn should_work_with_zeroizing() {
+    use clipboard_win::Zeroizing;
+
+    let text = Zeroizing::new(String::from("correct horse battery staple"));
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    Unicode.write_clipboard(&text).expect("Write text");
+
+    let mut output = Zeroizing::new(String::new());
+    assert_eq!(Unicode.read_clipboard(&mut output).expect("Read text"), text.len());
+    assert_eq!(text, output);
+
+    assert_eq!(Unicode.read_clipboard(&mut output).expect("Read text"), text.len());
+    assert_eq!(format!("{0}{0}", *text), *output);
+
+    let mut output = Zeroizing::new(Vec::new());
+    assert!(RawData(CF_UNICODETEXT).read_clipboard(&mut output).expect("Read bytes") >= text.len() * 2);
+}
+
+fn should_limit_size() {
+    use clipboard_win::limits::{self, Limits};
+
+    let text = "Too long for limits";
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+    Unicode.write_clipboard(&text).expect("Write text");
+
+    limits::set(Limits::UNLIMITED.with_format(CF_UNICODETEXT, 4));
+    let mut output = String::new();
+    let error = Unicode.read_clipboard(&mut output).expect_err("Exceed limit");
+    limits::set(Limits::UNLIMITED);
+
+    assert_eq!(error.raw_code(), 223);
+    assert!(output.is_empty());
+}
+
 extern "system" {
     fn GetConsoleWindow() -> winapi::shared::windef::HWND;
+    fn OleInitialize(reserved: *mut core::ffi::c_void) -> i32;
+}
+
+fn should_work_with_virtual_files() {
+    assert!(unsafe { OleInitialize(core::ptr::null_mut()) } >= 0);
+
+    let files = [
+        (VirtualFile { name: "first.txt".to_owned(), ..VirtualFile::default() }, &b"first content"[..]),
+        (VirtualFile { name: "dir\\second.bin".to_owned(), ..VirtualFile::default() }, &[0u8, 1, 2][..]),
+    ];
+    VirtualFiles.write_files(&files).expect("Write virtual files");
+
+    {
+        let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+        let mut output = Vec::new();
+        assert_eq!(VirtualFiles.read_clipboard(&mut output).expect("Read descriptors"), 2);
+        assert_eq!(output[0].name, "first.txt");
+        assert_eq!(output[0].info.file_size, 13);
+        assert_eq!(output[1].name, "dir\\second.bin");
+        assert_eq!(output[1].info.file_size, 3);
+    }
+
+    for (idx, (_, content)) in files.iter().enumerate() {
+        let mut output = Vec::new();
+        VirtualFiles.read_contents(idx, &mut output).expect("Read content");
+        assert_eq!(&output[..content.len()], *content);
+    }
+
+    let mut output = Vec::new();
+    assert!(VirtualFiles.read_contents(files.len(), &mut output).is_err());
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+    let format = clipboard_win::register_format("FileGroupDescriptorW").expect("Register format").get();
+    RawData(format).write_clipboard(&[]).expect("Write empty descriptors");
+    let mut output = Vec::new();
+    assert_eq!(VirtualFiles.read_clipboard(&mut output).expect("Read empty descriptors"), 0);
+    assert!(output.is_empty());
+}
+
+fn should_work_with_url() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    Url.write_clipboard(&" https://example.com/path?query=1 ").expect("Write url");
+    let mut output = String::new();
+    assert_eq!(Url.read_clipboard(&mut output).expect("Read url"), 30);
+    assert_eq!(output, "https://example.com/path?query=1");
+
+    let mut text = String::new();
+    Unicode.read_clipboard(&mut text).expect("Read text");
+    assert_eq!(text, "https://example.com/path?query=1");
+
+    let ansi = clipboard_win::register_format("UniformResourceLocator").expect("Register format");
+    let mut bytes = Vec::new();
+    RawData(ansi.get()).read_clipboard(&mut bytes).expect("Read ansi url");
+    assert_eq!(&bytes[..bytes.len() - 1], b"https://example.com/path?query=1");
+
+    assert!(Url.write_clipboard(&"not a url").is_err());
+    assert!(Url.write_clipboard(&"").is_err());
+
+    #[cfg(feature = "std")]
+    {
+        let path = std::env::temp_dir().join("clipboard-win-shortcut.url");
+        std::fs::write(&path, "[InternetShortcut]\r\nURL=https://example.com/shortcut\r\n").expect("Write shortcut");
+        clipboard_win::raw::set_file_list(&[path.to_str().expect("Path")]).expect("Write shortcut file");
+        let mut output = String::new();
+        let result = Url.read_clipboard(&mut output);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(result.expect("Read shortcut url"), 28);
+        assert_eq!(output, "https://example.com/shortcut");
+    }
+}
+
+fn should_work_with_table() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let rows = [vec!["Name", "Value"], vec!["with,comma", "multi\nline"]];
+    Table.write_clipboard(&rows[..]).expect("Write table");
+    assert!(is_format_avail(clipboard_win::register_format("Csv").expect("Register format").get()));
+
+    let mut output = Vec::new();
+    assert_eq!(Table.read_clipboard(&mut output).expect("Read table"), 2);
+    assert_eq!(output, rows);
+
+    let mut text = String::new();
+    Unicode.read_clipboard(&mut text).expect("Read text");
+    assert_eq!(text, "Name\tValue\r\nwith,comma\t\"multi\nline\"\r\n");
+
+    Unicode.write_clipboard(&"a\tb\r\nc\td\r\n\t\r\n").expect("Write text");
+    output.clear();
+    assert_eq!(Table.read_clipboard(&mut output).expect("Read text table"), 2);
+    assert_eq!(output, [["a", "b"], ["c", "d"]]);
+}
+
+fn should_work_with_sylk() {
+    use clipboard_win::codec::grid::{Grid, Cell, Value};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let mut grid = Grid::new();
+    grid.set(0, 0, Value::Text("Total".to_owned()));
+    grid.set(0, 1, Cell { value: Value::Number(4.0), formula: Some("2*2".to_owned()), format: None });
+    Sylk.write_clipboard(&grid).expect("Write sylk");
+
+    let mut output = Grid::new();
+    assert_eq!(Sylk.read_clipboard(&mut output).expect("Read sylk"), 2);
+    assert_eq!(output, grid);
+}
+
+fn should_work_with_dif() {
+    use clipboard_win::codec::grid::Value;
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let rows = vec![
+        vec![Value::Text("Name".to_owned()), Value::Text("Value".to_owned())],
+        vec![Value::Text("half".to_owned()), Value::Number(2.5)],
+    ];
+    Dif.write_clipboard(&rows[..]).expect("Write dif");
+
+    let mut output = Vec::new();
+    assert_eq!(Dif.read_clipboard(&mut output).expect("Read dif"), 2);
+    assert_eq!(output, rows);
+}
+
+fn should_work_with_xml_spreadsheet() {
+    use clipboard_win::codec::grid::Value;
+    use clipboard_win::codec::spreadsheet::{Workbook, Worksheet, Merge};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let mut worksheet = Worksheet { name: "Sheet1".to_owned(), ..Worksheet::default() };
+    worksheet.grid.set(0, 0, Value::DateTime("2021-03-04T00:00:00.000".to_owned()));
+    worksheet.grid.set(1, 0, Value::Number(42.0));
+    worksheet.merges.push(Merge { row: 0, column: 0, rows: 1, columns: 2 });
+    let workbook = Workbook { worksheets: vec![worksheet] };
+    XmlSpreadsheet.write_clipboard(&workbook).expect("Write xml spreadsheet");
+
+    let mut output = Workbook::default();
+    assert_eq!(XmlSpreadsheet.read_clipboard(&mut output).expect("Read xml spreadsheet"), 1);
+    assert_eq!(output, workbook);
+}
+
+fn should_work_with_wave() {
+    use clipboard_win::codec::wave::{self, Spec, SampleFormat};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let spec = Spec { format: SampleFormat::Pcm, channels: 2, sample_rate: 44100, bits_per_sample: 16 };
+    let samples = [0u8, 1, 2, 3, 4, 5, 6, 7];
+    Wave.write_samples(&spec, &samples).expect("Write samples");
+
+    let mut output = Vec::new();
+    assert_eq!(Wave.read_clipboard(&mut output).expect("Read wave"), output.len());
+    let parsed = wave::parse(&output).expect("Parse wave");
+    assert_eq!(parsed.spec, spec);
+    assert_eq!(parsed.samples, samples);
+
+    assert!(Wave.write_clipboard(&b"RIFF\x04\x00\x00\x00WAVE").is_err());
+}
+
+fn should_work_with_tiff() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let test_image_bytes = std::fs::read("tests/test-image.bmp").expect("Read test image");
+    Tiff.write_bitmaps(&[&test_image_bytes, &test_image_bytes]).expect("Write bitmaps");
+
+    let mut tiff = Vec::new();
+    assert_eq!(Tiff.read_clipboard(&mut tiff).expect("Read tiff"), tiff.len());
+    assert_eq!(clipboard_win::codec::tiff::parse(&tiff).expect("Parse tiff").len(), 2);
+
+    let mut pages = Vec::new();
+    assert_eq!(Tiff.read_bitmaps(&mut pages).expect("Read bitmaps"), 2);
+    let mut first = Vec::new();
+    assert_eq!(Tiff.read_bitmap(&mut first).expect("Read bitmap"), first.len());
+    assert_eq!(first, pages[0]);
+
+    Tiff.write_clipboard(&tiff).expect("Write tiff");
+    assert!(Tiff.write_clipboard(&b"II\x2A\x00\x00\x00\x00\x00").is_err());
+}
+
+fn should_work_with_enhmetafile() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    //Header with bounds, frame and reference device, followed by EMR_EOF
+    let mut metafile = Vec::new();
+    for value in &[1u32, 88, 0, 0, 9, 9, 0, 0, 254, 254, 0x464D4520, 0x10000, 108, 2, 0, 0, 0, 0, 1920, 1080, 508, 286, 14, 20, 0, 16, 20] {
+        metafile.extend_from_slice(&value.to_le_bytes());
+    }
+    EnhMetafile.write_clipboard(&metafile).expect("Write metafile");
+    let size = clipboard_win::size(clipboard_win::formats::CF_ENHMETAFILE).expect("Metafile size");
+
+    let mut output = Vec::new();
+    assert_eq!(EnhMetafile.read_clipboard(&mut output).expect("Read metafile"), size.get());
+    assert_eq!(output.len(), size.get());
+    let parsed = clipboard_win::codec::emf::parse(&output).expect("Parse metafile");
+    assert_eq!(parsed.header.frame.right, 254);
+
+    assert!(EnhMetafile.write_clipboard(&metafile[..80].to_vec()).is_err());
+}
+
+fn should_work_with_metafile_pict() {
+    use clipboard_win::codec::{Point, wmf::{self, Picture}};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    //Header, META_SETWINDOWEXT and META_EOF
+    let mut metafile = Vec::new();
+    for value in &[1u16, 9, 0x300, 17, 0, 0, 5, 0, 0, 5, 0, 0x20C, 100, 200, 3, 0, 0] {
+        metafile.extend_from_slice(&value.to_le_bytes());
+    }
+    let picture = Picture {
+        mapping_mode: wmf::MM_ANISOTROPIC,
+        extent: Point { x: 5080, y: 2540 },
+        metafile,
+    };
+    MetafilePict.write_clipboard(&picture).expect("Write metafile picture");
+
+    let mut output = Picture::default();
+    assert_eq!(MetafilePict.read_clipboard(&mut output).expect("Read metafile picture"), picture.metafile.len());
+    assert_eq!(output.mapping_mode, wmf::MM_ANISOTROPIC);
+    assert_eq!(output.extent, picture.extent);
+    let parsed = wmf::parse(&output.metafile).expect("Parse metafile");
+    assert_eq!(parsed.window().expect("Window").right, 200);
+
+    let invalid = Picture {
+        metafile: picture.metafile[..30].to_vec(),
+        ..picture
+    };
+    assert!(MetafilePict.write_clipboard(&invalid).is_err());
 }
 
 fn should_set_owner() {
@@ -91,6 +495,122 @@ fn should_set_owner() {
     }
 }
 
+fn should_work_with_image() {
+    use clipboard_win::codec::Resolution;
+    use clipboard_win::codec::image::{Image, PixelFormat};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let mut image = Image::new(3, 2, PixelFormat::Rgba8).expect("Create image");
+    for (index, pixel) in image.pixels.chunks_exact_mut(4).enumerate() {
+        pixel.copy_from_slice(&[index as u8 * 40, 100, 200, 255]);
+    }
+    image.resolution = Some(Resolution::from_dpi(144, 144));
+    image.icc_profile = Some(b"ICC profile".to_vec());
+
+    DibV5.write_clipboard(&image).expect("To set DIBV5");
+    let mut out = Image::default();
+    assert_eq!(DibV5.read_clipboard(&mut out).expect("To get DIBV5"), image.pixels.len());
+    assert_eq!(out, image);
+    //Converted by system
+    assert!(is_format_avail(CF_BITMAP));
+
+    Dib.write_clipboard(&image).expect("To set DIB");
+    let mut out = Image::default();
+    Dib.read_clipboard(&mut out).expect("To get DIB");
+    assert_eq!(out.pixels, image.pixels);
+    assert_eq!(out.icc_profile, None);
+
+    Png.write_clipboard(&image).expect("To set PNG");
+    let mut out = Image::default();
+    Png.read_clipboard(&mut out).expect("To get PNG");
+    assert_eq!(out, image);
+
+    let png = clipboard_win::register_format("PNG").expect("Register format").get();
+    RawData(png).write_clipboard(b"\x89PNG\r\n\x1a\nnot png").expect("To set malformed PNG");
+    let mut out = Vec::new();
+    assert!(clipboard_win::raw::get_png(&mut out, png).is_err());
+    assert!(out.is_empty());
+
+    //Translucent pixels survive premultiplication of bitmap
+    image.convert(PixelFormat::Bgra8);
+    image.pixels[3] = 0;
+    image.pixels[7] = 128;
+    Bitmap.write_clipboard(&image).expect("To set bitmap");
+    let mut out = Image::default();
+    Bitmap.read_clipboard(&mut out).expect("To get bitmap");
+    assert_eq!(out.format, PixelFormat::Rgba8);
+    assert_eq!(&out.pixels[..4], &[0, 0, 0, 0]);
+    assert_eq!(out.pixels[7], 128);
+    assert_eq!(&out.pixels[8..], &[80, 100, 200, 255, 120, 100, 200, 255, 160, 100, 200, 255, 200, 100, 200, 255]);
+}
+
+fn should_work_with_encoded_images() {
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    //Two frames of 1x1 animation
+    let mut gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00".to_vec();
+    for _ in 0..2 {
+        gif.extend_from_slice(&[0x21, 0xF9, 4, 0, 10, 0, 0, 0]);
+        gif.extend_from_slice(&[0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x4C, 0x01, 0]);
+    }
+    gif.push(0x3B);
+
+    Gif.write_clipboard(&gif).expect("To set GIF");
+    let mut out = Vec::new();
+    assert_eq!(Gif.read_clipboard(&mut out).expect("To get GIF"), gif.len());
+    assert_eq!(out, gif);
+    assert!(is_format_avail(clipboard_win::register_format("GIF").expect("Register format").get()));
+
+    let mut webp = b"RIFF\x14\x00\x00\x00WEBPVP8L\x08\x00\x00\x00\x2F\x00\x00\x00\x00\x00\x00\x00".to_vec();
+    WebP.write_clipboard(&webp).expect("To set WebP");
+    //Trailing data is dropped
+    webp.extend_from_slice(b"trailing");
+    RawData(clipboard_win::register_format("image/webp").expect("Register format").get()).write_clipboard(&webp).expect("To set raw WebP");
+    let mut out = Vec::new();
+    assert_eq!(WebP.read_clipboard(&mut out).expect("To get WebP"), webp.len() - 8);
+    assert_eq!(out, &webp[..webp.len() - 8]);
+
+    //Invalid stream is rejected before clipboard is emptied
+    assert!(Jpeg.write_clipboard(&gif).is_err());
+    assert!(WebP.read_clipboard(&mut Vec::new()).is_ok());
+    assert!(Jpeg.read_clipboard(&mut Vec::new()).is_err());
+}
+
+fn should_work_with_svg() {
+    use clipboard_win::codec::image::{Image, PixelFormat};
+
+    let _clip = Clipboard::new_attempts(10).expect("Open clipboard");
+
+    let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2\" height=\"1\"><rect width=\"2\" height=\"1\" fill=\"red\"/></svg>";
+    Svg.write_clipboard(&svg).expect("To set SVG");
+    let mut out = String::new();
+    assert_eq!(Svg.read_clipboard(&mut out).expect("To get SVG"), svg.len());
+    assert_eq!(out, svg);
+    assert!(is_format_avail(clipboard_win::register_format("SVG").expect("Register format").get()));
+    assert!(!is_format_avail(CF_BITMAP));
+
+    //UTF-16 with byte order mark, as placed by some applications
+    let mut wide = vec![0xFF, 0xFE];
+    wide.extend(svg.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    RawData(clipboard_win::register_format("image/svg+xml").expect("Register format").get()).write_clipboard(&wide).expect("To set raw SVG");
+    let mut out = String::new();
+    Svg.read_clipboard(&mut out).expect("To get UTF-16 SVG");
+    assert_eq!(out, svg);
+
+    let mut image = Image::new(2, 1, PixelFormat::Rgba8).expect("Create image");
+    image.pixels.copy_from_slice(&[255, 0, 0, 255, 255, 0, 0, 255]);
+    Svg.write_with_fallback(svg, &image).expect("To set SVG with fallback");
+    let mut out = Image::default();
+    DibV5.read_clipboard(&mut out).expect("To get fallback");
+    assert_eq!(out.pixels, image.pixels);
+    let mut out = String::new();
+    Svg.read_clipboard(&mut out).expect("To get SVG");
+    assert_eq!(out, svg);
+
+    assert!(Svg.write_clipboard(&"<html/>").is_err());
+}
+
 macro_rules! run {
     ($name:ident) => {
         println!("Clipboard test: {}...", stringify!($name));
@@ -101,10 +621,34 @@ macro_rules! run {
 #[test]
 fn clipboard_should_work() {
     run!(should_work_with_bitmap);
+    run!(should_work_with_dibv5_profile);
     assert!(is_format_avail(CF_BITMAP));
     run!(should_work_with_string);
     assert!(is_format_avail(CF_UNICODETEXT));
     run!(should_work_with_wide_string);
     run!(should_work_with_bytes);
+    run!(should_work_with_empty_string);
+    run!(should_work_with_empty_bytes);
+    run!(should_work_with_file_list);
+    run!(should_work_with_empty_file_list);
+    #[cfg(feature = "std")]
+    run!(should_work_with_file_list_paths);
+    run!(should_mark_sensitive);
+    run!(should_limit_size);
+    #[cfg(feature = "zeroize")]
+    run!(should_work_with_zeroizing);
+    run!(should_work_with_virtual_files);
+    run!(should_work_with_url);
+    run!(should_work_with_table);
+    run!(should_work_with_sylk);
+    run!(should_work_with_dif);
+    run!(should_work_with_xml_spreadsheet);
+    run!(should_work_with_wave);
+    run!(should_work_with_tiff);
+    run!(should_work_with_enhmetafile);
+    run!(should_work_with_metafile_pict);
+    run!(should_work_with_image);
+    run!(should_work_with_encoded_images);
+    run!(should_work_with_svg);
     run!(should_set_owner);
 }
@@ -0,0 +1,411 @@
+use clipboard_win::codec::{Error, Resolution};
+use clipboard_win::codec::bmp::{self, InfoHeader};
+use clipboard_win::codec::tiff::{self, COMPRESSION_LZW, COMPRESSION_PACKBITS, PHOTOMETRIC_RGB};
+
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
+const RATIONAL: u16 = 5;
+
+struct Page<'a> {
+    entries: Vec<(u16, u16, Vec<u32>)>,
+    strip: &'a [u8],
+}
+
+fn page<'a>(width: u32, height: u32, bits: &[u32], photometric: u32, strip: &'a [u8]) -> Page<'a> {
+    Page {
+        entries: vec![
+            (256, LONG, vec![width]),
+            (257, SHORT, vec![height]),
+            (258, SHORT, bits.to_vec()),
+            (262, SHORT, vec![photometric]),
+            (277, SHORT, vec![bits.len() as u32]),
+        ],
+        strip,
+    }
+}
+
+impl<'a> Page<'a> {
+    fn with(mut self, tag: u16, kind: u16, values: &[u32]) -> Self {
+        self.entries.push((tag, kind, values.to_vec()));
+        self
+    }
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16, big_endian: bool) {
+    match big_endian {
+        true => out.extend_from_slice(&value.to_be_bytes()),
+        false => out.extend_from_slice(&value.to_le_bytes()),
+    }
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32, big_endian: bool) {
+    match big_endian {
+        true => out.extend_from_slice(&value.to_be_bytes()),
+        false => out.extend_from_slice(&value.to_le_bytes()),
+    }
+}
+
+//Writes pages with single strip each, adding strip offset and size unless already present.
+fn tiff(big_endian: bool, pages: &[Page]) -> Vec<u8> {
+    let mut out = match big_endian {
+        true => b"MM\x00\x2A".to_vec(),
+        false => b"II\x2A\x00".to_vec(),
+    };
+    let mut next = out.len();
+    out.extend_from_slice(&[0; 4]);
+
+    for page in pages {
+        let strip_offset = out.len() as u32;
+        out.extend_from_slice(page.strip);
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+
+        let mut entries = page.entries.clone();
+        if !entries.iter().any(|entry| entry.0 == 273) {
+            entries.push((273, LONG, vec![strip_offset]));
+            entries.push((279, LONG, vec![page.strip.len() as u32]));
+        }
+        entries.sort_by_key(|entry| entry.0);
+
+        let mut values = Vec::new();
+        for (_, kind, entry_values) in entries.iter() {
+            let mut bytes = Vec::new();
+            for value in entry_values {
+                match *kind {
+                    SHORT => push_u16(&mut bytes, *value as u16, big_endian),
+                    _ => push_u32(&mut bytes, *value, big_endian),
+                }
+            }
+            if bytes.len() > 4 {
+                let offset = out.len() as u32;
+                out.extend_from_slice(&bytes);
+                bytes.clear();
+                push_u32(&mut bytes, offset, big_endian);
+            }
+            bytes.resize(4, 0);
+            values.push(bytes);
+        }
+
+        let ifd = out.len() as u32;
+        let link = match big_endian {
+            true => ifd.to_be_bytes(),
+            false => ifd.to_le_bytes(),
+        };
+        out[next..next + 4].copy_from_slice(&link);
+
+        push_u16(&mut out, entries.len() as u16, big_endian);
+        for ((tag, kind, entry_values), value) in entries.iter().zip(values.iter()) {
+            push_u16(&mut out, *tag, big_endian);
+            push_u16(&mut out, *kind, big_endian);
+            let count = match *kind {
+                RATIONAL => entry_values.len() / 2,
+                _ => entry_values.len(),
+            };
+            push_u32(&mut out, count as u32, big_endian);
+            out.extend_from_slice(value);
+        }
+        next = out.len();
+        out.extend_from_slice(&[0; 4]);
+    }
+
+    out
+}
+
+fn decode(data: &[u8], index: usize) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    tiff::parse(data)?.page(index)?.to_rgba(&mut out)?;
+    Ok(out)
+}
+
+//Encodes codes of TIFF LZW, tracking code size as decoder would.
+fn lzw(codes: &[u16]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut bits = 0u32;
+    let mut bits_len = 0;
+    let mut table_len = 258;
+    let mut first = true;
+
+    for &code in codes {
+        let width = match table_len + 1 {
+            len if len >= 2048 => 12,
+            len if len >= 1024 => 11,
+            len if len >= 512 => 10,
+            _ => 9,
+        };
+        bits = (bits << width) | code as u32;
+        bits_len += width;
+        while bits_len >= 8 {
+            out.push((bits >> (bits_len - 8)) as u8);
+            bits_len -= 8;
+        }
+
+        match code {
+            256 => {
+                table_len = 258;
+                first = true;
+            },
+            257 => (),
+            _ if first => first = false,
+            _ => table_len += 1,
+        }
+    }
+    if bits_len > 0 {
+        out.push((bits << (8 - bits_len)) as u8);
+    }
+
+    out
+}
+
+#[test]
+fn should_parse_gray_page() {
+    let data = tiff(false, &[page(3, 2, &[8], 1, &[0, 128, 255, 1, 2, 3])]);
+
+    let parsed = tiff::parse(&data).expect("Parse");
+    assert_eq!(parsed.len(), 1);
+    assert!(!parsed.is_big_endian());
+
+    let page = parsed.page(0).expect("Page");
+    assert_eq!((page.width, page.height), (3, 2));
+    assert_eq!(page.bits_per_sample, 8);
+    assert_eq!(page.samples_per_pixel, 1);
+    assert_eq!(page.compression, tiff::COMPRESSION_NONE);
+    assert_eq!(page.photometric, tiff::PHOTOMETRIC_BLACK_IS_ZERO);
+
+    let rgba = decode(&data, 0).expect("Decode");
+    assert_eq!(rgba.len(), 3 * 2 * 4);
+    assert_eq!(&rgba[..12], &[0, 0, 0, 255, 128, 128, 128, 255, 255, 255, 255, 255]);
+    assert_eq!(&rgba[12..16], &[1, 1, 1, 255]);
+}
+
+#[test]
+fn should_decode_big_endian_16_bit_rgb() {
+    let strip = [0xFF, 0x00, 0x80, 0x00, 0x00, 0x10, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+    let data = tiff(true, &[page(2, 1, &[16, 16, 16], 2, &strip)]);
+
+    assert!(tiff::parse(&data).expect("Parse").is_big_endian());
+    assert_eq!(decode(&data, 0).expect("Decode"), [0xFF, 0x80, 0x00, 255, 0x12, 0x56, 0x9A, 255]);
+}
+
+#[test]
+fn should_decode_bilevel_and_palette() {
+    //White is zero, rows are padded to whole bytes
+    let data = tiff(false, &[page(3, 2, &[1], 0, &[0b1010_0000, 0b0110_0000])]);
+    let rgba = decode(&data, 0).expect("Decode");
+    let gray: Vec<u8> = rgba.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(gray, [0, 255, 0, 255, 0, 0]);
+
+    let mut colors = vec![0u32; 3 * 16];
+    colors[1] = 0xFFFF;
+    colors[16 + 2] = 0x8000;
+    colors[32 + 15] = 0xFF00;
+    let data = tiff(false, &[page(3, 1, &[4], 3, &[0x12, 0xF0]).with(320, SHORT, &colors)]);
+    assert_eq!(decode(&data, 0).expect("Decode"), [255, 0, 0, 255, 0, 128, 0, 255, 0, 0, 255, 255]);
+
+    //Color map of wrong size
+    let data = tiff(false, &[page(3, 1, &[4], 3, &[0x12, 0xF0]).with(320, SHORT, &colors[..45])]);
+    assert_eq!(decode(&data, 0), Err(Error::Unsupported("TIFF bits or samples per pixel")));
+}
+
+#[test]
+fn should_decode_alpha() {
+    let strip = [200, 100, 50, 255, 64, 32, 0, 128];
+    let data = tiff(false, &[page(2, 1, &[8, 8, 8, 8], 2, &strip).with(338, SHORT, &[2])]);
+    assert_eq!(decode(&data, 0).expect("Decode"), strip);
+
+    //Premultiplied alpha
+    let data = tiff(false, &[page(2, 1, &[8, 8, 8, 8], 2, &strip).with(338, SHORT, &[1])]);
+    assert_eq!(decode(&data, 0).expect("Decode"), [200, 100, 50, 255, 127, 63, 0, 128]);
+
+    //Unspecified extra sample is ignored
+    let data = tiff(false, &[page(2, 1, &[8, 8, 8, 8], 2, &strip).with(338, SHORT, &[0])]);
+    assert_eq!(decode(&data, 0).expect("Decode"), [200, 100, 50, 255, 64, 32, 0, 255]);
+}
+
+#[test]
+fn should_decode_packbits() {
+    //Literal of 2 bytes, run of 3 bytes, no-op and literal of 1 byte
+    let strip = [1, 10, 20, (-2i8) as u8, 30, 0x80, 0, 40];
+    let data = tiff(false, &[page(3, 2, &[8], 1, &strip).with(259, SHORT, &[COMPRESSION_PACKBITS as u32])]);
+    let rgba = decode(&data, 0).expect("Decode");
+    let gray: Vec<u8> = rgba.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(gray, [10, 20, 30, 30, 30, 40]);
+
+    let data = tiff(false, &[page(3, 2, &[8], 1, &strip[..5]).with(259, SHORT, &[COMPRESSION_PACKBITS as u32])]);
+    assert_eq!(decode(&data, 0), Err(Error::Truncated));
+}
+
+#[test]
+fn should_decode_lzw_with_predictor() {
+    //Repeated byte is encoded by code being defined
+    let data = tiff(false, &[page(3, 1, &[8], 1, &lzw(&[256, 7, 258, 257])).with(259, SHORT, &[COMPRESSION_LZW as u32])]);
+    let rgba = decode(&data, 0).expect("Decode");
+    assert_eq!(rgba.chunks(4).map(|pixel| pixel[0]).collect::<Vec<_>>(), [7, 7, 7]);
+
+    //Enough codes to increase code size, with differences of horizontal predictor
+    let mut codes = vec![256];
+    for row in 0..24u16 {
+        codes.push(row);
+        codes.extend(&[1; 23]);
+    }
+    codes.push(257);
+    let data = tiff(false, &[
+        page(24, 24, &[8], 1, &lzw(&codes)).with(259, SHORT, &[COMPRESSION_LZW as u32]).with(317, SHORT, &[2])
+    ]);
+    let rgba = decode(&data, 0).expect("Decode");
+    for (idx, pixel) in rgba.chunks(4).enumerate() {
+        assert_eq!(pixel[0] as usize, idx / 24 + idx % 24);
+    }
+
+    let data = tiff(false, &[page(3, 1, &[8], 1, &lzw(&[256, 7, 300])).with(259, SHORT, &[COMPRESSION_LZW as u32])]);
+    assert_eq!(decode(&data, 0), Err(Error::Invalid("LZW code")));
+}
+
+#[test]
+fn should_read_multiple_pages() {
+    let data = tiff(true, &[page(1, 1, &[8], 1, &[10]), page(2, 1, &[8], 1, &[20, 30])]);
+
+    let parsed = tiff::parse(&data).expect("Parse");
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed.page(1).expect("Page").width, 2);
+    assert_eq!(parsed.page(2).unwrap_err(), Error::Invalid("page index"));
+    assert_eq!(decode(&data, 1).expect("Decode"), [20, 20, 20, 255, 30, 30, 30, 255]);
+
+    let mut bitmap = Vec::new();
+    parsed.page(1).expect("Page").write_bitmap(&mut bitmap).expect("Write bitmap");
+    let dib = bmp::parse_file(&bitmap).expect("Parse bitmap");
+    assert_eq!((dib.header.width, dib.header.height, dib.header.bit_count), (2, -1, 32));
+    assert_eq!(dib.pixels, &[20, 20, 20, 255, 30, 30, 30, 255]);
+}
+
+#[test]
+fn should_reject_invalid() {
+    let data = tiff(false, &[page(1, 1, &[8], 1, &[10])]);
+
+    assert_eq!(tiff::parse(b"XX\x2A\x00\x08\x00\x00\x00").unwrap_err(), Error::Invalid("TIFF byte order"));
+    assert_eq!(tiff::parse(b"II\x2B\x00\x08\x00\x00\x00").unwrap_err(), Error::Unsupported("BigTIFF"));
+    assert_eq!(tiff::parse(b"II\x2A\x00\x00\x00\x00\x00").unwrap_err(), Error::Invalid("missing IFD"));
+    assert_eq!(tiff::parse(&data[..data.len() - 1]).unwrap_err(), Error::Truncated);
+
+    //Next IFD points to itself
+    let mut looped = data.clone();
+    let ifd = looped[4..8].to_vec();
+    let len = looped.len();
+    looped[len - 4..].copy_from_slice(&ifd);
+    assert_eq!(tiff::parse(&looped).unwrap_err(), Error::Invalid("IFD chain loops"));
+
+    //Values outside of data
+    let mut outside = data.clone();
+    let ifd = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    outside[ifd + 2 + 12 * 2 + 4] = 100;
+    assert_eq!(tiff::parse(&outside).unwrap_err(), Error::Truncated);
+
+    let data = tiff(false, &[page(1, 1, &[8], 1, &[10]).with(322, SHORT, &[16])]);
+    assert_eq!(tiff::parse(&data).expect("Parse").page(0).unwrap_err(), Error::Unsupported("tiled TIFF"));
+
+    let data = tiff(false, &[page(1, 1, &[1], 0, &[0]).with(259, SHORT, &[4])]);
+    assert_eq!(decode(&data, 0), Err(Error::Unsupported("TIFF compression")));
+
+    let data = tiff(false, &[page(2, 2, &[8], 1, &[1, 2, 3])]);
+    assert_eq!(decode(&data, 0), Err(Error::Truncated));
+
+    let data = tiff(false, &[page(1, 2, &[8], 1, &[1, 2]).with(278, SHORT, &[1])]);
+    assert_eq!(decode(&data, 0), Err(Error::Invalid("TIFF strip count")));
+
+    let data = tiff(false, &[page(1, 1, &[8, 8, 8, 8], 5, &[1, 2, 3, 4])]);
+    assert_eq!(decode(&data, 0), Err(Error::Unsupported("TIFF photometric interpretation")));
+}
+
+#[test]
+fn should_write_bitmaps() {
+    //Bottom-up 24 bits with padded rows
+    let header = InfoHeader::new(2, 2, 24).expect("Create header");
+    let pixels = [1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0];
+    let mut opaque = Vec::new();
+    bmp::write_file(&mut opaque, &header, &[], &pixels).expect("Write bitmap");
+
+    let header = InfoHeader::new(1, -1, 32).expect("Create header");
+    let mut transparent = Vec::new();
+    bmp::write_file(&mut transparent, &header, &[], &[1, 2, 3, 128]).expect("Write bitmap");
+
+    let mut out = Vec::new();
+    let size = tiff::write_bitmaps(&mut out, &[&opaque]).expect("Write");
+    assert_eq!(size, out.len());
+    let parsed = tiff::parse(&out).expect("Parse");
+    assert_eq!(parsed.len(), 1);
+    let page = parsed.page(0).expect("Page");
+    assert_eq!(page.samples_per_pixel, 3);
+    assert_eq!(page.photometric, PHOTOMETRIC_RGB);
+    assert_eq!(decode(&out, 0).expect("Decode"), [9, 8, 7, 255, 12, 11, 10, 255, 3, 2, 1, 255, 6, 5, 4, 255]);
+
+    let mut out = Vec::new();
+    tiff::write_bitmaps(&mut out, &[&opaque, &transparent]).expect("Write");
+    let parsed = tiff::parse(&out).expect("Parse");
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed.page(1).expect("Page").samples_per_pixel, 4);
+    assert_eq!(decode(&out, 1).expect("Decode"), [3, 2, 1, 128]);
+
+    //Round trip through bitmap
+    let mut bitmap = Vec::new();
+    parsed.page(0).expect("Page").write_bitmap(&mut bitmap).expect("Write bitmap");
+    let (mut expected, mut actual) = (Vec::new(), Vec::new());
+    bmp::parse_file(&opaque).expect("Parse").to_rgba(&mut expected).expect("Decode");
+    bmp::parse_file(&bitmap).expect("Parse").to_rgba(&mut actual).expect("Decode");
+    assert_eq!(actual, expected);
+
+    let empty: [&[u8]; 0] = [];
+    assert_eq!(tiff::write_bitmaps(&mut out, &empty), Err(Error::Invalid("no bitmaps")));
+    let size = out.len();
+    assert_eq!(tiff::write_bitmaps(&mut out, &[&opaque, &opaque[..20]]), Err(Error::Truncated));
+    assert_eq!(out.len(), size);
+}
+
+#[test]
+fn should_map_resolution() {
+    use tiff::{RESOLUTION_UNIT_NONE, RESOLUTION_UNIT_INCH, RESOLUTION_UNIT_CENTIMETER};
+
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_INCH, (96, 1), (192, 2)), Some(Resolution { x: 3780, y: 3780 }));
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_INCH, (72, 1), (300, 1)), Some(Resolution { x: 2835, y: 11811 }));
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_CENTIMETER, (30, 1), (755, 20)), Some(Resolution { x: 3000, y: 3775 }));
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_NONE, (1, 1), (1, 1)), None);
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_INCH, (96, 0), (96, 1)), None);
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_INCH, (0, 1), (96, 1)), None);
+    assert_eq!(tiff::resolution(RESOLUTION_UNIT_CENTIMETER, (u32::MAX, 1), (1, 1)), None);
+
+    assert_eq!(tiff::resolution_tags(Resolution::from_dpi(96, 144)), (RESOLUTION_UNIT_INCH, (96, 1), (144, 1)));
+    assert_eq!(tiff::resolution_tags(Resolution { x: 3000, y: 3780 }), (RESOLUTION_UNIT_CENTIMETER, (3000, 100), (3780, 100)));
+}
+
+#[test]
+fn should_preserve_resolution() {
+    let gray = page(1, 1, &[8], 1, &[0]);
+    let data = tiff(true, &[gray.with(282, RATIONAL, &[300, 1]).with(283, RATIONAL, &[600, 2])]);
+    let first = tiff::parse(&data).expect("Parse").page(0).expect("Page");
+    assert_eq!(first.resolution, Some(Resolution::from_dpi(300, 300)));
+
+    let mut bitmap = Vec::new();
+    first.write_bitmap(&mut bitmap).expect("Write bitmap");
+    let dib = bmp::parse_file(&bitmap).expect("Parse bitmap");
+    assert_eq!(dib.resolution(), Some(Resolution::from_dpi(300, 300)));
+
+    let mut out = Vec::new();
+    tiff::write_bitmaps(&mut out, &[&bitmap]).expect("Write");
+    let parsed = tiff::parse(&out).expect("Parse");
+    assert_eq!(parsed.page(0).expect("Page").resolution, Some(Resolution::from_dpi(300, 300)));
+
+    //Resolution in centimeters, or without absolute unit
+    let data = tiff(false, &[page(1, 1, &[8], 1, &[0]).with(282, RATIONAL, &[40, 1]).with(283, RATIONAL, &[40, 1]).with(296, SHORT, &[3])]);
+    assert_eq!(tiff::parse(&data).expect("Parse").page(0).expect("Page").resolution, Some(Resolution { x: 4000, y: 4000 }));
+    let data = tiff(false, &[page(1, 1, &[8], 1, &[0]).with(282, RATIONAL, &[1, 1]).with(283, RATIONAL, &[2, 1]).with(296, SHORT, &[1])]);
+    assert_eq!(tiff::parse(&data).expect("Parse").page(0).expect("Page").resolution, None);
+
+    //Bitmap without resolution
+    let data = tiff(false, &[page(1, 1, &[8], 1, &[0])]);
+    let first = tiff::parse(&data).expect("Parse").page(0).expect("Page");
+    assert_eq!(first.resolution, None);
+    bitmap.clear();
+    first.write_bitmap(&mut bitmap).expect("Write bitmap");
+    out.clear();
+    tiff::write_bitmaps(&mut out, &[&bitmap]).expect("Write");
+    assert_eq!(tiff::parse(&out).expect("Parse").page(0).expect("Page").resolution, None);
+}
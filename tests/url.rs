@@ -0,0 +1,39 @@
+use clipboard_win::codec::Error;
+use clipboard_win::codec::url;
+
+#[test]
+fn should_validate_url() {
+    assert!(url::validate("https://example.com").is_ok());
+    assert!(url::validate("mailto:user@example.com").is_ok());
+    assert!(url::validate("git+ssh://host/repo.git").is_ok());
+
+    assert_eq!(url::validate(""), Err(Error::Invalid("empty url")));
+    assert_eq!(url::validate("example.com"), Err(Error::Invalid("url scheme")));
+    assert_eq!(url::validate("1http://example.com"), Err(Error::Invalid("url scheme")));
+    assert_eq!(url::validate("https:"), Err(Error::Invalid("url without content")));
+    assert_eq!(url::validate("https://example.com/a b"), Err(Error::Invalid("url contains whitespace or control character")));
+    assert_eq!(url::validate("https://example.com/\n"), Err(Error::Invalid("url contains whitespace or control character")));
+}
+
+#[test]
+fn should_extract_scheme() {
+    assert_eq!(url::scheme("https://example.com"), Some("https"));
+    assert_eq!(url::scheme("view-source:https://example.com"), Some("view-source"));
+    assert_eq!(url::scheme("no scheme"), None);
+    assert_eq!(url::scheme("://example.com"), None);
+}
+
+#[test]
+fn should_trim_url() {
+    assert_eq!(url::trim(" https://example.com\r\n\0\0"), "https://example.com");
+    assert_eq!(url::trim("\0"), "");
+}
+
+#[test]
+fn should_read_internet_shortcut() {
+    let content = "[DEFAULT]\r\nBASEURL=https://base.example.com\r\n[InternetShortcut]\r\nIconIndex=0\r\nurl = https://example.com/page\r\n";
+    assert_eq!(url::from_shortcut(content), Some("https://example.com/page"));
+
+    assert_eq!(url::from_shortcut("[Other]\nURL=https://example.com"), None);
+    assert_eq!(url::from_shortcut(""), None);
+}
@@ -0,0 +1,93 @@
+mod riff;
+
+use clipboard_win::codec::Error;
+use clipboard_win::codec::wave::{self, Spec, SampleFormat};
+
+fn fmt(tag: u16, channels: u16, rate: u32, block_align: u16, bits: u16) -> Vec<u8> {
+    let mut fmt = tag.to_le_bytes().to_vec();
+    fmt.extend_from_slice(&channels.to_le_bytes());
+    fmt.extend_from_slice(&rate.to_le_bytes());
+    fmt.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&bits.to_le_bytes());
+    fmt
+}
+
+#[test]
+fn should_parse_pcm_with_info() {
+    let data = riff::form(b"WAVE", &[
+        (b"fmt ", &fmt(1, 1, 8000, 1, 8)),
+        (b"LIST", b"INFOINAM\x06\x00\x00\x00Snip\x00\x00ISFT\x03\x00\x00\x00ab\x00\x00"),
+        (b"data", &[1, 2, 3]),
+    ]);
+
+    let wave = wave::parse(&data).expect("Parse");
+    assert_eq!(wave.spec, Spec { format: SampleFormat::Pcm, channels: 1, sample_rate: 8000, bits_per_sample: 8 });
+    assert_eq!(wave.samples, [1, 2, 3]);
+    assert_eq!(wave.frames(), 3);
+    assert_eq!(wave.channel_mask, None);
+    assert_eq!(wave.info().collect::<Vec<_>>(), [(*b"INAM", &b"Snip"[..]), (*b"ISFT", &b"ab"[..])]);
+}
+
+#[test]
+fn should_parse_extensible_float() {
+    let mut ext = fmt(0xFFFE, 6, 48000, 24, 32);
+    ext.extend_from_slice(&22u16.to_le_bytes());
+    ext.extend_from_slice(&32u16.to_le_bytes());
+    ext.extend_from_slice(&0x3Fu32.to_le_bytes());
+    ext.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0x10, 0, 0x80, 0, 0, 0xAA, 0, 0x38, 0x9B, 0x71]);
+    let data = riff::form(b"WAVE", &[(b"fmt ", &ext), (b"data", &[0; 50])]);
+
+    let wave = wave::parse(&data).expect("Parse");
+    assert_eq!(wave.spec.format, SampleFormat::Float);
+    assert_eq!(wave.spec.channels, 6);
+    assert_eq!(wave.channel_mask, Some(0x3F));
+    //Incomplete frame is ignored
+    assert_eq!(wave.frames(), 2);
+    assert_eq!(wave.samples.len(), 48);
+}
+
+#[test]
+fn should_write_and_parse_back() {
+    let samples: Vec<u8> = (0..36).collect();
+    let cases = [
+        (Spec { format: SampleFormat::Pcm, channels: 2, sample_rate: 44100, bits_per_sample: 16 }, 44, 16),
+        (Spec { format: SampleFormat::Pcm, channels: 1, sample_rate: 96000, bits_per_sample: 24 }, 68, 24),
+        (Spec { format: SampleFormat::Pcm, channels: 3, sample_rate: 8000, bits_per_sample: 12 }, 68, 12),
+    ];
+
+    for (spec, header_size, valid_bits) in cases.iter() {
+        let mut out = vec![0xFF];
+        assert_eq!(wave::write(&mut out, spec, &samples).expect("Write"), header_size + samples.len());
+
+        let wave = wave::parse(&out[1..]).expect("Parse back");
+        assert_eq!(wave.samples, &samples[..]);
+        assert_eq!(wave.valid_bits, *valid_bits);
+        assert_eq!(wave.spec.channels, spec.channels);
+        assert_eq!(wave.spec.sample_rate, spec.sample_rate);
+        assert_eq!(wave.block_align as usize, spec.block_align());
+    }
+
+    let spec = Spec { format: SampleFormat::Float, channels: 1, sample_rate: 48000, bits_per_sample: 32 };
+    let mut out = Vec::new();
+    wave::write(&mut out, &spec, &[0; 4]).expect("Write float");
+    assert_eq!(wave::parse(&out).expect("Parse back").spec, spec);
+}
+
+#[test]
+fn should_reject_invalid_content() {
+    assert_eq!(wave::parse(b"RIFF").err(), Some(Error::Truncated));
+    assert_eq!(wave::parse(b"RIFX\x04\x00\x00\x00WAVE").err(), Some(Error::Invalid("not RIFF")));
+    assert_eq!(wave::parse(b"RIFF\x04\x00\x00\x00AVI ").err(), Some(Error::Unsupported("RIFF form")));
+    assert_eq!(wave::parse(&riff::form(b"WAVE", &[(b"data", &[0])])).err(), Some(Error::Invalid("data precedes fmt chunk")));
+    assert_eq!(wave::parse(&riff::form(b"WAVE", &[(b"fmt ", &fmt(1, 1, 8000, 1, 8))])).err(), Some(Error::Invalid("missing data chunk")));
+    assert_eq!(wave::parse(&riff::form(b"WAVE", &[(b"fmt ", &fmt(1, 2, 8000, 3, 16)), (b"data", &[])])).err(), Some(Error::Invalid("block align")));
+    assert_eq!(wave::parse(&riff::form(b"WAVE", &[(b"fmt ", &fmt(3, 1, 8000, 2, 16)), (b"data", &[])])).err(), Some(Error::Invalid("bits per sample")));
+    assert_eq!(wave::parse(&riff::form(b"WAVE", &[(b"fmt ", &fmt(1, 0, 8000, 0, 8)), (b"data", &[])])).err(), Some(Error::Invalid("wave format")));
+
+    let spec = Spec { format: SampleFormat::Pcm, channels: 2, sample_rate: 8000, bits_per_sample: 16 };
+    let mut out = Vec::new();
+    assert_eq!(wave::write(&mut out, &spec, &[0; 3]), Err(Error::Invalid("samples are not whole number of frames")));
+    assert_eq!(wave::write(&mut out, &Spec { format: SampleFormat::Other(2), ..spec }, &[]), Err(Error::Unsupported("sample format")));
+    assert!(out.is_empty());
+}
@@ -0,0 +1,99 @@
+mod riff;
+
+use clipboard_win::codec::Error;
+use clipboard_win::codec::webp::{self, WebP};
+
+fn vp8(width: u16, height: u16) -> Vec<u8> {
+    let mut data = vec![0x10, 0x02, 0x00, 0x9D, 0x01, 0x2A];
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&[0; 7]);
+    data
+}
+
+fn vp8l(width: u32, height: u32, alpha: bool) -> Vec<u8> {
+    let bits = (width - 1) | ((height - 1) << 14) | ((alpha as u32) << 28);
+    let mut data = vec![0x2F];
+    data.extend_from_slice(&bits.to_le_bytes());
+    data.extend_from_slice(&[0; 3]);
+    data
+}
+
+fn vp8x(flags: u8, width: u32, height: u32) -> Vec<u8> {
+    let mut data = vec![flags, 0, 0, 0];
+    data.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    data.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+    data
+}
+
+#[test]
+fn should_parse_simple_lossy() {
+    let data = riff::form(b"WEBP", &[(&webp::VP8, &vp8(640, 480))]);
+
+    let webp = webp::parse(&data).expect("Parse");
+    assert_eq!(webp, WebP {
+        width: 640,
+        height: 480,
+        animated: false,
+        alpha: false,
+        lossless: false,
+        size: data.len(),
+    });
+}
+
+#[test]
+fn should_parse_simple_lossless() {
+    let data = riff::form(b"WEBP", &[(&webp::VP8L, &vp8l(3, 16384, true))]);
+
+    let webp = webp::parse(&data).expect("Parse");
+    assert_eq!((webp.width, webp.height), (3, 16384));
+    assert!(webp.lossless);
+    assert!(webp.alpha);
+}
+
+#[test]
+fn should_parse_extended_with_metadata() {
+    let data = riff::form(b"WEBP", &[(&webp::VP8X, &vp8x(0x10 | 0x08, 100_000, 2)), (b"ALPH", &[0; 3]), (&webp::VP8, &vp8(100, 2)), (b"EXIF", b"Exif")]);
+
+    let webp = webp::parse(&data).expect("Parse");
+    assert_eq!((webp.width, webp.height), (100_000, 2));
+    assert!(webp.alpha);
+    assert!(!webp.animated);
+    assert!(!webp.lossless);
+}
+
+#[test]
+fn should_parse_animation() {
+    let mut frame = vec![0; 16];
+    frame.extend_from_slice(&riff::form(b"WEBP", &[(&webp::VP8L, &vp8l(2, 2, false))])[12..]);
+    let data = riff::form(b"WEBP", &[(&webp::VP8X, &vp8x(0x02, 2, 2)), (&webp::ANIM, &[0; 6]), (&webp::ANMF, &frame), (&webp::ANMF, &frame)]);
+
+    let webp = webp::parse(&data).expect("Parse");
+    assert!(webp.animated);
+    assert_eq!(webp.size, data.len());
+}
+
+#[test]
+fn should_exclude_trailing_data() {
+    let mut data = riff::form(b"WEBP", &[(&webp::VP8, &vp8(1, 1))]);
+    let size = data.len();
+    data.extend_from_slice(b"trailing");
+
+    assert_eq!(webp::parse(&data).expect("Parse").size, size);
+}
+
+#[test]
+fn should_reject_invalid_stream() {
+    assert_eq!(webp::parse(b"RIFF\x04\x00\x00\x00WAVE"), Err(Error::Invalid("WebP signature")));
+    assert_eq!(webp::parse(b"RIFF\x02\x00\x00\x00WEBP"), Err(Error::Invalid("RIFF size")));
+    assert_eq!(webp::parse(b"RIFF\x10\x00\x00\x00WEBP"), Err(Error::Truncated));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(b"EXIF", b"Exif")])), Err(Error::Invalid("WebP chunk")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8X, &vp8x(0, 1, 1))])), Err(Error::Invalid("WebP without image")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8X, &vp8x(0, 1, 1)), (&webp::ANMF, &[0; 16])])), Err(Error::Invalid("WebP without image")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8X, &vp8x(0x02, 1, 1)), (&webp::VP8, &vp8(1, 1))])), Err(Error::Invalid("WebP chunk")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8, &vp8(0, 1))])), Err(Error::Invalid("VP8 dimensions")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8, &[1; 10])])), Err(Error::Invalid("VP8 key frame")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8, &[0; 10])])), Err(Error::Invalid("VP8 start code")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8L, &[0; 5])])), Err(Error::Invalid("VP8L signature")));
+    assert_eq!(webp::parse(&riff::form(b"WEBP", &[(&webp::VP8L, &[0x2F, 0, 0, 0, 0x20])])), Err(Error::Unsupported("VP8L version")));
+}
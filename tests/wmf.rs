@@ -0,0 +1,184 @@
+use clipboard_win::codec::{Error, Point, Rect};
+use clipboard_win::codec::wmf::{self, Picture, Placeable, META_EOF, META_SETWINDOWORG, META_SETWINDOWEXT};
+
+fn record(function: u16, params: &[i16]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(3 + params.len() as u32).to_le_bytes());
+    out.extend_from_slice(&function.to_le_bytes());
+    for param in params {
+        out.extend_from_slice(&param.to_le_bytes());
+    }
+    out
+}
+
+//Writes metafile with header, followed by `records` and META_EOF.
+fn metafile(records: &[Vec<u8>]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for record in records {
+        body.extend_from_slice(record);
+    }
+    body.extend_from_slice(&record(META_EOF, &[]));
+    let max_record = records.iter().map(|record| record.len()).max().unwrap_or(0).max(6) / 2;
+
+    let mut out = Vec::new();
+    for value in &[1u16, 9, 0x300] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&(9 + body.len() as u32 / 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&(max_record as u32).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&body);
+    out
+}
+
+fn window_metafile() -> Vec<u8> {
+    metafile(&[
+        record(META_SETWINDOWORG, &[-10, 20]),
+        record(META_SETWINDOWEXT, &[500, 1000]),
+        record(0x0418, &[0, 0, 500, 1000]),
+    ])
+}
+
+#[test]
+fn should_parse_metafile() {
+    let data = window_metafile();
+
+    let parsed = wmf::parse(&data).expect("Parse");
+    assert!(parsed.placeable.is_none());
+    assert_eq!(parsed.header.kind, 1);
+    assert_eq!(parsed.header.version, 0x300);
+    assert_eq!(parsed.header.size, data.len());
+    assert_eq!(parsed.header.objects, 2);
+    assert_eq!(parsed.header.max_record, 14);
+    assert_eq!(parsed.as_bytes(), &data[..]);
+
+    let functions: Vec<u16> = parsed.records().map(|record| record.function).collect();
+    assert_eq!(functions, [META_SETWINDOWORG, META_SETWINDOWEXT, 0x0418, META_EOF]);
+    assert_eq!(parsed.records().nth(1).expect("Record").data, &[0xF4, 1, 0xE8, 3]);
+    assert_eq!(parsed.window(), Some(Rect { left: 20, top: -10, right: 1020, bottom: 490 }));
+
+    assert_eq!(wmf::parse(&metafile(&[])).expect("Parse").window(), None);
+}
+
+#[test]
+fn should_parse_placeable() {
+    let data = window_metafile();
+    let mut file = Vec::new();
+    let placeable = Placeable {
+        bounds: Rect { left: 20, top: -10, right: 1020, bottom: 490 },
+        inch: 1000,
+    };
+    placeable.write(&mut file).expect("Write placeable");
+    assert_eq!(file.len(), wmf::PLACEABLE_SIZE);
+    assert_eq!(&file[..4], &[0xD7, 0xCD, 0xC6, 0x9A]);
+    let checksum = file[..20].chunks(2).fold(0, |checksum, word| checksum ^ u16::from_le_bytes([word[0], word[1]]));
+    assert_eq!(&file[20..], &checksum.to_le_bytes());
+
+    file.extend_from_slice(&data);
+    file.extend_from_slice(&[0; 7]);
+    let parsed = wmf::parse(&file).expect("Parse");
+    assert_eq!(parsed.placeable, Some(placeable));
+    assert_eq!(parsed.as_bytes(), &data[..]);
+
+    let picture = Picture::from_placeable(&file).expect("Import");
+    assert_eq!(picture.mapping_mode, wmf::MM_ANISOTROPIC);
+    assert_eq!(picture.extent, Point { x: 2540, y: 1270 });
+    assert_eq!(picture.metafile, data);
+
+    assert_eq!(Picture::from_placeable(&data).unwrap_err(), Error::Invalid("missing placeable header"));
+}
+
+#[test]
+fn should_export_placeable() {
+    let data = window_metafile();
+
+    let picture = Picture {
+        mapping_mode: wmf::MM_ANISOTROPIC,
+        extent: Point { x: 2540, y: 1270 },
+        metafile: data.clone(),
+    };
+    let mut file = Vec::new();
+    assert_eq!(picture.write_placeable(&mut file).expect("Export"), wmf::PLACEABLE_SIZE + data.len());
+    let parsed = wmf::parse(&file).expect("Parse");
+    assert_eq!(parsed.placeable, Some(Placeable {
+        bounds: Rect { left: 20, top: -10, right: 1020, bottom: 490 },
+        inch: 1000,
+    }));
+    assert_eq!(Picture::from_placeable(&file).expect("Import"), picture);
+
+    let picture = Picture {
+        extent: Point::default(),
+        ..picture
+    };
+    assert_eq!(picture.placeable().expect("Placeable").inch, 1440);
+
+    let picture = Picture {
+        mapping_mode: wmf::MM_HIMETRIC,
+        extent: Point { x: 5080, y: 2540 },
+        metafile: metafile(&[]),
+    };
+    assert_eq!(picture.placeable().expect("Placeable"), Placeable {
+        bounds: Rect { left: 0, top: 0, right: 5080, bottom: 2540 },
+        inch: 2540,
+    });
+
+    let picture = Picture {
+        mapping_mode: wmf::MM_TWIPS,
+        extent: Point { x: 40000, y: 100 },
+        metafile: metafile(&[]),
+    };
+    assert_eq!(picture.write_placeable(&mut Vec::new()).unwrap_err(), Error::Invalid("placeable header"));
+
+    let picture = Picture {
+        mapping_mode: wmf::MM_ISOTROPIC,
+        extent: Point { x: 100, y: 100 },
+        metafile: metafile(&[]),
+    };
+    assert_eq!(picture.placeable().unwrap_err(), Error::Invalid("metafile without window extent"));
+
+    let picture = Picture {
+        mapping_mode: 0,
+        ..picture
+    };
+    assert_eq!(picture.placeable().unwrap_err(), Error::Unsupported("mapping mode"));
+}
+
+#[test]
+fn should_reject_invalid() {
+    let data = window_metafile();
+
+    assert_eq!(wmf::parse(&data[..10]).unwrap_err(), Error::Truncated);
+    assert_eq!(wmf::parse(&data[..data.len() - 2]).unwrap_err(), Error::Truncated);
+
+    let mut invalid = data.clone();
+    invalid[0] = 3;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("metafile type"));
+
+    let mut invalid = data.clone();
+    invalid[2] = 8;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("metafile header size"));
+
+    let mut invalid = data.clone();
+    invalid[5] = 2;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Unsupported("metafile version"));
+
+    let mut invalid = data.clone();
+    invalid[12] = 4;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("record exceeds maximum size"));
+
+    let mut invalid = data.clone();
+    invalid[18] = 2;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("record size"));
+
+    //Record after end of file
+    let mut invalid = data.clone();
+    invalid.extend_from_slice(&record(META_SETWINDOWORG, &[0, 0]));
+    invalid[6] += 5;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("record after META_EOF"));
+
+    //Missing end of file
+    let mut invalid = data[..data.len() - 6].to_vec();
+    invalid[6] -= 3;
+    assert_eq!(wmf::parse(&invalid).unwrap_err(), Error::Invalid("missing META_EOF"));
+}